@@ -3,6 +3,7 @@ use wasm_bindgen::prelude::*;
 use crate::{
     gear::Gear,
     gear_array::GearArray,
+    plane::PlaneMut,
     types::{AirSquadronMode, GearAttr, GearType, SlotSizeVec},
 };
 
@@ -21,6 +22,25 @@ pub struct AirSquadron {
     pub max_slots: SlotSizeVec,
 }
 
+impl AirSquadron {
+    pub fn planes_mut(&mut self) -> impl Iterator<Item = PlaneMut<'_>> {
+        self.gears
+            .0
+            .iter_mut()
+            .zip(self.slots.iter_mut())
+            .enumerate()
+            .filter_map(|(index, (gear, slot_size))| {
+                let gear = gear.as_mut()?;
+
+                gear.has_proficiency().then_some(PlaneMut {
+                    index,
+                    gear,
+                    slot_size,
+                })
+            })
+    }
+}
+
 #[wasm_bindgen]
 impl AirSquadron {
     pub fn get_gear(&self, key: &str) -> Option<Gear> {
@@ -1,35 +1,51 @@
 mod action_report;
+mod air_state_analyzer;
 mod anti_air_analyzer;
 mod attack_analyzer;
 mod attack_analyzer_config;
 mod attack_report;
+mod battle_record_validator;
 mod comp_analyzer;
 mod contact_analyzer;
 mod damage_report;
 mod day_cutin_analyzer;
 mod fleet_cutin_analyzer;
+mod historical_bonus_analyzer;
+mod improvement_analyzer;
+mod modifier_solver;
+mod night_conditions_analyzer;
 mod night_cutin_analyzer;
 mod node_attack_analyzer;
 mod ship_analyzer;
+mod transport_point_planner;
 
 use wasm_bindgen::prelude::*;
 
 pub use action_report::*;
+pub use air_state_analyzer::*;
 pub use attack_analyzer::*;
 pub use attack_analyzer_config::*;
 pub use attack_report::*;
+pub use battle_record_validator::*;
 pub use comp_analyzer::*;
 pub use damage_report::*;
 pub use fleet_cutin_analyzer::*;
+pub use historical_bonus_analyzer::*;
+pub use improvement_analyzer::*;
+pub use modifier_solver::*;
+pub use night_conditions_analyzer::*;
 pub use node_attack_analyzer::*;
 pub use ship_analyzer::*;
+pub use transport_point_planner::*;
 
 use crate::{
     battle::BattleComp,
     comp::Comp,
+    import::BattleRecord,
+    org::Org,
     ship::Ship,
     simulator::{SimulatorResult, SupportShellingSimulator},
-    types::BattleDefinitions,
+    types::{BattleDefinitions, NodeState},
 };
 
 #[wasm_bindgen]
@@ -65,6 +81,8 @@ impl Analyzer {
             config,
             attacker,
             target,
+            attacker_comp: None,
+            target_comp: None,
         };
 
         analyzer.analyze()
@@ -90,10 +108,86 @@ impl Analyzer {
         analyzer.analyze()
     }
 
+    pub fn validate_battle_record(
+        &self,
+        config: NodeAttackAnalyzerConfig,
+        player: &Comp,
+        enemy: &Comp,
+        battle_json: &str,
+    ) -> Result<BattleRecordValidation, JsValue> {
+        let record = BattleRecord::from_api_json(battle_json, player.org_type)
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        let validator = BattleRecordValidator {
+            battle_defs: &self.battle_defs,
+            config: &config,
+            player,
+            enemy,
+        };
+
+        Ok(validator.validate(&record))
+    }
+
+    pub fn solve_damage_modifier(&self, config: ModifierSolverConfig) -> ModifierSolution {
+        ModifierSolver { config: &config }.solve()
+    }
+
     pub fn analyze_comp(&self, comp: &Comp, config: CompAnalyzerConfig) -> CompAnalysis {
         CompAnalyzer::new(&self.battle_defs, comp, config).analyze()
     }
 
+    /// 夜間触接・照明弾・探照灯の発動を考慮した夜戦CI率
+    pub fn analyze_night_conditions(
+        &self,
+        comp: &Comp,
+        config: CompAnalyzerConfig,
+    ) -> NightConditionsAnalysis {
+        NightConditionsAnalyzer {
+            battle_defs: &self.battle_defs,
+            comp,
+            dummy: &Default::default(),
+            config: &config,
+        }
+        .analyze()
+    }
+
+    pub fn analyze_historical_bonuses(
+        &self,
+        comp: &Comp,
+        node_state: NodeState,
+    ) -> HistoricalBonusAnalysis {
+        HistoricalBonusAnalyzer {
+            battle_defs: &self.battle_defs,
+            comp,
+            node_state,
+        }
+        .analyze()
+    }
+
+    pub fn analyze_air_state(
+        &self,
+        player_org: &Org,
+        enemy_comp: &Comp,
+        config: AirStateAnalyzerConfig,
+    ) -> Result<AirStateAnalysis, JsValue> {
+        use rand::prelude::*;
+
+        let mut rng = SmallRng::from_entropy();
+        let player_comp = player_org.create_comp();
+
+        let analyzer = AirStateAnalyzer {
+            battle_defs: &self.battle_defs,
+            config: &config,
+            player_org,
+            player_comp: &player_comp,
+            enemy_comp,
+        };
+
+        analyzer
+            .analyze(&mut rng)
+            .map_err(|err| JsValue::from(&err.to_string()))
+    }
+
     pub fn simulate_support_shelling(
         &self,
         player_comp: &Comp,
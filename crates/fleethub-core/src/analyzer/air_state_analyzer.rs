@@ -0,0 +1,266 @@
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use tsify::Tsify;
+
+use crate::{
+    air_squadron::AirSquadron,
+    comp::Comp,
+    error::CalculationError,
+    org::Org,
+    plane::{PlaneImpl, PlaneVec},
+    types::{
+        AirSquadronKey, AirState, AirWaveType, BattleDefinitions, FleetType, Formation, Role, Side,
+    },
+    utils::Histogram,
+};
+
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(default)]
+pub struct AirStateAnalyzerConfig {
+    /// 敵艦隊へ向かう基地航空隊の波を発進順に並べたもの
+    pub waves: Vec<AirSquadronKey>,
+    pub enemy_formation: Option<Formation>,
+    pub times: usize,
+}
+
+impl Default for AirStateAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            waves: vec![],
+            enemy_formation: None,
+            times: 10000,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Tsify)]
+pub struct AirStateChance {
+    pub air_state: AirState,
+    pub rate: f64,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+pub struct LandBaseWaveAirStateAnalysis {
+    pub key: AirSquadronKey,
+    pub air_state_chance: Vec<AirStateChance>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+pub struct EnemySlotsReport {
+    pub ship_id: u16,
+    pub role: Role,
+    pub index: usize,
+    pub max_slots: Vec<Option<u8>>,
+    /// 航空戦開始時の期待搭載数
+    pub expected_slots: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct AirStateAnalysis {
+    pub player_fighter_power: i32,
+    pub enemy_fighter_power: i32,
+    /// 基地航空隊による損耗を考慮しない制空状態
+    pub paper_air_state: AirState,
+    /// 航空戦開始時の期待敵制空値
+    pub expected_enemy_fighter_power: f64,
+    pub air_state_chance: Vec<AirStateChance>,
+    pub land_base_waves: Vec<LandBaseWaveAirStateAnalysis>,
+    pub enemy_slots: Vec<EnemySlotsReport>,
+}
+
+pub struct AirStateAnalyzer<'a> {
+    pub battle_defs: &'a BattleDefinitions,
+    pub config: &'a AirStateAnalyzerConfig,
+    pub player_org: &'a Org,
+    pub player_comp: &'a Comp,
+    pub enemy_comp: &'a Comp,
+}
+
+#[derive(Default)]
+struct AirStateLogger {
+    air_state: Histogram<AirState, usize>,
+    waves: Vec<Histogram<AirState, usize>>,
+    enemy_fighter_power: i64,
+    enemy_slots: Vec<Vec<u64>>,
+}
+
+impl AirStateLogger {
+    fn rates(hist: &Histogram<AirState, usize>, times: usize) -> Vec<AirStateChance> {
+        AirState::iter()
+            .map(|air_state| {
+                let count = hist.get(&air_state).cloned().unwrap_or_default();
+
+                AirStateChance {
+                    air_state,
+                    rate: count as f64 / times as f64,
+                }
+            })
+            .collect()
+    }
+}
+
+impl AirStateAnalyzer<'_> {
+    pub fn analyze<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<AirStateAnalysis, CalculationError> {
+        let times = self.config.times.max(1);
+
+        let player_fighter_power = self
+            .player_comp
+            .planes(true)
+            .collect::<PlaneVec<_>>()
+            .fighter_power(AirWaveType::Carrier)?;
+
+        let enemy_fighter_power = self
+            .enemy_comp
+            .planes(true)
+            .collect::<PlaneVec<_>>()
+            .fighter_power(AirWaveType::Carrier)?;
+
+        let mut logger = AirStateLogger {
+            waves: vec![Histogram::new(); self.config.waves.len()],
+            ..Default::default()
+        };
+
+        for _ in 0..times {
+            let mut enemy_comp = self.enemy_comp.clone();
+            let mut air_squadrons = [AirSquadronKey::A1, AirSquadronKey::A2, AirSquadronKey::A3]
+                .map(|key| self.player_org.get_air_squadron_by_key(key).clone());
+
+            for (wave, &key) in self.config.waves.iter().enumerate() {
+                let air_squadron = &mut air_squadrons[key as usize];
+                let air_state = self.try_land_base_wave(rng, air_squadron, &mut enemy_comp)?;
+                logger.waves[wave] += (air_state, 1);
+            }
+
+            let current_enemy_fighter_power = enemy_comp
+                .planes(true)
+                .collect::<PlaneVec<_>>()
+                .fighter_power(AirWaveType::Carrier)?;
+
+            let air_state = AirState::new(player_fighter_power, current_enemy_fighter_power);
+
+            logger.air_state += (air_state, 1);
+            logger.enemy_fighter_power += current_enemy_fighter_power as i64;
+
+            enemy_comp
+                .members_by(FleetType::Main | FleetType::Escort)
+                .enumerate()
+                .for_each(|(i, member)| {
+                    if logger.enemy_slots.len() <= i {
+                        logger.enemy_slots.push(vec![]);
+                    }
+
+                    let totals = &mut logger.enemy_slots[i];
+                    totals.resize(totals.len().max(member.slots.len()), 0);
+
+                    member
+                        .slots
+                        .iter()
+                        .zip(totals.iter_mut())
+                        .for_each(|(slot_size, total)| {
+                            *total += slot_size.unwrap_or_default() as u64;
+                        });
+                });
+        }
+
+        let enemy_slots = self
+            .enemy_comp
+            .members_by(FleetType::Main | FleetType::Escort)
+            .zip(logger.enemy_slots.iter())
+            .map(|(member, totals)| EnemySlotsReport {
+                ship_id: member.ship_id,
+                role: member.position.fleet_type.into(),
+                index: member.position.index,
+                max_slots: member.slots.to_vec(),
+                expected_slots: totals
+                    .iter()
+                    .map(|total| *total as f64 / times as f64)
+                    .collect(),
+            })
+            .collect();
+
+        let land_base_waves = self
+            .config
+            .waves
+            .iter()
+            .zip(logger.waves.iter())
+            .map(|(&key, hist)| LandBaseWaveAirStateAnalysis {
+                key,
+                air_state_chance: AirStateLogger::rates(hist, times),
+            })
+            .collect();
+
+        Ok(AirStateAnalysis {
+            player_fighter_power,
+            enemy_fighter_power,
+            paper_air_state: AirState::new(player_fighter_power, enemy_fighter_power),
+            expected_enemy_fighter_power: logger.enemy_fighter_power as f64 / times as f64,
+            air_state_chance: AirStateLogger::rates(&logger.air_state, times),
+            land_base_waves,
+            enemy_slots,
+        })
+    }
+
+    /// 基地航空隊1波分の航空戦
+    ///
+    /// stage1で双方の艦載機が撃墜され、stage2で攻撃機が敵艦隊の対空砲火を受ける
+    fn try_land_base_wave<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        air_squadron: &mut AirSquadron,
+        enemy_comp: &mut Comp,
+    ) -> Result<AirState, CalculationError> {
+        let air_type = AirWaveType::LandBase;
+        let land_base_fighter_power = air_squadron.fighter_power();
+
+        let mut enemy_planes = enemy_comp.planes_mut(true).collect::<PlaneVec<_>>();
+        let enemy_fighter_power = enemy_planes.fighter_power(air_type)?;
+        let air_state = AirState::new(land_base_fighter_power, enemy_fighter_power);
+
+        air_squadron
+            .planes_mut()
+            .filter(|plane| plane.participates_in_fighter_combat(air_type))
+            .for_each(|mut plane| plane.suffer_in_fighter_combat(rng, air_state, Side::Player));
+        enemy_planes
+            .iter_mut()
+            .filter(|plane| plane.participates_in_fighter_combat(air_type))
+            .for_each(|plane| plane.suffer_in_fighter_combat(rng, air_state, Side::Enemy));
+
+        drop(enemy_planes);
+
+        let formation = self
+            .config
+            .enemy_formation
+            .unwrap_or_else(|| enemy_comp.default_formation());
+        let formation_mod = self.battle_defs.get_formation_fleet_anti_air_mod(formation);
+        let fleet_adjusted_anti_air = enemy_comp.adjusted_anti_air(formation_mod);
+        let anti_air_cutin = enemy_comp.choose_anti_air_cutin(rng, self.battle_defs);
+
+        let member_vec = enemy_comp
+            .members_by(FleetType::Main | FleetType::Escort)
+            .filter(|member| member.remains())
+            .collect::<Vec<_>>();
+
+        if member_vec.is_empty() {
+            return Ok(air_state);
+        }
+
+        air_squadron
+            .planes_mut()
+            .filter(|plane| plane.remains() && plane.is_attacker())
+            .try_for_each(|mut plane| {
+                let member = member_vec.choose(rng).expect("member_vec.len() > 0");
+
+                member
+                    .air_defense(fleet_adjusted_anti_air, anti_air_cutin)
+                    .try_intercept(rng, &mut plane)
+            })?;
+
+        Ok(air_state)
+    }
+}
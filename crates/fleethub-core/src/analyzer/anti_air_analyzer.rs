@@ -119,7 +119,7 @@ impl AntiAirAnalyzer<'_> {
 
     fn calc_ship_anti_air_cutin_chance(&self, ship: &Ship) -> Vec<(u8, f64)> {
         let (sequential_cis, normal_cis) = ship
-            .get_possible_anti_air_cutin_ids()
+            .get_possible_anti_air_cutin_ids(self.battle_defs)
             .into_iter()
             .filter_map(|id| self.get_anti_air_cutin_def(id))
             .partition::<Vec<_>, _>(|aaci| aaci.is_sequential());
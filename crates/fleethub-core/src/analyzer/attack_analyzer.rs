@@ -6,6 +6,7 @@ use crate::{
         AswAttackParams, DayPhaseAttackParams, NightPhaseAttackParams, SupportShellingAttackParams,
         TorpedoAttackParams,
     },
+    comp::Comp,
     member::BattleMemberRef,
     ship::{NightCutinTermParams, Ship},
     types::{
@@ -24,6 +25,10 @@ pub struct AttackAnalyzer<'a> {
     pub config: AttackAnalyzerConfig,
     pub attacker: &'a Ship,
     pub target: &'a Ship,
+    /// 攻撃艦が所属する艦隊
+    pub attacker_comp: Option<&'a Comp>,
+    /// 防御艦が所属する艦隊
+    pub target_comp: Option<&'a Comp>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
@@ -98,6 +103,8 @@ impl AttackAnalyzer<'_> {
             self.config.node_state,
             &self.attacker_combat_ship(),
             &self.target_combat_ship(),
+            self.attacker_comp,
+            self.target_comp,
         )
     }
 
@@ -327,7 +334,7 @@ impl AttackAnalyzer<'_> {
         ActionReport::one(style, params)
     }
 
-    fn analyze_opening_asw(&self) -> ActionReport<AswAttackStyle> {
+    pub fn analyze_opening_asw(&self) -> ActionReport<AswAttackStyle> {
         let attacker = &self.attacker_combat_ship();
         let target = &self.target_combat_ship();
 
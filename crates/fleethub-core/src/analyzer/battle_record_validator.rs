@@ -0,0 +1,212 @@
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::{
+    comp::Comp,
+    import::{BattleAttackRecord, BattleHitRecord, BattleHitType, BattleRecord, BattleRecordPhase},
+    ship::Ship,
+    types::{BattleDefinitions, DayPhaseAttackStyle, NightCutinLike, NightPhaseAttackStyle, Side},
+};
+
+use super::{
+    AttackAnalyzer, AttackAnalyzerConfig, DamageReport, NodeAttackAnalyzerConfig,
+    NodeAttackAnalyzerShipConfig,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub enum HitValidationStatus {
+    /// 予測範囲内
+    Valid,
+    /// 予測範囲外
+    Impossible,
+    /// 旗艦庇護や特殊攻撃など、検証の対象外
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct HitValidation {
+    pub attack_index: usize,
+    pub hit_index: usize,
+    pub phase: BattleRecordPhase,
+    pub attacker_side: Side,
+    pub attacker_index: usize,
+    pub target_index: usize,
+    pub hit_type: BattleHitType,
+    pub damage: u16,
+    pub min_damage: Option<u16>,
+    pub max_damage: Option<u16>,
+    pub status: HitValidationStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct BattleRecordValidation {
+    pub hits: Vec<HitValidation>,
+    pub valid_count: usize,
+    pub impossible_count: usize,
+    pub skipped_count: usize,
+}
+
+/// 記録された戦闘の各攻撃を、同じ条件で計算したダメージ範囲と比較する
+///
+/// config の left が自軍、right が敵軍となる
+pub struct BattleRecordValidator<'a> {
+    pub battle_defs: &'a BattleDefinitions,
+    pub config: &'a NodeAttackAnalyzerConfig,
+    pub player: &'a Comp,
+    pub enemy: &'a Comp,
+}
+
+fn get_ship(comp: &Comp, index: usize) -> Option<&Ship> {
+    match &comp.escort {
+        Some(escort) if index >= 6 => escort.ships.get(index - 6),
+        _ => comp.main.ships.get(index),
+    }
+}
+
+fn damage_range(damage: &DamageReport, hit_type: BattleHitType) -> (u16, u16) {
+    match hit_type {
+        BattleHitType::Miss => (damage.miss_damage_min, damage.miss_damage_max),
+        BattleHitType::Normal => (damage.normal_damage_min, damage.normal_damage_max),
+        BattleHitType::Critical => (damage.critical_damage_min, damage.critical_damage_max),
+    }
+}
+
+impl BattleRecordValidator<'_> {
+    pub fn validate(&self, record: &BattleRecord) -> BattleRecordValidation {
+        let hits = record
+            .attacks
+            .iter()
+            .enumerate()
+            .flat_map(|(attack_index, attack)| {
+                attack.hits.iter().enumerate().map(move |(hit_index, hit)| {
+                    let range = self.predict(attack, hit);
+
+                    let status = match range {
+                        Some((min, max)) if (min..=max).contains(&hit.damage) => {
+                            HitValidationStatus::Valid
+                        }
+                        Some(_) => HitValidationStatus::Impossible,
+                        None => HitValidationStatus::Skipped,
+                    };
+
+                    HitValidation {
+                        attack_index,
+                        hit_index,
+                        phase: attack.phase,
+                        attacker_side: attack.attacker_side,
+                        attacker_index: attack.attacker_index,
+                        target_index: hit.target_index,
+                        hit_type: hit.hit_type,
+                        damage: hit.damage,
+                        min_damage: range.map(|(min, _)| min),
+                        max_damage: range.map(|(_, max)| max),
+                        status,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let count = |status| hits.iter().filter(|hit| hit.status == status).count();
+
+        BattleRecordValidation {
+            valid_count: count(HitValidationStatus::Valid),
+            impossible_count: count(HitValidationStatus::Impossible),
+            skipped_count: count(HitValidationStatus::Skipped),
+            hits,
+        }
+    }
+
+    fn get_comp_and_config(&self, side: Side) -> (&Comp, &NodeAttackAnalyzerShipConfig) {
+        match side {
+            Side::Player => (self.player, &self.config.left),
+            Side::Enemy => (self.enemy, &self.config.right),
+        }
+    }
+
+    /// 攻撃の種別に合致する攻撃方法のダメージ範囲の和集合
+    ///
+    /// カットインは記録された種別と同じものに限る
+    fn predict(&self, attack: &BattleAttackRecord, hit: &BattleHitRecord) -> Option<(u16, u16)> {
+        if hit.protected || attack.is_special() {
+            return None;
+        }
+
+        let (attacker_comp, attacker_config) = self.get_comp_and_config(attack.attacker_side);
+        let (target_comp, target_config) = self.get_comp_and_config(attack.target_side());
+
+        let attacker = get_ship(attacker_comp, attack.attacker_index)?;
+        let target = get_ship(target_comp, hit.target_index)?;
+
+        let config = AttackAnalyzerConfig {
+            air_state: self.config.air_state,
+            engagement: self.config.engagement,
+            node_state: self.config.node_state,
+            attacker: attacker_config.attack_analyzer_ship_config(attacker_comp, attacker),
+            target: target_config.attack_analyzer_ship_config(target_comp, target),
+        };
+
+        let mut target = target.clone();
+        target.current_hp = hit.hp_before;
+
+        let analyzer = AttackAnalyzer {
+            battle_defs: self.battle_defs,
+            config,
+            attacker,
+            target: &target,
+            attacker_comp: Some(attacker_comp),
+            target_comp: Some(target_comp),
+        };
+
+        let damages = match attack.phase {
+            BattleRecordPhase::OpeningAsw => analyzer
+                .analyze_opening_asw()
+                .data
+                .into_values()
+                .filter_map(|report| report.damage)
+                .collect::<Vec<_>>(),
+            BattleRecordPhase::DayShelling => {
+                let cutins = attack.day_cutins()?;
+
+                analyzer
+                    .analyze_day_phase_action()
+                    .data
+                    .into_values()
+                    .filter(|report| match &report.style {
+                        DayPhaseAttackStyle::Shelling(style) => match &style.cutin {
+                            Some(cutin) => cutin
+                                .as_day_cutin()
+                                .is_some_and(|cutin| cutins.contains(cutin)),
+                            None => cutins.is_empty(),
+                        },
+                        DayPhaseAttackStyle::Asw(_) => cutins.is_empty(),
+                    })
+                    .filter_map(|report| report.damage)
+                    .collect()
+            }
+            BattleRecordPhase::NightShelling => {
+                let cutins = attack.night_cutins()?;
+
+                analyzer
+                    .analyze_night_phase_action()
+                    .data
+                    .into_values()
+                    .filter(|report| match &report.style {
+                        NightPhaseAttackStyle::Night(style) => match &style.cutin {
+                            Some(NightCutinLike::NightCutin(cutin)) => cutins.contains(*cutin),
+                            Some(NightCutinLike::FleetCutin(_)) => false,
+                            None => cutins.is_empty(),
+                        },
+                        NightPhaseAttackStyle::Asw(_) => cutins.is_empty(),
+                    })
+                    .filter_map(|report| report.damage)
+                    .collect()
+            }
+        };
+
+        damages
+            .iter()
+            .map(|damage| damage_range(damage, hit.hit_type))
+            .reduce(|(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)))
+    }
+}
@@ -40,6 +40,15 @@ pub struct CompNightAnalysis {
     pub fleet_cutin: Vec<FleetCutinReport<NightAttackStyle>>,
 }
 
+/// 照明弾1本あたりの発動率の既定値
+///
+/// 検証報告による概算値のため、設定で上書きできる
+pub const DEFAULT_STARSHELL_ACTIVATION_RATE: f64 = 0.7;
+
+fn default_starshell_activation_rate() -> f64 {
+    DEFAULT_STARSHELL_ACTIVATION_RATE
+}
+
 #[derive(Deserialize, Tsify)]
 #[tsify(from_wasm_abi)]
 pub struct CompAnalyzerConfig {
@@ -50,6 +59,9 @@ pub struct CompAnalyzerConfig {
     pub fleet_anti_air_resist: f64,
     pub left_night_fleet_conditions: NightFleetConditions,
     pub right_night_fleet_conditions: NightFleetConditions,
+    /// 照明弾1本あたりの発動率
+    #[serde(default = "default_starshell_activation_rate")]
+    pub starshell_activation_rate: f64,
 }
 impl Default for CompAnalyzerConfig {
     fn default() -> Self {
@@ -61,6 +73,7 @@ impl Default for CompAnalyzerConfig {
             fleet_anti_air_resist: 1.0,
             left_night_fleet_conditions: Default::default(),
             right_night_fleet_conditions: Default::default(),
+            starshell_activation_rate: DEFAULT_STARSHELL_ACTIVATION_RATE,
         }
     }
 }
@@ -20,6 +20,8 @@ pub struct DamageReport {
     pub critical_scratch_rate: f64,
     pub damage_density: Histogram<u16, f64>,
     pub damage_state_density: Histogram<DamageState, f64>,
+    /// 防御側のステータスに推定値を用いた概算
+    pub rough: bool,
 }
 
 impl DamageReport {
@@ -67,7 +69,10 @@ impl DamageReport {
             .unwrap_or_default();
 
         let &DefenseParams {
-            current_hp, max_hp, ..
+            current_hp,
+            max_hp,
+            rough,
+            ..
         } = defense_params;
 
         let damage_state_density = damage_density
@@ -91,6 +96,7 @@ impl DamageReport {
             critical_scratch_rate,
             damage_density,
             damage_state_density,
+            rough,
         })
     }
 }
@@ -183,6 +189,7 @@ mod test {
                 max_hp: 1000,
                 sinkable: true,
                 overkill_protection: false,
+                rough: false,
             }),
             hit_rate: Some(HitRate {
                 normal: 0.2,
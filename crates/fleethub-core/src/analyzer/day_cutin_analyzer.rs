@@ -76,6 +76,8 @@ impl DayCutinAnalyzer<'_> {
             },
             attacker: ship,
             target: self.dummy,
+            attacker_comp: Some(self.comp),
+            target_comp: None,
         };
 
         let observation_term = attack_analyzer.calc_observation_term();
@@ -81,7 +81,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
         let engagement = self.engagement;
 
         let cutin = effect.cutin;
-        let rate = calc_fleet_cutin_rate(fleet, cutin);
+        let rate = calc_fleet_cutin_rate(self.battle_defs, fleet, &cutin);
 
         let attacks = effect
             .attacks
@@ -94,7 +94,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
                 let target = &self.target();
                 let style = ShellingStyle {
                     attack_type: ShellingType::Normal,
-                    cutin: Some(DayCutinLike::FleetCutin(cutin)),
+                    cutin: Some(DayCutinLike::FleetCutin(cutin.clone())),
                     power_mod,
                     accuracy_mod: 1.0,
                     hits: 1.0,
@@ -114,6 +114,8 @@ impl<'a> FleetCutinAnalyzer<'a> {
                         self.node_state,
                         &attacker,
                         target,
+                        Some(self.comp),
+                        None,
                     ),
                 }
                 .calc_attack_params();
@@ -145,7 +147,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
         let fleet_type = self.comp.night_fleet_type();
         let fleet = self.comp.night_fleet();
         let cutin = effect.cutin;
-        let rate = calc_fleet_cutin_rate(fleet, cutin);
+        let rate = calc_fleet_cutin_rate(self.battle_defs, fleet, &cutin);
 
         let attacks = effect
             .attacks
@@ -161,7 +163,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
 
                 let style = NightAttackStyle {
                     attack_type,
-                    cutin: Some(cutin.into()),
+                    cutin: Some(cutin.clone().into()),
                     power_mod,
                     ..Default::default()
                 };
@@ -179,6 +181,8 @@ impl<'a> FleetCutinAnalyzer<'a> {
                         self.node_state,
                         &attacker,
                         target,
+                        Some(self.comp),
+                        None,
                     ),
                     night_conditions,
                 }
@@ -207,6 +211,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
             .flat_map(|formation| {
                 let fleet = &self.comp.main;
                 let vec = get_possible_fleet_cutin_effect_vec(
+                    self.battle_defs,
                     fleet,
                     formation,
                     self.engagement,
@@ -227,6 +232,7 @@ impl<'a> FleetCutinAnalyzer<'a> {
             .flat_map(|formation| {
                 let fleet = self.comp.night_fleet();
                 let vec = get_possible_fleet_cutin_effect_vec(
+                    self.battle_defs,
                     fleet,
                     formation,
                     self.engagement,
@@ -245,7 +251,13 @@ impl<'a> FleetCutinAnalyzer<'a> {
         formation: Formation,
     ) -> Vec<FleetCutinReport<ShellingStyle>> {
         let fleet = &self.comp.main;
-        let vec = get_possible_fleet_cutin_effect_vec(fleet, formation, self.engagement, Time::Day);
+        let vec = get_possible_fleet_cutin_effect_vec(
+            self.battle_defs,
+            fleet,
+            formation,
+            self.engagement,
+            Time::Day,
+        );
 
         vec.into_iter()
             .map(move |effect| self.analyze_shelling(formation, effect))
@@ -258,8 +270,13 @@ impl<'a> FleetCutinAnalyzer<'a> {
         night_conditions: &NightConditions,
     ) -> Vec<FleetCutinReport<NightAttackStyle>> {
         let fleet = self.comp.night_fleet();
-        let vec =
-            get_possible_fleet_cutin_effect_vec(fleet, formation, self.engagement, Time::Night);
+        let vec = get_possible_fleet_cutin_effect_vec(
+            self.battle_defs,
+            fleet,
+            formation,
+            self.engagement,
+            Time::Night,
+        );
 
         vec.into_iter()
             .map(move |effect| self.analyze_night_attack(formation, effect, night_conditions))
@@ -0,0 +1,197 @@
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::{
+    comp::Comp,
+    member::BattleMemberRef,
+    types::{BattleDefinitions, FleetType, HistoricalBonusDef, NodeState},
+};
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct HistoricalBonusMatch {
+    /// `historical_bonuses` 内の位置
+    pub index: usize,
+    pub def: HistoricalBonusDef,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct ShipHistoricalBonusReport {
+    pub ship_id: u16,
+    pub fleet_type: FleetType,
+    pub index: usize,
+    /// 適用される補正の積
+    pub power_mod: f64,
+    pub accuracy_mod: f64,
+    pub evasion_mod: f64,
+    /// 適用される補正
+    pub matched: Vec<HistoricalBonusMatch>,
+    /// 艦の条件を満たし、敵艦の条件次第で適用される補正
+    pub enemy_conditional: Vec<HistoricalBonusMatch>,
+    /// 艦の条件のうち1項のみを満たさない補正
+    ///
+    /// 敵艦の条件を持つ補正も含む
+    pub missed: Vec<HistoricalBonusMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct HistoricalBonusAnalysis {
+    pub node_state: NodeState,
+    pub ships: Vec<ShipHistoricalBonusReport>,
+}
+
+pub struct HistoricalBonusAnalyzer<'a> {
+    pub battle_defs: &'a BattleDefinitions,
+    pub comp: &'a Comp,
+    pub node_state: NodeState,
+}
+
+impl HistoricalBonusAnalyzer<'_> {
+    fn analyze_ship(
+        &self,
+        defs: &[(usize, &HistoricalBonusDef)],
+        ship: &BattleMemberRef,
+    ) -> ShipHistoricalBonusReport {
+        let mut matched = vec![];
+        let mut enemy_conditional = vec![];
+        let mut missed = vec![];
+
+        for &(index, def) in defs {
+            let m = HistoricalBonusMatch {
+                index,
+                def: def.clone(),
+            };
+
+            if !def.matches_ship(ship, Some(self.comp)) {
+                if def.misses_one_ship_term(ship, Some(self.comp)) {
+                    missed.push(m);
+                }
+            } else if def.has_enemy_condition() {
+                enemy_conditional.push(m);
+            } else {
+                matched.push(m);
+            }
+        }
+
+        let product =
+            |f: fn(&HistoricalBonusDef) -> f64| matched.iter().map(|m| f(&m.def)).product::<f64>();
+
+        ShipHistoricalBonusReport {
+            ship_id: ship.ship_id,
+            fleet_type: ship.position.fleet_type,
+            index: ship.position.index,
+            power_mod: product(|def| def.power_mod),
+            accuracy_mod: product(|def| def.accuracy_mod),
+            evasion_mod: product(|def| def.evasion_mod),
+            matched,
+            enemy_conditional,
+            missed,
+        }
+    }
+
+    pub fn analyze(&self) -> HistoricalBonusAnalysis {
+        let node_state = self.node_state;
+
+        let defs = if node_state.map == 0 || node_state.disable_historical_mod {
+            vec![]
+        } else {
+            self.battle_defs
+                .historical_bonuses
+                .iter()
+                .enumerate()
+                .filter(|(_, def)| def.matches_node(node_state))
+                .collect::<Vec<_>>()
+        };
+
+        let ships = self
+            .comp
+            .members_by(FleetType::Main | FleetType::Escort)
+            .map(|member| {
+                let ship =
+                    BattleMemberRef::new(member.ship, member.position, Default::default(), None);
+
+                self.analyze_ship(&defs, &ship)
+            })
+            .collect();
+
+        HistoricalBonusAnalysis { node_state, ships }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fleet::Fleet,
+        ship::Ship,
+        types::{OrgType, ShipType},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_historical_bonus_analyzer() {
+        let ship = |ship_id: u16, ship_type: ShipType| {
+            let mut ship = Ship::default();
+            ship.ship_id = ship_id;
+            ship.ship_type = ship_type;
+            ship
+        };
+
+        let comp = Comp {
+            org_type: OrgType::Single,
+            hq_level: 120,
+            main: Fleet {
+                len: 6,
+                ships: [Some(ship(1, ShipType::DD)), Some(ship(2, ShipType::BB))]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            escort: None,
+            route_sup: None,
+            boss_sup: None,
+        };
+
+        let battle_defs = BattleDefinitions {
+            historical_bonuses: serde_json::from_value(serde_json::json!([
+                { "map": 581, "ship": "ship_id_in(1)", "power_mod": 1.2, "accuracy_mod": 1.1 },
+                { "map": 581, "ship": "ship_id_in(1)", "enemy": "ship_id == 1501", "power_mod": 1.5 },
+                { "map": 581, "node": "Z", "power_mod": 1.3 },
+                { "map": 582, "power_mod": 1.4 },
+                { "map": 581, "ship": "ship_id_in(2) && ship_type_in(99)", "power_mod": 1.1 },
+                { "map": 581, "ship": "ship_id_in(2) && has(100)", "enemy": "ship_id == 1501", "power_mod": 1.1 },
+            ]))
+            .unwrap(),
+            ..Default::default()
+        };
+
+        let analysis = HistoricalBonusAnalyzer {
+            battle_defs: &battle_defs,
+            comp: &comp,
+            node_state: NodeState {
+                map: 581,
+                node: "A".parse().unwrap(),
+                ..Default::default()
+            },
+        }
+        .analyze();
+
+        let indexes = |matches: &[HistoricalBonusMatch]| -> Vec<usize> {
+            matches.iter().map(|m| m.index).collect()
+        };
+
+        let first = &analysis.ships[0];
+        assert_eq!(indexes(&first.matched), [0]);
+        assert_eq!(indexes(&first.enemy_conditional), [1]);
+        assert!(first.missed.is_empty());
+        assert_eq!(first.power_mod, 1.2);
+        assert_eq!(first.accuracy_mod, 1.1);
+        assert_eq!(first.evasion_mod, 1.0);
+
+        let second = &analysis.ships[1];
+        assert!(second.matched.is_empty());
+        assert!(second.enemy_conditional.is_empty());
+        assert_eq!(indexes(&second.missed), [4, 5]);
+        assert_eq!(second.power_mod, 1.0);
+    }
+}
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::{
+    factory::Factory,
+    ship::Ship,
+    types::{AirWaveType, AttackStyleKey, BattleDefinitions, ShipType, Side},
+};
+
+use super::{
+    ActionReport, AttackAnalyzer, AttackAnalyzerConfig, AttackAnalyzerShipConfig, AttackReport,
+};
+
+/// 改修値の上限
+const MAX_STARS: u8 = 10;
+
+macro_rules! impl_improvement_metrics {
+    ($($key: ident,)*) => {
+        /// 改修値ごとに再計算する値
+        ///
+        /// 攻撃は通常攻撃の基本攻撃力と命中項
+        #[derive(Debug, Default, Clone, PartialEq, Serialize, Tsify)]
+        pub struct ImprovementMetrics {
+            $(pub $key: Option<f64>,)*
+        }
+
+        impl ImprovementMetrics {
+            fn delta(&self, prev: &Self) -> Self {
+                Self {
+                    $($key: self.$key.zip(prev.$key).map(|(a, b)| a - b),)*
+                }
+            }
+        }
+    };
+}
+
+impl_improvement_metrics!(
+    shelling_power,
+    shelling_accuracy,
+    night_power,
+    night_accuracy,
+    torpedo_power,
+    torpedo_accuracy,
+    asw_power,
+    asw_accuracy,
+    fighter_power,
+    adjusted_anti_air,
+    fleet_anti_air_mod,
+    elos,
+);
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct ImprovementLevelReport {
+    pub stars: u8,
+    pub metrics: ImprovementMetrics,
+    /// 改修値が 1 低い場合との差
+    pub delta: Option<ImprovementMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ImprovementAnalysis {
+    pub ship_id: u16,
+    pub gear_id: u16,
+    pub key: String,
+    pub improvable: bool,
+    pub current_stars: u8,
+    pub levels: Vec<ImprovementLevelReport>,
+}
+
+pub struct ImprovementAnalyzer<'a> {
+    pub battle_defs: &'a BattleDefinitions,
+    pub factory: &'a Factory,
+    pub ship: &'a Ship,
+    pub key: &'a str,
+}
+
+fn single_attack<T: AttackStyleKey>(report: &ActionReport<T>) -> Option<&AttackReport<T>> {
+    report.data.get("SingleAttack")
+}
+
+fn power<T: AttackStyleKey>(report: &ActionReport<T>) -> Option<f64> {
+    single_attack(report)?
+        .attack_power_params
+        .as_ref()
+        .map(|params| params.basic)
+}
+
+fn accuracy<T: AttackStyleKey>(report: &ActionReport<T>) -> Option<f64> {
+    single_attack(report)?
+        .hit_rate_params
+        .as_ref()
+        .map(|params| params.accuracy_term)
+}
+
+impl ImprovementAnalyzer<'_> {
+    fn create_ship(&self, stars: u8) -> Option<Ship> {
+        let mut state = self.ship.state();
+        state.gears.get_mut(self.key)?.stars = Some(stars);
+        self.factory.create_ship(Some(state))
+    }
+
+    fn metrics(&self, ship: &Ship, surface: &Ship, submarine: &Ship) -> ImprovementMetrics {
+        let analyze = |target| {
+            AttackAnalyzer {
+                battle_defs: self.battle_defs,
+                config: AttackAnalyzerConfig {
+                    air_state: Default::default(),
+                    engagement: Default::default(),
+                    node_state: Default::default(),
+                    attacker: Default::default(),
+                    target: AttackAnalyzerShipConfig::dummy_enemy(),
+                },
+                attacker: ship,
+                target,
+                attacker_comp: None,
+                target_comp: None,
+            }
+            .analyze()
+        };
+
+        let surface = analyze(surface);
+        let submarine = analyze(submarine);
+
+        ImprovementMetrics {
+            shelling_power: power(&surface.day),
+            shelling_accuracy: accuracy(&surface.day),
+            night_power: power(&surface.night),
+            night_accuracy: accuracy(&surface.night),
+            torpedo_power: power(&surface.closing_torpedo),
+            torpedo_accuracy: accuracy(&surface.closing_torpedo),
+            asw_power: power(&submarine.day),
+            asw_accuracy: accuracy(&submarine.day),
+            fighter_power: ship.fighter_power(AirWaveType::Carrier).map(f64::from),
+            adjusted_anti_air: ship.ship_adjusted_anti_air(Side::Player),
+            fleet_anti_air_mod: Some(ship.fleet_anti_air_mod()),
+            elos: ship.elos(1),
+        }
+    }
+
+    pub fn analyze(&self) -> Option<ImprovementAnalysis> {
+        let gear = self.ship.gears.get_by_gear_key(self.key)?;
+
+        let surface = Ship::default();
+        let mut submarine = Ship::default();
+        submarine.ship_type = ShipType::SS;
+
+        let mut levels = Vec::<ImprovementLevelReport>::new();
+
+        for stars in 0..=MAX_STARS {
+            let ship = self.create_ship(stars)?;
+            let metrics = self.metrics(&ship, &surface, &submarine);
+            let delta = levels.last().map(|prev| metrics.delta(&prev.metrics));
+
+            levels.push(ImprovementLevelReport {
+                stars,
+                metrics,
+                delta,
+            });
+        }
+
+        Some(ImprovementAnalysis {
+            ship_id: self.ship.ship_id,
+            gear_id: gear.gear_id,
+            key: self.key.to_string(),
+            improvable: gear.improvable,
+            current_stars: gear.stars,
+            levels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_improvement_metrics_delta() {
+        let prev = ImprovementMetrics {
+            shelling_power: Some(50.0),
+            elos: Some(10.0),
+            ..Default::default()
+        };
+
+        let current = ImprovementMetrics {
+            shelling_power: Some(51.5),
+            elos: None,
+            fighter_power: Some(20.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            current.delta(&prev),
+            ImprovementMetrics {
+                shelling_power: Some(1.5),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    attack::{max_scratch_damage, AttackPowerParams},
+    estimation::Estimation,
+    types::{AttackPowerModifier, DefensePower},
+};
+
+const BISECTION_ITERATIONS: usize = 64;
+
+/// 逆算する補正
+///
+/// いずれも既知の補正に追加で乗算される倍率として扱う
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum UnknownModifier {
+    /// 海域補正
+    #[default]
+    Historical,
+    /// 特効のキャップ後補正
+    PostcapGeneral,
+    Precap,
+    Postcap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct ObservedDamage {
+    pub damage: u16,
+    pub critical: bool,
+    /// 攻撃を受ける直前の耐久
+    #[serde(default)]
+    pub hp_before: Option<u16>,
+}
+
+impl ObservedDamage {
+    /// カスダメの範囲に含まれる可能性があるか
+    ///
+    /// 耐久が不明な場合は0ダメージのみを対象とする
+    fn may_be_scratch(&self) -> bool {
+        match self.hp_before {
+            Some(hp) => self.damage <= max_scratch_damage(hp),
+            None => self.damage == 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[serde(default)]
+#[tsify(from_wasm_abi)]
+pub struct ModifierSolverConfig {
+    /// 未知の補正を含まない攻撃力の計算パラメータ
+    pub params: AttackPowerParams,
+    pub basic_defense_power: f64,
+    pub modifier: UnknownModifier,
+    pub observations: Vec<ObservedDamage>,
+    pub search_min: f64,
+    pub search_max: f64,
+}
+
+impl Default for ModifierSolverConfig {
+    fn default() -> Self {
+        Self {
+            params: Default::default(),
+            basic_defense_power: 1.0,
+            modifier: Default::default(),
+            observations: Default::default(),
+            search_min: 0.0,
+            search_max: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ModifierSolution {
+    pub modifier: UnknownModifier,
+    /// 全ての観測値と矛盾しない倍率の範囲
+    pub min: f64,
+    pub max: f64,
+    pub feasible: bool,
+    /// 計算に用いた観測値の数
+    pub used_count: usize,
+    /// カスダメの範囲に含まれるため除外した観測値の数
+    pub ignored_count: usize,
+    /// 単独で探索範囲に解を持たない観測値の位置
+    pub inconsistent: Vec<usize>,
+}
+
+impl ModifierSolution {
+    pub fn estimation(&self) -> Estimation<f64> {
+        if !self.feasible || self.used_count == 0 {
+            return Estimation::Unknown;
+        }
+
+        let mid = (self.min + self.max) / 2.0;
+
+        if self.max - self.min < 1e-3 {
+            Estimation::Just(mid)
+        } else {
+            Estimation::Rough(mid)
+        }
+    }
+}
+
+/// 観測されたダメージから未知の補正の取りうる範囲を逆算する
+///
+/// 攻撃力は補正に対して単調増加なので、
+/// 防御力の乱数の全範囲で観測値を満たす攻撃力の範囲を求め、二分探索で補正の範囲に戻す
+pub struct ModifierSolver<'a> {
+    pub config: &'a ModifierSolverConfig,
+}
+
+impl ModifierSolver<'_> {
+    fn attack_term(&self, value: f64, critical: bool) -> f64 {
+        let mut params = self.config.params.clone();
+
+        match self.config.modifier {
+            UnknownModifier::Historical => params.historical_mod *= value,
+            UnknownModifier::PostcapGeneral => {
+                params.special_enemy_mods.postcap_general_mod = params
+                    .special_enemy_mods
+                    .postcap_general_mod
+                    .compose(AttackPowerModifier::new(value, 0.0))
+            }
+            UnknownModifier::Precap => params.custom_mods.precap_mod.a *= value,
+            UnknownModifier::Postcap => params.custom_mods.postcap_mod.a *= value,
+        }
+
+        let attack_power = params.calc();
+
+        if critical {
+            attack_power.critical
+        } else {
+            attack_power.normal
+        }
+    }
+
+    /// attack_term(x) >= target となる最小の x
+    fn lower_bound(&self, target: f64, critical: bool) -> Option<f64> {
+        let ModifierSolverConfig {
+            search_min,
+            search_max,
+            ..
+        } = *self.config;
+
+        if self.attack_term(search_max, critical) < target {
+            return None;
+        }
+
+        if self.attack_term(search_min, critical) >= target {
+            return Some(search_min);
+        }
+
+        let (mut lo, mut hi) = (search_min, search_max);
+
+        for _ in 0..BISECTION_ITERATIONS {
+            let mid = (lo + hi) / 2.0;
+
+            if self.attack_term(mid, critical) >= target {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        Some(hi)
+    }
+
+    /// attack_term(x) < target となる最大の x
+    fn upper_bound(&self, target: f64, critical: bool) -> Option<f64> {
+        let ModifierSolverConfig {
+            search_min,
+            search_max,
+            ..
+        } = *self.config;
+
+        if self.attack_term(search_min, critical) >= target {
+            return None;
+        }
+
+        if self.attack_term(search_max, critical) < target {
+            return Some(search_max);
+        }
+
+        let (mut lo, mut hi) = (search_min, search_max);
+
+        for _ in 0..BISECTION_ITERATIONS {
+            let mid = (lo + hi) / 2.0;
+
+            if self.attack_term(mid, critical) < target {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Some(lo)
+    }
+
+    fn solve_one(&self, observed: &ObservedDamage) -> Option<(f64, f64)> {
+        let defense_power = DefensePower::new(self.config.basic_defense_power.max(1.0));
+        let remaining_ammo_mod = self.config.params.remaining_ammo_mod;
+
+        // floor((攻撃力 - 防御力) * 残弾補正) == ダメージ
+        let damage = observed.damage as f64;
+        let attack_min = damage / remaining_ammo_mod + defense_power.min().max(1.0);
+        let attack_max = (damage + 1.0) / remaining_ammo_mod + defense_power.max().max(1.0);
+
+        let min = self.lower_bound(attack_min, observed.critical)?;
+        let max = self.upper_bound(attack_max, observed.critical)?;
+
+        (min <= max).then_some((min, max))
+    }
+
+    pub fn solve(&self) -> ModifierSolution {
+        let ModifierSolverConfig {
+            search_min,
+            search_max,
+            modifier,
+            ..
+        } = *self.config;
+
+        let mut min = search_min;
+        let mut max = search_max;
+        let mut used_count = 0;
+        let mut ignored_count = 0;
+        let mut inconsistent = vec![];
+
+        let usable = self.config.params.remaining_ammo_mod > 0.0;
+
+        for (index, observed) in self.config.observations.iter().enumerate() {
+            if observed.may_be_scratch() || !usable {
+                ignored_count += 1;
+                continue;
+            }
+
+            used_count += 1;
+
+            match self.solve_one(observed) {
+                Some((lo, hi)) => {
+                    min = min.max(lo);
+                    max = max.min(hi);
+                }
+                None => inconsistent.push(index),
+            }
+        }
+
+        ModifierSolution {
+            modifier,
+            min,
+            max,
+            feasible: inconsistent.is_empty() && min <= max,
+            used_count,
+            ignored_count,
+            inconsistent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_modifier_solver() {
+        let params = AttackPowerParams {
+            basic: 120.0,
+            cap: 220.0,
+            ..Default::default()
+        };
+
+        let historical_mod = 1.35;
+        let basic_defense_power = 40.0;
+
+        let attack_term = AttackPowerParams {
+            historical_mod,
+            ..params.clone()
+        }
+        .calc();
+
+        let observations = DefensePower::new(basic_defense_power)
+            .iter()
+            .step_by(7)
+            .flat_map(|defense_power| {
+                [
+                    ObservedDamage {
+                        damage: (attack_term.normal - defense_power).floor() as u16,
+                        critical: false,
+                        hp_before: None,
+                    },
+                    ObservedDamage {
+                        damage: (attack_term.critical - defense_power).floor() as u16,
+                        critical: true,
+                        hp_before: None,
+                    },
+                ]
+            })
+            .chain([
+                ObservedDamage {
+                    damage: 0,
+                    critical: false,
+                    hp_before: None,
+                },
+                // 耐久50に対するカスダメは 3..=6
+                ObservedDamage {
+                    damage: 6,
+                    critical: false,
+                    hp_before: Some(50),
+                },
+            ])
+            .collect();
+
+        let config = ModifierSolverConfig {
+            params,
+            basic_defense_power,
+            observations,
+            ..Default::default()
+        };
+
+        let solution = ModifierSolver { config: &config }.solve();
+
+        assert!(solution.feasible);
+        assert_eq!(solution.ignored_count, 2);
+        assert!(solution.min <= historical_mod && historical_mod <= solution.max);
+        assert!(solution.max - solution.min < 0.05);
+        assert!(matches!(solution.estimation(), Estimation::Rough(_)));
+
+        let config = ModifierSolverConfig {
+            observations: vec![ObservedDamage {
+                damage: 5000,
+                critical: false,
+                hp_before: Some(50),
+            }],
+            ..config
+        };
+
+        let solution = ModifierSolver { config: &config }.solve();
+        assert!(!solution.feasible);
+        assert_eq!(solution.inconsistent, vec![0]);
+        assert!(matches!(solution.estimation(), Estimation::Unknown));
+    }
+}
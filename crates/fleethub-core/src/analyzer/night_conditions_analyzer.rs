@@ -0,0 +1,231 @@
+use hashbrown::HashMap;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::{
+    comp::Comp,
+    fleet::Fleet,
+    ship::Ship,
+    types::{BattleDefinitions, ContactRank, DamageState, GearType, NightFleetConditions},
+};
+
+use super::{
+    night_cutin_analyzer::{
+        CompNightCutinAnalysis, NightContactChance, NightCutinActionReport, NightCutinAnalyzer,
+    },
+    CompAnalyzerConfig,
+};
+
+/// 探照灯1本あたりの発動率
+///
+/// 判定艦が大破していなければ発動する
+const SEARCHLIGHT_ACTIVATION_RATE: f64 = 1.0;
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct NightConditionsScenario {
+    pub rate: f64,
+    #[serde(flatten)]
+    pub conditions: NightFleetConditions,
+}
+
+#[derive(Debug, Default, Serialize, Tsify)]
+pub struct WeightedNightCutinReport {
+    pub cutin_term: Option<f64>,
+    pub rates: HashMap<String, f64>,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+pub struct ShipWeightedNightCutinAnalysis {
+    pub ship_id: u16,
+    pub index: usize,
+    pub normal: WeightedNightCutinReport,
+    pub chuuha: WeightedNightCutinReport,
+}
+
+#[derive(Debug, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct NightConditionsAnalysis {
+    pub night_contact_chance: Vec<(Option<ContactRank>, f64)>,
+    pub starshell_index: Option<usize>,
+    pub starshell_chance: f64,
+    pub searchlight_index: Option<usize>,
+    pub searchlight_chance: f64,
+    pub activates_large_searchlight: bool,
+    pub scenarios: Vec<NightConditionsScenario>,
+    pub ships: Vec<ShipWeightedNightCutinAnalysis>,
+}
+
+pub struct NightConditionsAnalyzer<'a> {
+    pub battle_defs: &'a BattleDefinitions,
+    pub comp: &'a Comp,
+    pub dummy: &'a Ship,
+    pub config: &'a CompAnalyzerConfig,
+}
+
+/// 夜戦装備の判定艦と発動率
+///
+/// 装備艦のうち艦隊の先頭に近い大破していない艦のみが判定を行う
+#[derive(Debug, Default, Clone, Copy)]
+struct NightGearActivation {
+    index: Option<usize>,
+    rate: f64,
+    large: bool,
+}
+
+impl NightGearActivation {
+    fn cases(&self) -> [(Option<usize>, f64); 2] {
+        [(self.index, self.rate), (None, 1.0 - self.rate)]
+    }
+}
+
+fn can_activate_night_gear(ship: &Ship, gear_type: GearType) -> bool {
+    ship.damage_state() < DamageState::Taiha && ship.gears.has_type(gear_type)
+}
+
+fn find_starshell(fleet: &Fleet, rate: f64) -> NightGearActivation {
+    fleet
+        .ships
+        .iter()
+        .find(|(_, ship)| can_activate_night_gear(ship, GearType::Starshell))
+        .filter(|_| rate > 0.0)
+        .map(|(index, _)| NightGearActivation {
+            index: Some(index),
+            rate,
+            large: false,
+        })
+        .unwrap_or_default()
+}
+
+fn find_searchlight(fleet: &Fleet) -> NightGearActivation {
+    fleet
+        .ships
+        .iter()
+        .find_map(|(index, ship)| {
+            [GearType::LargeSearchlight, GearType::Searchlight]
+                .into_iter()
+                .find(|&gear_type| can_activate_night_gear(ship, gear_type))
+                .map(|gear_type| NightGearActivation {
+                    index: Some(index),
+                    rate: SEARCHLIGHT_ACTIVATION_RATE,
+                    large: gear_type == GearType::LargeSearchlight,
+                })
+        })
+        .unwrap_or_default()
+}
+
+impl WeightedNightCutinReport {
+    fn add(&mut self, report: &NightCutinActionReport, rate: f64) {
+        self.cutin_term = match (self.cutin_term, report.cutin_term) {
+            (Some(total), Some(current)) => Some(total + current * rate),
+            _ => None,
+        };
+
+        report.report.data.iter().for_each(|(key, attack)| {
+            let proc_rate = attack.proc_rate.unwrap_or_default();
+            *self.rates.entry(key.clone()).or_default() += proc_rate * rate;
+        });
+    }
+}
+
+impl NightConditionsAnalyzer<'_> {
+    pub fn analyze(&self) -> NightConditionsAnalysis {
+        let fleet = self.comp.night_fleet();
+
+        let NightContactChance {
+            rank1,
+            rank2,
+            rank3,
+        } = NightContactChance::new(fleet);
+
+        let night_contact_chance = vec![
+            (None, 1.0 - rank1 - rank2 - rank3),
+            (Some(ContactRank::Rank1), rank1),
+            (Some(ContactRank::Rank2), rank2),
+            (Some(ContactRank::Rank3), rank3),
+        ];
+
+        let starshell = find_starshell(fleet, self.config.starshell_activation_rate);
+        let searchlight = find_searchlight(fleet);
+        let activates_large_searchlight = searchlight.large;
+
+        let scenarios = night_contact_chance
+            .iter()
+            .flat_map(|&(night_contact_rank, contact_rate)| {
+                starshell
+                    .cases()
+                    .into_iter()
+                    .flat_map(move |(starshell_index, starshell_rate)| {
+                        searchlight.cases().into_iter().map(
+                            move |(searchlight_index, searchlight_rate)| NightConditionsScenario {
+                                rate: contact_rate * starshell_rate * searchlight_rate,
+                                conditions: NightFleetConditions {
+                                    night_contact_rank,
+                                    starshell_index,
+                                    searchlight_index,
+                                    activates_large_searchlight: searchlight_index.is_some()
+                                        && activates_large_searchlight,
+                                },
+                            },
+                        )
+                    })
+            })
+            .filter(|scenario| scenario.rate > 0.0)
+            .collect::<Vec<_>>();
+
+        let mut ships = self
+            .comp
+            .members_by(self.comp.night_fleet_type())
+            .map(|member| ShipWeightedNightCutinAnalysis {
+                ship_id: member.ship_id,
+                index: member.position.index,
+                normal: WeightedNightCutinReport {
+                    cutin_term: Some(0.0),
+                    ..Default::default()
+                },
+                chuuha: WeightedNightCutinReport {
+                    cutin_term: Some(0.0),
+                    ..Default::default()
+                },
+            })
+            .collect::<Vec<_>>();
+
+        scenarios.iter().for_each(|scenario| {
+            let analysis = self.analyze_scenario(&scenario.conditions);
+
+            ships
+                .iter_mut()
+                .zip(analysis.ships.iter())
+                .for_each(|(weighted, current)| {
+                    weighted.normal.add(&current.normal, scenario.rate);
+                    weighted.chuuha.add(&current.chuuha, scenario.rate);
+                });
+        });
+
+        NightConditionsAnalysis {
+            night_contact_chance,
+            starshell_index: starshell.index,
+            starshell_chance: starshell.rate,
+            searchlight_index: searchlight.index,
+            searchlight_chance: searchlight.rate,
+            activates_large_searchlight,
+            scenarios,
+            ships,
+        }
+    }
+
+    fn analyze_scenario(&self, conditions: &NightFleetConditions) -> CompNightCutinAnalysis {
+        let config = CompAnalyzerConfig {
+            left_night_fleet_conditions: conditions.clone(),
+            right_night_fleet_conditions: self.config.right_night_fleet_conditions.clone(),
+            ..*self.config
+        };
+
+        NightCutinAnalyzer {
+            battle_defs: self.battle_defs,
+            comp: self.comp,
+            dummy: self.dummy,
+            config: &config,
+        }
+        .analyze()
+    }
+}
@@ -16,24 +16,24 @@ use super::{
 };
 
 #[derive(Serialize, Tsify)]
-struct NightCutinActionReport {
-    cutin_term: Option<f64>,
+pub(super) struct NightCutinActionReport {
+    pub(super) cutin_term: Option<f64>,
     #[serde(flatten)]
-    report: ActionReport<NightPhaseAttackStyle>,
+    pub(super) report: ActionReport<NightPhaseAttackStyle>,
 }
 
 #[derive(Serialize, Tsify)]
-struct ShipNightCutinAnalysis {
-    ship_id: u16,
-    index: usize,
-    normal: NightCutinActionReport,
-    chuuha: NightCutinActionReport,
+pub(super) struct ShipNightCutinAnalysis {
+    pub(super) ship_id: u16,
+    pub(super) index: usize,
+    pub(super) normal: NightCutinActionReport,
+    pub(super) chuuha: NightCutinActionReport,
 }
 
 #[derive(Serialize, Tsify)]
 pub struct CompNightCutinAnalysis {
-    night_contact_chance: NightContactChance,
-    ships: Vec<ShipNightCutinAnalysis>,
+    pub(super) night_contact_chance: NightContactChance,
+    pub(super) ships: Vec<ShipNightCutinAnalysis>,
 }
 
 pub struct NightCutinAnalyzer<'a> {
@@ -63,6 +63,8 @@ impl NightCutinAnalyzer<'_> {
             },
             attacker: &ship,
             target: self.dummy,
+            attacker_comp: Some(self.comp),
+            target_comp: None,
         };
 
         let cutin_term = attack_analyzer.calc_night_cutin_term();
@@ -112,14 +114,14 @@ impl NightCutinAnalyzer<'_> {
 }
 
 #[derive(Serialize, Tsify)]
-struct NightContactChance {
-    rank1: f64,
-    rank2: f64,
-    rank3: f64,
+pub(super) struct NightContactChance {
+    pub(super) rank1: f64,
+    pub(super) rank2: f64,
+    pub(super) rank3: f64,
 }
 
 impl NightContactChance {
-    fn new(fleet: &Fleet) -> Self {
+    pub(super) fn new(fleet: &Fleet) -> Self {
         let vec = fleet
             .ships
             .values()
@@ -83,17 +83,7 @@ impl NodeAttackAnalyzer<'_> {
             }
         };
 
-        let conditions = comp.get_ship_conditions(ship, Some(config.formation));
-        let fleet = comp.get_fleet(conditions.position.fleet_type);
-        let fleet_los_mod = fleet.and_then(|fleet| fleet.fleet_los_mod());
-
-        let attack_analyzer_ship_config = AttackAnalyzerShipConfig {
-            conditions,
-            fleet_los_mod,
-            night_fleet_conditions: config.night_fleet_conditions.clone(),
-        };
-
-        (attack_analyzer_ship_config, ship)
+        (config.attack_analyzer_ship_config(comp, ship), ship)
     }
 
     fn analyze_attack(&self, attacker_is_left: bool) -> AttackAnalysis {
@@ -103,6 +93,12 @@ impl NodeAttackAnalyzer<'_> {
             (Align::Right, Align::Left)
         };
 
+        let (attacker_comp, target_comp) = if attacker_is_left {
+            (self.left_comp, self.right_comp)
+        } else {
+            (self.right_comp, self.left_comp)
+        };
+
         let (attacker_config, attacker_ship) =
             self.get_attack_analyzer_ship_config_and_ship(attacker_side);
         let (target_config, target_ship) =
@@ -121,6 +117,8 @@ impl NodeAttackAnalyzer<'_> {
             config,
             attacker: attacker_ship,
             target: target_ship,
+            attacker_comp: Some(attacker_comp),
+            target_comp: Some(target_comp),
         }
         .analyze()
     }
@@ -161,6 +159,24 @@ pub struct NodeAttackAnalyzerShipConfig {
     pub night_fleet_conditions: NightFleetConditions,
 }
 
+impl NodeAttackAnalyzerShipConfig {
+    pub(super) fn attack_analyzer_ship_config(
+        &self,
+        comp: &Comp,
+        ship: &Ship,
+    ) -> AttackAnalyzerShipConfig {
+        let conditions = comp.get_ship_conditions(ship, Some(self.formation));
+        let fleet = comp.get_fleet(conditions.position.fleet_type);
+        let fleet_los_mod = fleet.and_then(|fleet| fleet.fleet_los_mod());
+
+        AttackAnalyzerShipConfig {
+            conditions,
+            fleet_los_mod,
+            night_fleet_conditions: self.night_fleet_conditions.clone(),
+        }
+    }
+}
+
 enum Align {
     Left,
     Right,
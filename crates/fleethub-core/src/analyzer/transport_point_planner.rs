@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    comp::Comp,
+    gear::Gear,
+    gear_array::{into_gear_key, GearArray},
+    types::{gear_id, FleetType, Role},
+};
+
+/// 輸送作戦の戦闘評価
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum TransportRank {
+    #[default]
+    S,
+    A,
+}
+
+impl TransportRank {
+    pub fn multiplier(self) -> f64 {
+        match self {
+            Self::S => 1.0,
+            Self::A => 0.7,
+        }
+    }
+
+    /// 評価による補正後の輸送量
+    pub fn apply(self, transport_point: i32) -> i32 {
+        (transport_point as f64 * self.multiplier()).floor() as i32
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(default)]
+pub struct TransportPointPlannerConfig {
+    /// ゲージの総量
+    pub gauge: i32,
+    pub rank: TransportRank,
+    /// 1出撃あたりの目標TP
+    ///
+    /// 指定しない場合は出撃回数を1回減らすのに必要なTPを目標とする
+    pub target: Option<i32>,
+    /// 積み替え候補の装備
+    pub candidate_gear_ids: Vec<u16>,
+}
+
+impl Default for TransportPointPlannerConfig {
+    fn default() -> Self {
+        Self {
+            gauge: 0,
+            rank: TransportRank::S,
+            target: None,
+            candidate_gear_ids: vec![gear_id!("ドラム缶(輸送用)"), gear_id!("大発動艇")],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct TransportGearSwap {
+    pub role: Role,
+    pub index: usize,
+    pub ship_id: u16,
+    pub key: String,
+    pub current_gear_id: Option<u16>,
+    pub gear_id: u16,
+    pub gain: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct TransportPointPlan {
+    pub transport_point: i32,
+    pub main_transport_point: i32,
+    pub escort_transport_point: Option<i32>,
+    /// 評価補正後の1出撃あたりの輸送量
+    pub rank_transport_point: i32,
+    pub sorties: Option<i32>,
+    pub target: Option<i32>,
+    pub swaps: Vec<TransportGearSwap>,
+    pub planned_transport_point: i32,
+    pub planned_sorties: Option<i32>,
+}
+
+pub struct TransportPointPlanner<'a> {
+    pub comp: &'a Comp,
+    pub config: &'a TransportPointPlannerConfig,
+    pub candidates: &'a [Gear],
+}
+
+fn calc_sorties(gauge: i32, rank_transport_point: i32) -> Option<i32> {
+    if rank_transport_point <= 0 {
+        return None;
+    }
+
+    Some(((gauge + rank_transport_point - 1) / rank_transport_point).max(1))
+}
+
+impl TransportPointPlanner<'_> {
+    pub fn plan(&self) -> TransportPointPlan {
+        let TransportPointPlannerConfig { gauge, rank, .. } = *self.config;
+
+        let transport_point = self.comp.transport_point();
+        let main_transport_point = self
+            .comp
+            .members_by(FleetType::Main)
+            .map(|ship| ship.transport_point())
+            .sum();
+        let escort_transport_point = self.comp.escort.as_ref().map(|_| {
+            self.comp
+                .members_by(FleetType::Escort)
+                .map(|ship| ship.transport_point())
+                .sum()
+        });
+
+        let rank_transport_point = rank.apply(transport_point);
+        let sorties = calc_sorties(gauge, rank_transport_point);
+
+        let target = self.config.target.or_else(|| self.default_target(sorties));
+
+        let mut swaps = self.swap_candidates();
+        swaps.sort_by_key(|swap| (-swap.gain, swap.current_gear_id.is_some()));
+
+        let mut planned_transport_point = transport_point;
+
+        let swaps = if let Some(target) = target {
+            swaps
+                .into_iter()
+                .take_while(|swap| {
+                    if planned_transport_point >= target {
+                        return false;
+                    }
+
+                    planned_transport_point += swap.gain;
+                    true
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        TransportPointPlan {
+            transport_point,
+            main_transport_point,
+            escort_transport_point,
+            rank_transport_point,
+            sorties,
+            target,
+            swaps,
+            planned_transport_point,
+            planned_sorties: calc_sorties(gauge, rank.apply(planned_transport_point)),
+        }
+    }
+
+    /// 出撃回数を1回減らすのに必要なTP
+    fn default_target(&self, sorties: Option<i32>) -> Option<i32> {
+        let TransportPointPlannerConfig { gauge, rank, .. } = *self.config;
+        let sorties = sorties?;
+
+        if sorties <= 1 {
+            return None;
+        }
+
+        let required = (gauge + sorties - 2) / (sorties - 1);
+        let mut target = (required as f64 / rank.multiplier()).floor() as i32;
+
+        while rank.apply(target) < required {
+            target += 1;
+        }
+
+        Some(target)
+    }
+
+    /// 各スロットで最もTPが増える積み替え
+    fn swap_candidates(&self) -> Vec<TransportGearSwap> {
+        self.comp
+            .members_by(FleetType::Main | FleetType::Escort)
+            .flat_map(|member| {
+                let ship = member.ship;
+                let position = member.position;
+
+                (0..GearArray::CAPACITY).filter_map(move |index| {
+                    let key = into_gear_key(index)?;
+                    let current = ship.gears.get(index);
+                    let current_tp = current.map_or(0, |gear| gear.transport_point());
+
+                    let best = self
+                        .candidates
+                        .iter()
+                        .filter(|gear| ship.can_equip(gear, key))
+                        .max_by_key(|gear| gear.transport_point())?;
+
+                    let gain = best.transport_point() - current_tp;
+
+                    (gain > 0).then(|| TransportGearSwap {
+                        role: position.fleet_type.into(),
+                        index: position.index,
+                        ship_id: ship.ship_id,
+                        key: key.to_string(),
+                        current_gear_id: current.map(|gear| gear.gear_id),
+                        gear_id: best.gear_id,
+                        gain,
+                    })
+                })
+            })
+            .collect()
+    }
+}
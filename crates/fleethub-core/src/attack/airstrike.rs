@@ -49,16 +49,19 @@ pub fn create_airstrike_params<P: PlaneImpl, R: Rng + ?Sized>(
     };
 
     let hit_rate_params = {
-        let evasion_term = target.evasion_term(1.0, 0.0, 1.0);
+        let evasion_term = target.estimated_evasion_term(1.0, 0.0, 1.0);
 
-        evasion_term.map(|evasion_term| HitRateParams {
-            accuracy_term: 95.0,
-            evasion_term,
-            target_morale_mod: target.morale_state().hit_rate_mod(),
-            critical_rate_constant: 0.2,
-            critical_percentage_bonus: proficiency_modifiers.critical_percentage_bonus,
-            hit_percentage_bonus: proficiency_modifiers.hit_percentage_bonus,
-        })
+        evasion_term
+            .value_and_rough()
+            .map(|(evasion_term, rough)| HitRateParams {
+                accuracy_term: 95.0,
+                evasion_term,
+                target_morale_mod: target.morale_state().hit_rate_mod(),
+                critical_rate_constant: 0.2,
+                critical_percentage_bonus: proficiency_modifiers.critical_percentage_bonus,
+                hit_percentage_bonus: proficiency_modifiers.hit_percentage_bonus,
+                rough,
+            })
     };
 
     const ARMOR_PENETRATION: f64 = 0.0;
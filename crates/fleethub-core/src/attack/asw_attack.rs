@@ -142,8 +142,9 @@ impl AswAttackParams<'_> {
 
         let calc_hit_rate_params = || {
             let accuracy_term = calc_accuracy_term()?;
-            let evasion_term =
-                target.evasion_term(formation_params.target_evasion_mod, 0.0, 1.0)?;
+            let (evasion_term, rough) = target
+                .estimated_evasion_term(formation_params.target_evasion_mod, 0.0, 1.0)
+                .value_and_rough()?;
 
             let hit_percentage_bonus = proficiency_mods
                 .as_ref()
@@ -162,6 +163,7 @@ impl AswAttackParams<'_> {
                 critical_rate_constant: ASW_CRITICAL_RATE_CONSTANT,
                 critical_percentage_bonus,
                 hit_percentage_bonus,
+                rough,
             })
         };
 
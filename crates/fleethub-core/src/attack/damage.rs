@@ -39,6 +39,11 @@ impl ScratchDamage {
     }
 }
 
+/// 現在耐久に対するカスダメの最大値
+pub(crate) fn max_scratch_damage(current_hp: u16) -> u16 {
+    ScratchDamage { current_hp }.max()
+}
+
 struct OverkillProtectionDamage {
     current_hp: u16,
 }
@@ -70,6 +75,8 @@ pub struct DefenseParams {
     pub basic_defense_power: f64,
     pub overkill_protection: bool,
     pub sinkable: bool,
+    /// 装甲や耐久に類似艦からの推定値を用いている
+    pub rough: bool,
 }
 
 impl DefenseParams {
@@ -77,12 +84,19 @@ impl DefenseParams {
         let overkill_protection = side.is_player() && target.morale_state() != MoraleState::Red;
         let sinkable = side.is_enemy();
 
+        let estimation = target
+            .estimated_basic_defense_power(armor_penetration)
+            .zip(target.estimated_max_hp());
+        let rough = estimation.is_rough();
+        let (basic_defense_power, max_hp) = estimation.value()?;
+
         Some(Self {
-            basic_defense_power: target.basic_defense_power(armor_penetration)?,
-            max_hp: target.max_hp()?,
+            basic_defense_power,
+            max_hp,
             current_hp: target.current_hp,
             overkill_protection,
             sinkable,
+            rough,
         })
     }
 }
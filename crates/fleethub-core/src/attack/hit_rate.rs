@@ -19,6 +19,9 @@ pub struct HitRateParams {
     pub critical_rate_constant: f64,
     pub critical_percentage_bonus: f64,
     pub hit_percentage_bonus: f64,
+    /// 回避に類似艦からの推定値を用いている
+    #[serde(default)]
+    pub rough: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Tsify)]
@@ -100,6 +103,7 @@ mod test {
             critical_rate_constant: 1.0,
             critical_percentage_bonus: 0.0,
             hit_percentage_bonus: 0.0,
+            rough: false,
         };
 
         let max = HitRateParams {
@@ -135,6 +139,7 @@ mod test {
             critical_rate_constant: 1.3,
             critical_percentage_bonus: 12.0,
             hit_percentage_bonus: 9.0,
+            rough: false,
         }
         .calc();
 
@@ -1,4 +1,5 @@
 use crate::{
+    estimation::Estimation,
     member::BattleMemberRef,
     types::{
         AswPhase, AttackPowerModifier, Engagement, FormationParams, GearType, HistoricalParams,
@@ -255,7 +256,7 @@ impl NightAttackParams<'_> {
         Some(accuracy_term)
     }
 
-    fn calc_evasion_term(&self) -> Option<f64> {
+    fn calc_evasion_term(&self) -> Estimation<f64> {
         let target = self.target;
         let formation_mod = self.formation_params.target_evasion_mod;
         let historical_mod = self.historical_params.target_evasion_mod;
@@ -274,8 +275,11 @@ impl NightAttackParams<'_> {
 
         let postcap_multiplicative = searchlight_evasion_mod * historical_mod;
 
-        self.target
-            .evasion_term(formation_mod, ship_type_additive, postcap_multiplicative)
+        self.target.estimated_evasion_term(
+            formation_mod,
+            ship_type_additive,
+            postcap_multiplicative,
+        )
     }
 
     fn calc_hit_rate_params(
@@ -283,7 +287,7 @@ impl NightAttackParams<'_> {
         proficiency_mods: Option<ProficiencyModifiers>,
     ) -> Option<HitRateParams> {
         let accuracy_term = self.calc_accuracy_term()?;
-        let evasion_term = self.calc_evasion_term()?;
+        let (evasion_term, rough) = self.calc_evasion_term().value_and_rough()?;
         let attacker_night_conditions = self.attacker_night_conditions();
 
         let critical_rate_constant = attacker_night_conditions
@@ -305,6 +309,7 @@ impl NightAttackParams<'_> {
             critical_rate_constant,
             hit_percentage_bonus,
             critical_percentage_bonus,
+            rough,
         })
     }
 }
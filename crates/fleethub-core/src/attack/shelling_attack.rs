@@ -152,7 +152,9 @@ impl ShellingAttackParams<'_> {
         let calc_hit_rate_params = || {
             let formation_mod = formation_params.target_evasion_mod;
             let historical_mod = self.historical_params.target_evasion_mod;
-            let evasion_term = target.evasion_term(formation_mod, 0.0, historical_mod)?;
+            let (evasion_term, rough) = target
+                .estimated_evasion_term(formation_mod, 0.0, historical_mod)
+                .value_and_rough()?;
 
             let accuracy_term = calc_accuracy_term()?;
 
@@ -167,6 +169,7 @@ impl ShellingAttackParams<'_> {
                 hit_percentage_bonus: proficiency_mods
                     .as_ref()
                     .map_or(0.0, |mods| mods.hit_percentage_bonus),
+                rough,
             })
         };
 
@@ -106,7 +106,9 @@ impl SupportShellingAttackParams<'_> {
     fn calc_hit_rate_params(&self) -> Option<HitRateParams> {
         let target = self.target;
         let formation_mod = self.formation_params.target_evasion_mod;
-        let evasion_term = target.evasion_term(formation_mod, 0.0, 1.0)?;
+        let (evasion_term, rough) = target
+            .estimated_evasion_term(formation_mod, 0.0, 1.0)
+            .value_and_rough()?;
         let accuracy_term = self.calc_accuracy_term()?;
 
         Some(HitRateParams {
@@ -116,6 +118,7 @@ impl SupportShellingAttackParams<'_> {
             critical_rate_constant: SUPPORT_SHELLING_CRITICAL_RATE_CONSTANT,
             critical_percentage_bonus: 0.0,
             hit_percentage_bonus: 0.0,
+            rough,
         })
     }
 }
@@ -142,7 +142,9 @@ impl TorpedoAttackParams<'_> {
         let formation_mod = self.formation_params.target_evasion_mod;
         let ibonus = target.gears.sum_by(|gear| gear.ibonuses.torpedo_evasion);
         let historical_mod = self.historical_params.target_evasion_mod;
-        let evasion_term = target.evasion_term(formation_mod, ibonus, historical_mod)?;
+        let (evasion_term, rough) = target
+            .estimated_evasion_term(formation_mod, ibonus, historical_mod)
+            .value_and_rough()?;
 
         Some(HitRateParams {
             accuracy_term,
@@ -151,6 +153,7 @@ impl TorpedoAttackParams<'_> {
             critical_rate_constant: TORPEDO_CRITICAL_RATE_CONSTANT,
             critical_percentage_bonus: 0.0,
             hit_percentage_bonus: 0.0,
+            rough,
         })
     }
 }
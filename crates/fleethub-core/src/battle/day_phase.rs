@@ -108,6 +108,14 @@ where
         };
 
         let attack_type = picked.attack_type;
+        let historical_params = self.battle_defs.get_historical_params(
+            self.node_state,
+            &attacker,
+            &target_comp.get_ship(picked.position).expect(SHIP_NOT_FOUND),
+            Some(attacker_comp),
+            Some(target_comp),
+        );
+
         let mut target = target_comp
             .get_ship_mut(picked.position)
             .expect(SHIP_NOT_FOUND);
@@ -118,10 +126,6 @@ where
             target.conditions(),
         );
 
-        let historical_params =
-            self.battle_defs
-                .get_historical_params(self.node_state, &attacker, &target.as_ref());
-
         let style = match attack_type {
             DayPhaseAttackType::Shelling(attack_type) => {
                 let is_main_flagship = attacker.position.is_main_flagship();
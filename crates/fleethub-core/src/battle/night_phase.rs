@@ -63,6 +63,14 @@ where
         let picked = some_or_return!(picker.choose(self.rng), Ok(()));
 
         let attack_type = picked.attack_type;
+        let historical_params = self.battle_defs.get_historical_params(
+            self.node_state,
+            &attacker,
+            &target_comp.get_ship(picked.position).expect(SHIP_NOT_FOUND),
+            Some(attacker_comp),
+            Some(target_comp),
+        );
+
         let mut target = target_comp
             .get_ship_mut(picked.position)
             .expect(SHIP_NOT_FOUND);
@@ -71,9 +79,6 @@ where
             attacker.conditions(),
             target.conditions(),
         );
-        let historical_params =
-            self.battle_defs
-                .get_historical_params(self.node_state, &attacker, &target.as_ref());
 
         let style = match attack_type {
             NightPhaseAttackType::Night(attack_type) => {
@@ -8,6 +8,7 @@ use crate::{
         AttackType, BattleDefinitions, Engagement, NodeState, Participant, ShipPosition,
         TorpedoAttackType,
     },
+    utils::some_or_return,
 };
 
 use super::battle_comp::BattleComp;
@@ -68,21 +69,25 @@ where
             Participant::Both,
         );
 
-        let picked = picker.choose(self.rng);
-        let mut target = if let Some(t) = picked {
-            target_comp.get_ship_mut(t.position).expect(SHIP_NOT_FOUND)
-        } else {
-            return Ok(());
-        };
+        let picked = some_or_return!(picker.choose(self.rng), Ok(()));
+
+        let historical_params = self.battle_defs.get_historical_params(
+            self.node_state,
+            &attacker,
+            &target_comp.get_ship(picked.position).expect(SHIP_NOT_FOUND),
+            Some(attacker_comp),
+            Some(target_comp),
+        );
+
+        let mut target = target_comp
+            .get_ship_mut(picked.position)
+            .expect(SHIP_NOT_FOUND);
 
         let formation_params = self.battle_defs.get_formation_params(
             AttackType::Torpedo,
             attacker.conditions(),
             target.conditions(),
         );
-        let historical_params =
-            self.battle_defs
-                .get_historical_params(self.node_state, &attacker, &target.as_ref());
 
         let attack = TorpedoAttackParams {
             attacker: &attacker,
@@ -1,6 +1,7 @@
 use std::ops::Deref;
 
 use enumset::EnumSet;
+use fasteval::{bool_to_f64, EvalNamespace};
 use itertools::Itertools;
 use rand::Rng;
 use wasm_bindgen::prelude::*;
@@ -11,8 +12,9 @@ use crate::{
     plane::{Plane, PlaneMut},
     ship::Ship,
     types::{
-        AirWaveType, AntiAirCutinDef, BattleDefinitions, CompMeta, FleetType, Formation, OrgType,
-        Role, ShipConditions, ShipKey, ShipPosition, Side,
+        gear_id, AirWaveType, AntiAirCutinDef, BattleDefinitions, CompMeta, FleetType, Formation,
+        OrgType, Role, ShipConditions, ShipKey, ShipPosition, ShipSpeedSuggestion, Side, Speed,
+        SpeedReport,
     },
 };
 
@@ -279,7 +281,7 @@ impl Comp {
         self.members_by(FleetType::Main | FleetType::Escort)
             .map(|entry| entry.ship)
             .filter_map(|ship| {
-                let aaci_vec = ship.get_possible_anti_air_cutin_ids();
+                let aaci_vec = ship.get_possible_anti_air_cutin_ids(battle_defs);
 
                 let r = rng.gen_range(0.0..1.0);
 
@@ -301,6 +303,54 @@ impl Comp {
             .next()
     }
 
+    /// 艦隊全体を対象とした式の名前空間
+    ///
+    /// 連合艦隊の場合は第一艦隊と第二艦隊の両方を対象とする
+    pub fn ns(&self) -> impl EvalNamespace + '_ {
+        |name: &str, args: Vec<f64>| -> Option<f64> {
+            let members = || self.members_by(FleetType::Main | FleetType::Escort);
+            let count_by = |f: &dyn Fn(&Ship) -> bool| members().filter(|ship| f(ship)).count();
+            let drum_id = gear_id!("ドラム缶(輸送用)");
+
+            let result = match name {
+                "ship_count" => count_by(&|_| true) as f64,
+                "count_ship_id" => count_by(&|ship| args.contains(&ship.ship_id.into())) as f64,
+                "count_ship_type" => {
+                    count_by(&|ship| args.contains(&(ship.ship_type as u8).into())) as f64
+                }
+                "count_ship_class" => count_by(&|ship| args.contains(&ship.ctype.into())) as f64,
+                "count_gear_id" => members()
+                    .map(|ship| {
+                        ship.gears
+                            .count_by(|gear| args.contains(&gear.gear_id.into()))
+                    })
+                    .sum::<usize>() as f64,
+                "flagship_ship_type" => (self.main.ships.get(0)?.ship_type as u8).into(),
+
+                "speed" => self.speed().into(),
+                "elos" => self.elos(*args.first()? as u8)?,
+                "transport_point" => self.transport_point().into(),
+                "drum_count" => members()
+                    .map(|ship| ship.gears.count(drum_id))
+                    .sum::<usize>() as f64,
+                "drum_ship_count" => count_by(&|ship| ship.gears.has(drum_id)) as f64,
+
+                "is_combined" => bool_to_f64!(self.is_combined()),
+                "is_carrier_task_force" => {
+                    bool_to_f64!(self.org_type == OrgType::CarrierTaskForce)
+                }
+                "is_surface_task_force" => {
+                    bool_to_f64!(self.org_type == OrgType::SurfaceTaskForce)
+                }
+                "is_transport_escort" => bool_to_f64!(self.org_type == OrgType::TransportEscort),
+
+                _ => return None,
+            };
+
+            Some(result)
+        }
+    }
+
     pub fn reset_battle_state(&mut self) {
         self.main.reset_battle_state();
         if let Some(f) = self.escort.as_mut() {
@@ -319,6 +369,36 @@ impl Comp {
         self.escort.is_some()
     }
 
+    /// 艦隊速力
+    ///
+    /// 連合艦隊の場合は第一艦隊と第二艦隊で最も遅い艦の速力
+    pub fn speed(&self) -> u8 {
+        self.members_by(FleetType::Main | FleetType::Escort)
+            .map(|ship| ship.speed())
+            .min()
+            .unwrap_or_default()
+    }
+
+    pub fn speed_report(&self, target: Speed) -> SpeedReport {
+        let suggestions = self
+            .members_by(FleetType::Main | FleetType::Escort)
+            .filter(|member| member.speed_class() < target)
+            .map(|member| ShipSpeedSuggestion {
+                role: member.position.fleet_type.into(),
+                index: member.position.index,
+                ship_id: member.ship_id,
+                speed: member.speed_class(),
+                gears: member.speed_gear_set(target),
+            })
+            .collect();
+
+        SpeedReport {
+            speed: Speed::from_value(self.speed()),
+            target,
+            suggestions,
+        }
+    }
+
     pub fn is_player(&self) -> bool {
         self.org_type.is_player()
     }
@@ -11,3 +11,21 @@ pub enum CalculationError {
 #[derive(Debug, Error, PartialEq, Eq)]
 #[error("TryFromOrgTypeError")]
 pub struct TryFromOrgTypeError;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Unknown ship id: {0}")]
+    UnknownShip(u16),
+    #[error("Unknown gear id: {0}")]
+    UnknownGear(u16),
+    #[error("Invalid master data: {0}")]
+    InvalidMasterData(String),
+    #[error("Invalid share code: {0}")]
+    InvalidShareCode(&'static str),
+    #[error("Unsupported share code version: {0}")]
+    UnsupportedVersion(u8),
+}
@@ -1,5 +1,6 @@
 use std::ops::{Add, Div, Mul, Sub};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Estimation<T> {
     Just(T),
     Rough(T),
@@ -9,6 +10,37 @@ pub enum Estimation<T> {
 use Estimation::*;
 
 impl<T> Estimation<T> {
+    /// 値が不明な場合は代替値を概算値として用いる
+    pub fn with_fallback(value: Option<T>, fallback: impl FnOnce() -> Option<T>) -> Self {
+        match value {
+            Some(x) => Just(x),
+            None => fallback().map_or(Unknown, Rough),
+        }
+    }
+
+    #[inline]
+    pub fn value(self) -> Option<T> {
+        match self {
+            Just(x) | Rough(x) => Some(x),
+            Unknown => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_rough(&self) -> bool {
+        matches!(self, Rough(_))
+    }
+
+    /// 値と概算値かどうかの組
+    #[inline]
+    pub fn value_and_rough(self) -> Option<(T, bool)> {
+        match self {
+            Just(x) => Some((x, false)),
+            Rough(x) => Some((x, true)),
+            Unknown => None,
+        }
+    }
+
     #[inline]
     pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Estimation<U> {
         match self {
@@ -22,10 +54,25 @@ impl<T> Estimation<T> {
     pub fn and_then<U, F: FnOnce(T) -> Estimation<U>>(self, f: F) -> Estimation<U> {
         match self {
             Just(x) => f(x),
-            Rough(x) => f(x),
+            Rough(x) => match f(x) {
+                Just(y) => Rough(y),
+                other => other,
+            },
             Unknown => Unknown,
         }
     }
+
+    /// 両方が判明している場合のみ値を持ち、どちらかが概算値なら概算値となる
+    #[inline]
+    pub fn zip<U>(self, other: Estimation<U>) -> Estimation<(T, U)> {
+        self.and_then(|x| other.map(|y| (x, y)))
+    }
+}
+
+impl<T> From<Option<T>> for Estimation<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Unknown, Just)
+    }
 }
 
 macro_rules! impl_ops {
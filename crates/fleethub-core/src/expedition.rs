@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    fleet::Fleet,
+    types::{gear_id, MoraleState, ShipType},
+};
+
+/// 大成功の判定方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum ExpeditionGreatSuccessType {
+    /// 全艦キラ付けが必要な通常遠征
+    #[default]
+    Regular,
+    /// ドラム缶の数で大成功率が変動する遠征
+    Drum,
+    /// 旗艦のレベルで大成功率が変動する遠征
+    FlagshipLevel,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+pub struct ExpeditionShipTypeCount {
+    pub types: Vec<ShipType>,
+    pub count: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+#[serde(default)]
+pub struct ExpeditionDef {
+    pub id: u16,
+    pub name: String,
+    /// 所要時間(分)
+    pub duration: u32,
+    /// 燃料, 弾薬, 鋼材, ボーキサイト
+    pub resources: [i32; 4],
+    pub ship_count: usize,
+    pub flagship_level: u16,
+    pub total_level: u16,
+    /// 空の場合は旗艦の艦種を問わない
+    pub flagship_types: Vec<ShipType>,
+    /// いずれかの編成を満たす必要がある
+    pub compositions: Vec<Vec<ExpeditionShipTypeCount>>,
+    pub firepower: Option<u16>,
+    pub anti_air: Option<u16>,
+    pub asw: Option<u16>,
+    pub los: Option<u16>,
+    pub drum_count: usize,
+    pub drum_ship_count: usize,
+    pub great_success_type: ExpeditionGreatSuccessType,
+    /// 大成功率が上昇するドラム缶の数
+    pub great_success_drum_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub enum ExpeditionCondition {
+    ShipCount,
+    FlagshipLevel,
+    TotalLevel,
+    FlagshipType,
+    Composition,
+    Firepower,
+    AntiAir,
+    Asw,
+    Los,
+    DrumCount,
+    DrumShipCount,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct ExpeditionShortfall {
+    pub condition: ExpeditionCondition,
+    pub current: f64,
+    pub required: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct ExpeditionReport {
+    pub expedition_id: u16,
+    pub qualifies: bool,
+    pub shortfalls: Vec<ExpeditionShortfall>,
+    pub sparkle_count: usize,
+    pub great_success_rate: f64,
+    /// 大発動艇などによる資源ボーナス
+    pub bonus: f64,
+    /// 1時間あたりの期待獲得資源
+    pub resources_per_hour: [f64; 4],
+}
+
+impl ExpeditionDef {
+    pub fn check(&self, fleet: &Fleet) -> ExpeditionReport {
+        let shortfalls = self.shortfalls(fleet);
+        let qualifies = shortfalls.is_empty();
+        let sparkle_count = fleet
+            .ships
+            .count_by(|ship| ship.morale_state() == MoraleState::Sparkle);
+
+        let great_success_rate = if qualifies {
+            self.great_success_rate(fleet, sparkle_count)
+        } else {
+            0.0
+        };
+
+        let bonus = fleet.expedition_bonus();
+        let hours = self.duration.max(1) as f64 / 60.0;
+
+        let resources_per_hour = if qualifies {
+            self.resources.map(|value| {
+                value as f64 * (1.0 + bonus) * (1.0 + 0.5 * great_success_rate) / hours
+            })
+        } else {
+            [0.0; 4]
+        };
+
+        ExpeditionReport {
+            expedition_id: self.id,
+            qualifies,
+            shortfalls,
+            sparkle_count,
+            great_success_rate,
+            bonus,
+            resources_per_hour,
+        }
+    }
+
+    fn shortfalls(&self, fleet: &Fleet) -> Vec<ExpeditionShortfall> {
+        let mut shortfalls = vec![];
+
+        let mut check = |condition, current: f64, required: f64| {
+            if current < required {
+                shortfalls.push(ExpeditionShortfall {
+                    condition,
+                    current,
+                    required,
+                })
+            }
+        };
+
+        let ships = fleet.ships.values().collect::<Vec<_>>();
+        let flagship = fleet.ships.get(0);
+
+        check(
+            ExpeditionCondition::ShipCount,
+            ships.len() as f64,
+            self.ship_count as f64,
+        );
+        check(
+            ExpeditionCondition::FlagshipLevel,
+            flagship.map_or(0, |ship| ship.level) as f64,
+            self.flagship_level as f64,
+        );
+        check(
+            ExpeditionCondition::TotalLevel,
+            fleet.sum_ship_stat_by("level").unwrap_or_default(),
+            self.total_level as f64,
+        );
+
+        if !self.flagship_types.is_empty() {
+            let matches =
+                flagship.is_some_and(|ship| self.flagship_types.contains(&ship.ship_type));
+            check(ExpeditionCondition::FlagshipType, matches as u8 as f64, 1.0);
+        }
+
+        if !self.compositions.is_empty() {
+            let matches = self.compositions.iter().any(|composition| {
+                composition.iter().all(|requirement| {
+                    let count = ships
+                        .iter()
+                        .filter(|ship| requirement.types.contains(&ship.ship_type))
+                        .count();
+                    count >= requirement.count
+                })
+            });
+            check(ExpeditionCondition::Composition, matches as u8 as f64, 1.0);
+        }
+
+        [
+            (ExpeditionCondition::Firepower, "firepower", self.firepower),
+            (ExpeditionCondition::AntiAir, "anti_air", self.anti_air),
+            (ExpeditionCondition::Asw, "asw", self.asw),
+            (ExpeditionCondition::Los, "los", self.los),
+        ]
+        .into_iter()
+        .for_each(|(condition, key, required)| {
+            if let Some(required) = required {
+                let current = fleet.sum_ship_stat_by(key).unwrap_or_default();
+                check(condition, current, required as f64);
+            }
+        });
+
+        let drum_id = gear_id!("ドラム缶(輸送用)");
+
+        check(
+            ExpeditionCondition::DrumCount,
+            fleet.ships.sum_by(|ship| ship.gears.count(drum_id)) as f64,
+            self.drum_count as f64,
+        );
+        check(
+            ExpeditionCondition::DrumShipCount,
+            fleet.ships.count_by(|ship| ship.gears.has(drum_id)) as f64,
+            self.drum_ship_count as f64,
+        );
+
+        shortfalls
+    }
+
+    /// 大成功率
+    ///
+    /// キラ付け艦数による近似式で、運などの未解明な要素は考慮しない
+    fn great_success_rate(&self, fleet: &Fleet, sparkle_count: usize) -> f64 {
+        let ship_count = fleet.ships.values().count();
+        let sparkle_term = 20.0 + 15.0 * sparkle_count as f64;
+
+        let flagship = match fleet.ships.get(0) {
+            Some(ship) => ship,
+            None => return 0.0,
+        };
+
+        let percent = match self.great_success_type {
+            ExpeditionGreatSuccessType::Regular => {
+                if sparkle_count < ship_count {
+                    return 0.0;
+                }
+
+                sparkle_term
+            }
+            ExpeditionGreatSuccessType::Drum => {
+                if flagship.morale_state() != MoraleState::Sparkle {
+                    return 0.0;
+                }
+
+                let drum_count = fleet
+                    .ships
+                    .sum_by(|ship| ship.gears.count(gear_id!("ドラム缶(輸送用)")));
+
+                let drum_bonus = match self.great_success_drum_count {
+                    Some(required) if drum_count >= required => 20.0,
+                    _ => -15.0,
+                };
+
+                sparkle_term + drum_bonus
+            }
+            ExpeditionGreatSuccessType::FlagshipLevel => {
+                if flagship.morale_state() != MoraleState::Sparkle {
+                    return 0.0;
+                }
+
+                let level = flagship.level as f64;
+                let level_bonus = (level.sqrt() + level / 10.0).floor();
+
+                sparkle_term - 4.0 + level_bonus
+            }
+        };
+
+        (percent / 100.0).clamp(0.0, 1.0)
+    }
+}
@@ -42,11 +42,7 @@ impl Factory {
         let state = input?;
         let hash = self.make_hash(&state);
 
-        let master_gear = self
-            .master_data
-            .gears
-            .iter()
-            .find(|mg| mg.gear_id == state.gear_id)?;
+        let master_gear = self.master_data.get_gear(state.gear_id)?;
 
         let ibonuses = self
             .master_data
@@ -65,21 +61,33 @@ impl Factory {
             .map(|g| self.create_gear(g.cloned()))
             .collect::<GearArray>();
 
-        let master_ship = self
-            .master_data
-            .ships
-            .iter()
-            .find(|ship| ship.ship_id == state.ship_id)?;
+        let master_ship = self.master_data.get_ship(state.ship_id)?;
 
-        let equippability = self.master_data.create_ship_equippability(master_ship);
+        let level = state.level.unwrap_or_else(|| master_ship.default_level());
+        let equippability = self
+            .master_data
+            .create_ship_equippability(master_ship, level);
 
         let ebonuses: EBonuses = if gears.has_by(|gear| !gear.is_abyssal()) {
-            EBonuses::new(master_ship, &gears)
+            self.master_data.get_ebonuses(master_ship, &gears)
         } else {
             Default::default()
         };
 
-        let ship = Ship::new(hash, state, master_ship, equippability, gears, ebonuses);
+        let fallback_stats = self.master_data.get_fallback_stats(master_ship);
+
+        let mut ship = Ship::new(
+            hash,
+            state,
+            master_ship,
+            equippability,
+            gears,
+            ebonuses,
+            fallback_stats,
+        );
+
+        ship.special_enemy_modifiers = self.master_data.special_enemy_modifiers.clone();
+        ship.gunfit_accuracy_bonus = self.master_data.gunfit_accuracy.eval(&ship);
 
         Some(ship)
     }
@@ -227,11 +235,7 @@ impl Factory {
     }
 
     pub fn create_ship_state_by_id(&self, ship_id: u16) -> Option<ShipState> {
-        let master_ship = self
-            .master_data
-            .ships
-            .iter()
-            .find(|ship| ship.ship_id == ship_id)?;
+        let master_ship = self.master_data.get_ship(ship_id)?;
 
         let state = if master_ship.is_abyssal() {
             let stock = &master_ship.stock;
@@ -46,6 +46,15 @@ impl Fleet {
         }
     }
 
+    /// 艦隊速力
+    pub fn speed(&self) -> u8 {
+        self.ships
+            .values()
+            .map(|ship| ship.speed())
+            .min()
+            .unwrap_or_default()
+    }
+
     pub fn ship_keys(&self) -> Vec<JsString> {
         (0..self.len)
             .map(|i| format!("s{}", i + 1).into())
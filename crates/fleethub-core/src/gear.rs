@@ -435,6 +435,21 @@ impl Gear {
         }
     }
 
+    /// 輸送物資量(TP)
+    pub fn transport_point(&self) -> i32 {
+        if self.gear_type == GearType::LandingCraft {
+            8
+        } else if self.gear_type == GearType::CombatRation {
+            1
+        } else if self.gear_id == gear_id!("ドラム缶(輸送用)") {
+            5
+        } else if self.gear_id == gear_id!("特二式内火艇") {
+            2
+        } else {
+            0
+        }
+    }
+
     pub fn can_be_deployed_to_land_base(&self) -> bool {
         self.has_proficiency()
     }
@@ -554,6 +569,21 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_ns() {
+        let gear = Gear {
+            gear_id: 1,
+            attrs: GearAttr::MainGun.into(),
+            ..Default::default()
+        };
+        let mut ns = gear.ns();
+        let mut lookup = |name: &str, args: Vec<f64>| ns.lookup(name, args, &mut String::new());
+
+        assert_eq!(lookup("MainGun", vec![]), Some(1.0));
+        assert_eq!(lookup("Radar", vec![]), Some(0.0));
+        assert_eq!(lookup("has_gear_attr", vec![0.0]), None);
+    }
+
     #[test]
     fn test_ace() {
         let gear = Gear::default();
@@ -0,0 +1,27 @@
+mod battle_api;
+mod deck_builder;
+mod member_api;
+mod share_code;
+mod start2;
+
+pub use battle_api::*;
+pub use deck_builder::*;
+pub use member_api::*;
+pub use share_code::*;
+pub use start2::*;
+
+/// 熟練度から内部熟練度への変換
+///
+/// 各熟練度の最小値とし、最大の熟練度は内部熟練度の上限とする
+fn alv_to_exp(alv: u8) -> u8 {
+    match alv {
+        0 => 0,
+        1 => 10,
+        2 => 25,
+        3 => 40,
+        4 => 55,
+        5 => 70,
+        6 => 85,
+        _ => 120,
+    }
+}
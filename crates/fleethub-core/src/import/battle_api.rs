@@ -0,0 +1,694 @@
+use enumset::EnumSet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tsify::Tsify;
+
+use crate::{
+    error::ImportError,
+    types::{DayCutin, NightCutin, OrgType, Side},
+};
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiHougeki {
+    api_at_eflag: Vec<u8>,
+    api_at_list: Vec<i32>,
+    /// 昼戦の攻撃種別
+    api_at_type: Vec<i32>,
+    /// 夜戦の攻撃種別
+    api_sp_list: Vec<i32>,
+    api_df_list: Vec<Vec<i32>>,
+    api_cl_list: Vec<Vec<i32>>,
+    api_damage: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiRaigeki {
+    api_fdam: Vec<f64>,
+    api_edam: Vec<f64>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiKouku {
+    api_stage3: Option<ApiRaigeki>,
+    api_stage3_combined: Option<ApiRaigeki>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiSupportInfo {
+    api_support_airatack: Option<ApiKouku>,
+    api_support_hourai: Option<ApiRaigeki>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiFriendlyBattle {
+    api_hougeki: Option<ApiHougeki>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+struct ApiBattle {
+    api_ship_ke: Vec<i32>,
+    api_ship_ke_combined: Vec<i32>,
+    api_formation: Vec<i32>,
+
+    // 一部の敵はHPが "N/A" となる
+    api_f_nowhps: Vec<Value>,
+    api_f_maxhps: Vec<Value>,
+    api_f_nowhps_combined: Vec<Value>,
+    api_f_maxhps_combined: Vec<Value>,
+    api_e_nowhps: Vec<Value>,
+    api_e_maxhps: Vec<Value>,
+    api_e_nowhps_combined: Vec<Value>,
+    api_e_maxhps_combined: Vec<Value>,
+
+    /// 夜昼戦の夜戦支援と砲撃戦
+    api_n_support_info: Option<ApiSupportInfo>,
+    api_n_hougeki1: Option<ApiHougeki>,
+    api_n_hougeki2: Option<ApiHougeki>,
+
+    api_air_base_injection: Option<ApiKouku>,
+    api_injection_kouku: Option<ApiKouku>,
+    api_air_base_attack: Vec<ApiKouku>,
+    api_friendly_kouku: Option<ApiKouku>,
+    api_kouku: Option<ApiKouku>,
+    api_support_info: Option<ApiSupportInfo>,
+    api_kouku2: Option<ApiKouku>,
+    api_opening_taisen: Option<ApiHougeki>,
+    api_opening_atack: Option<ApiRaigeki>,
+    api_hougeki1: Option<ApiHougeki>,
+    api_hougeki2: Option<ApiHougeki>,
+    api_hougeki3: Option<ApiHougeki>,
+    api_raigeki: Option<ApiRaigeki>,
+
+    api_friendly_battle: Option<ApiFriendlyBattle>,
+    api_hougeki: Option<ApiHougeki>,
+}
+
+/// 昼戦の砲撃戦・雷撃戦
+#[derive(Debug, Clone, Copy)]
+enum DayCombatStep {
+    Hougeki1,
+    Hougeki2,
+    Hougeki3,
+    Raigeki,
+}
+
+impl DayCombatStep {
+    /// 自艦隊と敵艦隊の種別に応じた処理順
+    fn order(org_type: OrgType, enemy_combined: bool) -> [Self; 4] {
+        use DayCombatStep::*;
+
+        match org_type {
+            OrgType::CarrierTaskForce | OrgType::TransportEscort if enemy_combined => {
+                [Hougeki1, Hougeki2, Raigeki, Hougeki3]
+            }
+            OrgType::CarrierTaskForce | OrgType::TransportEscort => {
+                [Hougeki1, Raigeki, Hougeki2, Hougeki3]
+            }
+            OrgType::SurfaceTaskForce => [Hougeki1, Hougeki2, Hougeki3, Raigeki],
+            _ if enemy_combined => [Hougeki1, Raigeki, Hougeki2, Hougeki3],
+            _ => [Hougeki1, Hougeki2, Hougeki3, Raigeki],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum BattleRecordPhase {
+    OpeningAsw,
+    DayShelling,
+    NightShelling,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum BattleHitType {
+    Miss,
+    Normal,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct BattleHitRecord {
+    pub target_index: usize,
+    pub hit_type: BattleHitType,
+    pub damage: u16,
+    /// 旗艦庇護が発生したか
+    pub protected: bool,
+    /// 攻撃を受ける直前の耐久
+    pub hp_before: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct BattleAttackRecord {
+    pub phase: BattleRecordPhase,
+    pub attacker_side: Side,
+    pub attacker_index: usize,
+    /// api_at_type または api_sp_list の値
+    pub attack_type: i32,
+    pub hits: Vec<BattleHitRecord>,
+}
+
+impl BattleAttackRecord {
+    /// 特殊攻撃(艦隊カットイン)
+    ///
+    /// 200番台は瑞雲系のカットイン
+    pub fn is_special(&self) -> bool {
+        self.attack_type >= 100 && !(200..300).contains(&self.attack_type)
+    }
+
+    pub fn is_cutin(&self) -> bool {
+        self.attack_type != 0
+    }
+
+    /// api_at_type に該当する昼戦カットイン
+    ///
+    /// 通常攻撃は空集合、判別できない攻撃種別は None となる
+    pub fn day_cutins(&self) -> Option<EnumSet<DayCutin>> {
+        let set = match self.attack_type {
+            0 => EnumSet::empty(),
+            2 => DayCutin::DoubleAttack.into(),
+            3 => DayCutin::MainSec.into(),
+            4 => DayCutin::MainRadar.into(),
+            5 => DayCutin::MainAp.into(),
+            6 => DayCutin::MainMain.into(),
+            7 => DayCutin::FBA | DayCutin::BBA | DayCutin::BA,
+            200 => DayCutin::Zuiun.into(),
+            201 => DayCutin::AirSea.into(),
+            _ => return None,
+        };
+
+        Some(set)
+    }
+
+    /// api_sp_list に該当する夜戦カットイン
+    ///
+    /// 通常攻撃は空集合、判別できない攻撃種別は None となる
+    pub fn night_cutins(&self) -> Option<EnumSet<NightCutin>> {
+        let set = match self.attack_type {
+            0 => EnumSet::empty(),
+            1 => NightCutin::DoubleAttack.into(),
+            2 => NightCutin::TorpTorpMain.into(),
+            3 => NightCutin::TorpTorpTorp.into(),
+            4 => NightCutin::MainMainSec.into(),
+            5 => NightCutin::MainMainMain.into(),
+            6 => {
+                NightCutin::Cvci1_25
+                    | NightCutin::Cvci1_20
+                    | NightCutin::Cvci1_18
+                    | NightCutin::Photobomber
+            }
+            7 => NightCutin::MainTorpRadar.into(),
+            8 => NightCutin::TorpLookoutRadar.into(),
+            9 => NightCutin::TorpTsloTorp.into(),
+            10 => NightCutin::TorpTsloDrum.into(),
+            11 => NightCutin::SubRadarTorp.into(),
+            12 => NightCutin::SubTorpTorp.into(),
+            200 => {
+                NightCutin::NightZuiun2Radar
+                    | NightCutin::NightZuiun2
+                    | NightCutin::NightZuiunRadar
+                    | NightCutin::NightZuiun
+            }
+            _ => return None,
+        };
+
+        Some(set)
+    }
+
+    pub fn target_side(&self) -> Side {
+        !self.attacker_side
+    }
+}
+
+/// 記録された戦闘APIのレスポンス
+///
+/// 基地航空隊や友軍艦隊、支援、航空戦、雷撃戦による被害は耐久の推移にのみ反映する。
+/// 各フェーズは自艦隊と敵艦隊の種別に応じてAPIの処理順で反映する。
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct BattleRecord {
+    pub enemy_ship_ids: Vec<u16>,
+    /// 自陣形, 敵陣形, 交戦形態
+    pub formation: Vec<i32>,
+    pub player_max_hp: Vec<u16>,
+    pub enemy_max_hp: Vec<u16>,
+    pub player_hp_before: Vec<u16>,
+    pub enemy_hp_before: Vec<u16>,
+    pub player_hp_after: Vec<u16>,
+    pub enemy_hp_after: Vec<u16>,
+    pub attacks: Vec<BattleAttackRecord>,
+}
+
+/// 随伴艦隊は主力艦隊の6隻の後ろに並べる
+fn hp_list(main: &[Value], combined: &[Value]) -> Vec<u16> {
+    let to_hp = |value: &Value| {
+        value
+            .as_i64()
+            .and_then(|hp| u16::try_from(hp).ok())
+            .unwrap_or_default()
+    };
+
+    let mut list = main.iter().map(to_hp).collect::<Vec<_>>();
+
+    if !combined.is_empty() {
+        list.resize(list.len().max(6), 0);
+        list.extend(combined.iter().map(to_hp));
+    }
+
+    list
+}
+
+fn ship_ids(main: &[i32], combined: &[i32]) -> Vec<u16> {
+    main.iter()
+        .chain(combined)
+        .filter_map(|&id| u16::try_from(id).ok())
+        .collect()
+}
+
+fn split_damage(value: f64) -> (u16, bool) {
+    let damage = value.max(0.0).floor();
+    let protected = value - damage > 0.05;
+    (damage as u16, protected)
+}
+
+struct HpTracker {
+    player: Vec<u16>,
+    enemy: Vec<u16>,
+}
+
+impl HpTracker {
+    fn get_mut(&mut self, side: Side) -> &mut Vec<u16> {
+        match side {
+            Side::Player => &mut self.player,
+            Side::Enemy => &mut self.enemy,
+        }
+    }
+
+    fn apply(&mut self, side: Side, index: usize, damage: u16) -> u16 {
+        let list = self.get_mut(side);
+
+        if list.len() <= index {
+            list.resize(index + 1, 0);
+        }
+
+        let hp_before = list[index];
+        list[index] = hp_before.saturating_sub(damage);
+        hp_before
+    }
+
+    fn apply_list(&mut self, side: Side, damages: &[f64]) {
+        damages.iter().enumerate().for_each(|(index, &value)| {
+            let (damage, _) = split_damage(value);
+
+            if damage > 0 {
+                self.apply(side, index, damage);
+            }
+        });
+    }
+
+    fn apply_raigeki(&mut self, raigeki: &Option<ApiRaigeki>) {
+        if let Some(raigeki) = raigeki {
+            self.apply_list(Side::Player, &raigeki.api_fdam);
+            self.apply_list(Side::Enemy, &raigeki.api_edam);
+        }
+    }
+
+    fn apply_kouku(&mut self, kouku: &Option<ApiKouku>) {
+        self.apply_kouku_to(kouku.as_ref(), &[Side::Player, Side::Enemy]);
+    }
+
+    /// `sides` の被害のみ反映する
+    fn apply_kouku_to(&mut self, kouku: Option<&ApiKouku>, sides: &[Side]) {
+        let Some(kouku) = kouku else {
+            return;
+        };
+
+        // 連合艦隊側の被害は随伴艦隊の位置に反映する
+        let offset = |damages: &[f64], len: usize| {
+            std::iter::repeat_n(0.0, len)
+                .chain(damages.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+
+        for (stage3, len) in [(&kouku.api_stage3, 0), (&kouku.api_stage3_combined, 6)] {
+            if let Some(stage3) = stage3 {
+                for &side in sides {
+                    let damages = match side {
+                        Side::Player => &stage3.api_fdam,
+                        Side::Enemy => &stage3.api_edam,
+                    };
+
+                    self.apply_list(side, &offset(damages, len));
+                }
+            }
+        }
+    }
+
+    /// 友軍艦隊の砲撃戦と夜戦支援、夜戦の砲撃戦
+    fn apply_night(
+        &mut self,
+        battle: &ApiBattle,
+        hougekis: &[&Option<ApiHougeki>],
+        attacks: &mut Vec<BattleAttackRecord>,
+    ) {
+        if let Some(friendly) = &battle.api_friendly_battle {
+            self.apply_friendly_hougeki(&friendly.api_hougeki);
+        }
+
+        self.apply_support(&battle.api_n_support_info);
+
+        for hougeki in hougekis {
+            self.apply_hougeki(BattleRecordPhase::NightShelling, hougeki, attacks);
+        }
+    }
+
+    fn apply_support(&mut self, support: &Option<ApiSupportInfo>) {
+        if let Some(support) = support {
+            self.apply_kouku(&support.api_support_airatack);
+            self.apply_raigeki(&support.api_support_hourai);
+        }
+    }
+
+    /// 友軍艦隊の攻撃による敵艦の被害のみ反映する
+    fn apply_friendly_hougeki(&mut self, hougeki: &Option<ApiHougeki>) {
+        let Some(hougeki) = hougeki else {
+            return;
+        };
+
+        for (i, targets) in hougeki.api_df_list.iter().enumerate() {
+            if hougeki.api_at_eflag.get(i) == Some(&1) {
+                continue;
+            }
+
+            let damages = hougeki.api_damage.get(i).cloned().unwrap_or_default();
+
+            for (&target_index, &value) in targets.iter().zip(&damages) {
+                if let Ok(target_index) = usize::try_from(target_index) {
+                    self.apply(Side::Enemy, target_index, split_damage(value).0);
+                }
+            }
+        }
+    }
+
+    fn apply_hougeki(
+        &mut self,
+        phase: BattleRecordPhase,
+        hougeki: &Option<ApiHougeki>,
+        attacks: &mut Vec<BattleAttackRecord>,
+    ) {
+        let hougeki = match hougeki {
+            Some(hougeki) => hougeki,
+            None => return,
+        };
+
+        let attack_types = if phase == BattleRecordPhase::NightShelling {
+            &hougeki.api_sp_list
+        } else {
+            &hougeki.api_at_type
+        };
+
+        for (i, &attacker_index) in hougeki.api_at_list.iter().enumerate() {
+            let attacker_index = match usize::try_from(attacker_index) {
+                Ok(index) => index,
+                Err(_) => continue,
+            };
+
+            let attacker_side = if hougeki.api_at_eflag.get(i) == Some(&1) {
+                Side::Enemy
+            } else {
+                Side::Player
+            };
+
+            let targets = hougeki.api_df_list.get(i).cloned().unwrap_or_default();
+            let criticals = hougeki.api_cl_list.get(i).cloned().unwrap_or_default();
+            let damages = hougeki.api_damage.get(i).cloned().unwrap_or_default();
+
+            let hits = targets
+                .iter()
+                .enumerate()
+                .filter_map(|(j, &target_index)| {
+                    let target_index = usize::try_from(target_index).ok()?;
+                    let (damage, protected) = split_damage(damages.get(j).cloned()?);
+
+                    let hit_type = match criticals.get(j) {
+                        Some(2) => BattleHitType::Critical,
+                        Some(1) => BattleHitType::Normal,
+                        _ => BattleHitType::Miss,
+                    };
+
+                    let hp_before = self.apply(!attacker_side, target_index, damage);
+
+                    Some(BattleHitRecord {
+                        target_index,
+                        hit_type,
+                        damage,
+                        protected,
+                        hp_before,
+                    })
+                })
+                .collect();
+
+            attacks.push(BattleAttackRecord {
+                phase,
+                attacker_side,
+                attacker_index,
+                attack_type: attack_types.get(i).cloned().unwrap_or_default(),
+                hits,
+            });
+        }
+    }
+}
+
+impl BattleRecord {
+    /// 昼戦、夜戦のレスポンスを読み込む
+    ///
+    /// 生のレスポンスと api_data のどちらも受け付ける。
+    /// `org_type` は自艦隊の種別で、連合艦隊の砲撃戦と雷撃戦の順序に用いる
+    pub fn from_api_json(json: &str, org_type: OrgType) -> Result<Self, ImportError> {
+        let mut value = serde_json::from_str::<Value>(json)?;
+
+        if let Some(data) = value.get_mut("api_data") {
+            value = data.take();
+        }
+
+        if !value.is_object() {
+            return Err(ImportError::UnsupportedFormat("api_data".to_string()));
+        }
+
+        let battle = serde_json::from_value::<ApiBattle>(value)?;
+        Ok(Self::from_api_battle(&battle, org_type))
+    }
+
+    fn from_api_battle(battle: &ApiBattle, org_type: OrgType) -> Self {
+        let player_hp_before = hp_list(&battle.api_f_nowhps, &battle.api_f_nowhps_combined);
+        let enemy_hp_before = hp_list(&battle.api_e_nowhps, &battle.api_e_nowhps_combined);
+
+        let mut tracker = HpTracker {
+            player: player_hp_before.clone(),
+            enemy: enemy_hp_before.clone(),
+        };
+        let mut attacks = vec![];
+
+        // 夜昼戦は夜戦から始まる
+        let night_to_day = battle.api_n_hougeki1.is_some() || battle.api_n_hougeki2.is_some();
+
+        if night_to_day {
+            tracker.apply_night(
+                battle,
+                &[&battle.api_n_hougeki1, &battle.api_n_hougeki2],
+                &mut attacks,
+            );
+        }
+
+        tracker.apply_kouku(&battle.api_air_base_injection);
+        tracker.apply_kouku(&battle.api_injection_kouku);
+
+        for kouku in &battle.api_air_base_attack {
+            tracker.apply_kouku_to(Some(kouku), &[Side::Enemy]);
+        }
+
+        tracker.apply_kouku_to(battle.api_friendly_kouku.as_ref(), &[Side::Enemy]);
+        tracker.apply_kouku(&battle.api_kouku);
+        tracker.apply_support(&battle.api_support_info);
+        tracker.apply_kouku(&battle.api_kouku2);
+
+        tracker.apply_hougeki(
+            BattleRecordPhase::OpeningAsw,
+            &battle.api_opening_taisen,
+            &mut attacks,
+        );
+        tracker.apply_raigeki(&battle.api_opening_atack);
+
+        let enemy_combined = !battle.api_e_nowhps_combined.is_empty();
+
+        for step in DayCombatStep::order(org_type, enemy_combined) {
+            let hougeki = match step {
+                DayCombatStep::Hougeki1 => &battle.api_hougeki1,
+                DayCombatStep::Hougeki2 => &battle.api_hougeki2,
+                DayCombatStep::Hougeki3 => &battle.api_hougeki3,
+                DayCombatStep::Raigeki => {
+                    tracker.apply_raigeki(&battle.api_raigeki);
+                    continue;
+                }
+            };
+
+            tracker.apply_hougeki(BattleRecordPhase::DayShelling, hougeki, &mut attacks);
+        }
+
+        if !night_to_day {
+            tracker.apply_night(battle, &[&battle.api_hougeki], &mut attacks);
+        }
+
+        Self {
+            enemy_ship_ids: ship_ids(&battle.api_ship_ke, &battle.api_ship_ke_combined),
+            formation: battle.api_formation.clone(),
+            player_max_hp: hp_list(&battle.api_f_maxhps, &battle.api_f_maxhps_combined),
+            enemy_max_hp: hp_list(&battle.api_e_maxhps, &battle.api_e_maxhps_combined),
+            player_hp_before,
+            enemy_hp_before,
+            player_hp_after: tracker.player,
+            enemy_hp_after: tracker.enemy,
+            attacks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_battle_record() {
+        let json = r#"{
+            "api_result": 1,
+            "api_data": {
+                "api_ship_ke": [1501, 1502],
+                "api_formation": [1, 1, 2],
+                "api_f_nowhps": [30, 20],
+                "api_f_maxhps": [31, 20],
+                "api_e_nowhps": [20, "N/A"],
+                "api_e_maxhps": [20, "N/A"],
+                "api_opening_atack": { "api_fdam": [0, 5], "api_edam": [4, 0] },
+                "api_hougeki": {
+                    "api_at_eflag": [0, 1],
+                    "api_at_list": [0, 0],
+                    "api_sp_list": [2, 0],
+                    "api_df_list": [[0, 0], [1]],
+                    "api_cl_list": [[2, 1], [1]],
+                    "api_damage": [[10, 30], [7.1]]
+                }
+            }
+        }"#;
+
+        let record = BattleRecord::from_api_json(json, OrgType::Single).unwrap();
+
+        assert_eq!(record.enemy_ship_ids, vec![1501, 1502]);
+        assert_eq!(record.enemy_hp_before, vec![20, 0]);
+        assert_eq!(record.attacks.len(), 2);
+
+        let attack = &record.attacks[0];
+        assert_eq!(attack.phase, BattleRecordPhase::NightShelling);
+        assert_eq!(attack.attacker_side, Side::Player);
+        assert!(attack.is_cutin());
+        assert_eq!(attack.night_cutins(), Some(NightCutin::TorpTorpMain.into()));
+        assert_eq!(attack.hits[0].hit_type, BattleHitType::Critical);
+        assert_eq!(attack.hits[0].hp_before, 16);
+        assert_eq!(attack.hits[1].hp_before, 6);
+
+        let attack = &record.attacks[1];
+        assert_eq!(attack.night_cutins(), Some(EnumSet::empty()));
+        assert_eq!(attack.target_side(), Side::Player);
+        assert_eq!(attack.hits[0].damage, 7);
+        assert!(attack.hits[0].protected);
+        assert_eq!(attack.hits[0].hp_before, 15);
+
+        assert_eq!(record.player_hp_after, vec![30, 8]);
+        assert_eq!(record.enemy_hp_after, vec![0, 0]);
+
+        assert!(BattleRecord::from_api_json("[]", OrgType::Single).is_err());
+    }
+
+    #[test]
+    fn test_battle_record_phase_order() {
+        let json = r#"{
+            "api_f_nowhps": [30],
+            "api_f_nowhps_combined": [30],
+            "api_e_nowhps": [40],
+            "api_air_base_injection": { "api_stage3": { "api_edam": [1] } },
+            "api_injection_kouku": { "api_stage3": { "api_fdam": [2], "api_edam": [1] } },
+            "api_air_base_attack": [
+                { "api_stage3": { "api_edam": [2] } },
+                { "api_stage3": { "api_edam": [3] } }
+            ],
+            "api_friendly_kouku": { "api_stage3": { "api_fdam": [9], "api_edam": [1] } },
+            "api_kouku": { "api_stage3_combined": { "api_fdam": [4] } },
+            "api_kouku2": { "api_stage3": { "api_edam": [2] } },
+            "api_hougeki1": {
+                "api_at_eflag": [0],
+                "api_at_list": [6],
+                "api_at_type": [0],
+                "api_df_list": [[0]],
+                "api_cl_list": [[1]],
+                "api_damage": [[5]]
+            },
+            "api_raigeki": { "api_fdam": [0, 0], "api_edam": [10] },
+            "api_hougeki2": {
+                "api_at_eflag": [0],
+                "api_at_list": [0],
+                "api_at_type": [0],
+                "api_df_list": [[0]],
+                "api_cl_list": [[1]],
+                "api_damage": [[3]]
+            }
+        }"#;
+
+        let hp_before = |org_type| {
+            let record = BattleRecord::from_api_json(json, org_type).unwrap();
+            assert_eq!(record.player_hp_after, vec![28, 0, 0, 0, 0, 0, 26]);
+            assert_eq!(record.enemy_hp_after, vec![12]);
+            record.attacks[1].hits[0].hp_before
+        };
+
+        // 空母機動部隊は第1砲撃戦の後に雷撃戦
+        assert_eq!(hp_before(OrgType::CarrierTaskForce), 15);
+        assert_eq!(hp_before(OrgType::TransportEscort), 15);
+        // 水上打撃部隊は砲撃戦の後に雷撃戦
+        assert_eq!(hp_before(OrgType::SurfaceTaskForce), 25);
+    }
+
+    #[test]
+    fn test_night_battle_record() {
+        let json = r#"{
+            "api_f_nowhps": [30],
+            "api_e_nowhps": [40, 40],
+            "api_friendly_battle": {
+                "api_hougeki": {
+                    "api_at_eflag": [0, 1],
+                    "api_at_list": [0, 0],
+                    "api_df_list": [[1], [0]],
+                    "api_damage": [[15], [20]]
+                }
+            },
+            "api_n_support_info": { "api_support_hourai": { "api_edam": [5, 0] } },
+            "api_hougeki": {
+                "api_at_eflag": [0],
+                "api_at_list": [0],
+                "api_sp_list": [0],
+                "api_df_list": [[0]],
+                "api_cl_list": [[1]],
+                "api_damage": [[10]]
+            }
+        }"#;
+
+        let record = BattleRecord::from_api_json(json, OrgType::Single).unwrap();
+
+        assert_eq!(record.attacks.len(), 1);
+        assert_eq!(record.attacks[0].hits[0].hp_before, 35);
+        assert_eq!(record.player_hp_after, vec![30]);
+        assert_eq!(record.enemy_hp_after, vec![25, 25]);
+    }
+}
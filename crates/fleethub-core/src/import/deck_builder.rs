@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+
+use crate::{
+    error::ImportError,
+    factory::Factory,
+    gear_array::into_gear_key,
+    types::{
+        AirSquadronMode, AirSquadronState, FleetState, GearState, GearVecState, OrgState, OrgType,
+        ShipState,
+    },
+};
+
+use super::alv_to_exp;
+
+const DECK_BUILDER_VERSION: u8 = 4;
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilderItem {
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub id: u16,
+    /// 改修値
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub rf: Option<u8>,
+    /// 熟練度
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub mas: Option<u8>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilderItems {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i1: Option<DeckBuilderItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i2: Option<DeckBuilderItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i3: Option<DeckBuilderItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i4: Option<DeckBuilderItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub i5: Option<DeckBuilderItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ix: Option<DeckBuilderItem>,
+}
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilderShip {
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub id: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub lv: Option<u16>,
+    /// 運 (-1は未設定)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub luck: Option<i16>,
+    /// 耐久
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub hp: Option<u16>,
+    /// 素の対潜
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub asw: Option<u16>,
+    pub items: DeckBuilderItems,
+}
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilderFleet {
+    /// 連合艦隊の種別
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub t: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s1: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s2: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s4: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s5: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s6: Option<DeckBuilderShip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s7: Option<DeckBuilderShip>,
+}
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilderAirBase {
+    /// 1: 出撃, 2: 防空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub mode: Option<u8>,
+    pub items: DeckBuilderItems,
+}
+
+/// デッキビルダー形式の編成
+///
+/// 数値は文字列で記録されていても受け付ける
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeckBuilder {
+    /// 文字列で出力するツールもあるため任意の値を許容する
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    pub hqlv: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub f1: Option<DeckBuilderFleet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub f2: Option<DeckBuilderFleet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub f3: Option<DeckBuilderFleet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub f4: Option<DeckBuilderFleet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a1: Option<DeckBuilderAirBase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a2: Option<DeckBuilderAirBase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a3: Option<DeckBuilderAirBase>,
+}
+
+fn convert<T, U>(
+    value: &Option<T>,
+    f: impl Fn(&T) -> Result<U, ImportError>,
+) -> Result<Option<U>, ImportError> {
+    value.as_ref().map(f).transpose()
+}
+
+impl DeckBuilderItem {
+    fn to_gear_state(&self, factory: &Factory) -> Result<GearState, ImportError> {
+        let known = factory
+            .master_data
+            .gears
+            .iter()
+            .any(|gear| gear.gear_id == self.id);
+
+        if !known {
+            return Err(ImportError::UnknownGear(self.id));
+        }
+
+        Ok(GearState {
+            id: None,
+            gear_id: self.id,
+            exp: self.mas.map(alv_to_exp),
+            stars: self.rf,
+        })
+    }
+
+    fn from_gear_state(factory: &Factory, state: &GearState) -> Result<Self, ImportError> {
+        let gear = factory
+            .create_gear(Some(state.clone()))
+            .ok_or(ImportError::UnknownGear(state.gear_id))?;
+
+        Ok(Self {
+            id: state.gear_id,
+            rf: state.stars,
+            mas: state.exp.map(|_| gear.ace()),
+        })
+    }
+}
+
+impl DeckBuilderItems {
+    /// 補強増設が空の場合、`slotnum` 番目の装備は補強増設として扱う
+    fn to_gear_vec_state(
+        &self,
+        factory: &Factory,
+        slotnum: Option<usize>,
+    ) -> Result<GearVecState, ImportError> {
+        let f = |item: &DeckBuilderItem| item.to_gear_state(factory);
+
+        let mut state = GearVecState {
+            g1: convert(&self.i1, f)?,
+            g2: convert(&self.i2, f)?,
+            g3: convert(&self.i3, f)?,
+            g4: convert(&self.i4, f)?,
+            g5: convert(&self.i5, f)?,
+            gx: convert(&self.ix, f)?,
+        };
+
+        if state.gx.is_none() {
+            if let Some(slot) = slotnum
+                .and_then(into_gear_key)
+                .and_then(|key| state.slot_mut(key))
+            {
+                state.gx = slot.take();
+            }
+        }
+
+        Ok(state)
+    }
+
+    fn from_gear_vec_state(factory: &Factory, state: &GearVecState) -> Result<Self, ImportError> {
+        let f = |gear: &GearState| DeckBuilderItem::from_gear_state(factory, gear);
+
+        Ok(Self {
+            i1: convert(&state.g1, f)?,
+            i2: convert(&state.g2, f)?,
+            i3: convert(&state.g3, f)?,
+            i4: convert(&state.g4, f)?,
+            i5: convert(&state.g5, f)?,
+            ix: convert(&state.gx, f)?,
+        })
+    }
+}
+
+impl DeckBuilderShip {
+    fn to_ship_state(&self, factory: &Factory) -> Result<ShipState, ImportError> {
+        // 運や耐久は実数値で記録されているので、補正なしの値との差分を求める
+        let base = factory
+            .create_ship(Some(ShipState {
+                ship_id: self.id,
+                level: self.lv,
+                ..Default::default()
+            }))
+            .ok_or(ImportError::UnknownShip(self.id))?;
+
+        let diff = |value: Option<i16>, base: Option<u16>| Some(value? - base? as i16);
+
+        Ok(ShipState {
+            ship_id: self.id,
+            level: self.lv,
+            luck_mod: diff(self.luck.filter(|&luck| luck >= 0), base.luck()),
+            max_hp_mod: diff(self.hp.map(|hp| hp as i16), base.max_hp()),
+            asw_mod: diff(self.asw.map(|asw| asw as i16), base.naked_asw()),
+            gears: self
+                .items
+                .to_gear_vec_state(factory, Some(base.slotnum()))?,
+            ..Default::default()
+        })
+    }
+
+    fn from_ship_state(factory: &Factory, state: &ShipState) -> Result<Self, ImportError> {
+        let ship = factory
+            .create_ship(Some(state.clone()))
+            .ok_or(ImportError::UnknownShip(state.ship_id))?;
+
+        let luck = state
+            .luck_mod
+            .and(ship.luck())
+            .map_or(-1, |luck| luck as i16);
+
+        Ok(Self {
+            id: state.ship_id,
+            lv: Some(ship.level),
+            luck: Some(luck),
+            hp: state.max_hp_mod.and(ship.max_hp()),
+            asw: state.asw_mod.and(ship.naked_asw()),
+            items: DeckBuilderItems::from_gear_vec_state(factory, &state.gears)?,
+        })
+    }
+}
+
+impl DeckBuilderFleet {
+    fn to_fleet_state(&self, factory: &Factory) -> Result<FleetState, ImportError> {
+        let f = |ship: &DeckBuilderShip| ship.to_ship_state(factory);
+
+        Ok(FleetState {
+            s1: convert(&self.s1, f)?,
+            s2: convert(&self.s2, f)?,
+            s3: convert(&self.s3, f)?,
+            s4: convert(&self.s4, f)?,
+            s5: convert(&self.s5, f)?,
+            s6: convert(&self.s6, f)?,
+            s7: convert(&self.s7, f)?,
+            ..Default::default()
+        })
+    }
+
+    fn from_fleet_state(factory: &Factory, state: &FleetState) -> Result<Self, ImportError> {
+        let f = |ship: &ShipState| DeckBuilderShip::from_ship_state(factory, ship);
+
+        Ok(Self {
+            t: None,
+            s1: convert(&state.s1, f)?,
+            s2: convert(&state.s2, f)?,
+            s3: convert(&state.s3, f)?,
+            s4: convert(&state.s4, f)?,
+            s5: convert(&state.s5, f)?,
+            s6: convert(&state.s6, f)?,
+            s7: convert(&state.s7, f)?,
+        })
+    }
+}
+
+impl DeckBuilderAirBase {
+    fn to_air_squadron_state(&self, factory: &Factory) -> Result<AirSquadronState, ImportError> {
+        let mode = match self.mode {
+            Some(1) => Some(AirSquadronMode::Sortie),
+            Some(2) => Some(AirSquadronMode::AirDefense),
+            _ => None,
+        };
+
+        Ok(AirSquadronState {
+            mode,
+            gears: self.items.to_gear_vec_state(factory, None)?,
+            ..Default::default()
+        })
+    }
+
+    fn from_air_squadron_state(
+        factory: &Factory,
+        state: &AirSquadronState,
+    ) -> Result<Self, ImportError> {
+        let mode = state.mode.map(|mode| match mode {
+            AirSquadronMode::Sortie => 1,
+            AirSquadronMode::AirDefense => 2,
+        });
+
+        Ok(Self {
+            mode,
+            items: DeckBuilderItems::from_gear_vec_state(factory, &state.gears)?,
+        })
+    }
+}
+
+fn org_type_from_deck_builder(t: u8) -> Option<OrgType> {
+    match t {
+        0 => Some(OrgType::Single),
+        1 => Some(OrgType::CarrierTaskForce),
+        2 => Some(OrgType::SurfaceTaskForce),
+        3 => Some(OrgType::TransportEscort),
+        _ => None,
+    }
+}
+
+fn org_type_to_deck_builder(org_type: OrgType) -> Option<u8> {
+    match org_type {
+        OrgType::Single => Some(0),
+        OrgType::CarrierTaskForce => Some(1),
+        OrgType::SurfaceTaskForce => Some(2),
+        OrgType::TransportEscort => Some(3),
+        _ => None,
+    }
+}
+
+impl DeckBuilder {
+    pub fn from_json(json: &str) -> Result<Self, ImportError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, ImportError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// 存在しない艦娘や装備が含まれている場合はエラーとなる
+    pub fn to_org_state(&self, factory: &Factory) -> Result<OrgState, ImportError> {
+        let fleet = |fleet: &DeckBuilderFleet| fleet.to_fleet_state(factory);
+        let air_squadron = |air_base: &DeckBuilderAirBase| air_base.to_air_squadron_state(factory);
+
+        let org_type = self
+            .f1
+            .as_ref()
+            .and_then(|fleet| fleet.t)
+            .and_then(org_type_from_deck_builder);
+
+        Ok(OrgState {
+            f1: convert(&self.f1, fleet)?,
+            f2: convert(&self.f2, fleet)?,
+            f3: convert(&self.f3, fleet)?,
+            f4: convert(&self.f4, fleet)?,
+            a1: convert(&self.a1, air_squadron)?,
+            a2: convert(&self.a2, air_squadron)?,
+            a3: convert(&self.a3, air_squadron)?,
+            hq_level: self.hqlv,
+            org_type,
+            ..Default::default()
+        })
+    }
+
+    pub fn from_org_state(factory: &Factory, state: &OrgState) -> Result<Self, ImportError> {
+        let fleet = |fleet: &FleetState| DeckBuilderFleet::from_fleet_state(factory, fleet);
+        let air_base = |air_squadron: &AirSquadronState| {
+            DeckBuilderAirBase::from_air_squadron_state(factory, air_squadron)
+        };
+
+        let mut f1 = convert(&state.f1, fleet)?;
+
+        if let Some(t) = state.org_type.and_then(org_type_to_deck_builder) {
+            f1.get_or_insert_with(Default::default).t = Some(t);
+        }
+
+        Ok(Self {
+            version: Some(DECK_BUILDER_VERSION.into()),
+            hqlv: state.hq_level,
+            f1,
+            f2: convert(&state.f2, fleet)?,
+            f3: convert(&state.f3, fleet)?,
+            f4: convert(&state.f4, fleet)?,
+            a1: convert(&state.a1, air_base)?,
+            a2: convert(&state.a2, air_base)?,
+            a3: convert(&state.a3, air_base)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_json_with_string_numbers() {
+        let deck = DeckBuilder::from_json(
+            r#"{
+                "version": "4",
+                "hqlv": "120",
+                "f1": {
+                    "s1": {
+                        "id": "277",
+                        "lv": "99",
+                        "luck": "-1",
+                        "items": { "i1": { "id": "21", "rf": "10", "mas": "7" } }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(deck.hqlv, Some(120));
+
+        let ship = deck.f1.unwrap().s1.unwrap();
+        assert_eq!(ship.id, 277);
+        assert_eq!(ship.lv, Some(99));
+        assert_eq!(ship.luck, Some(-1));
+        assert_eq!(
+            ship.items.i1,
+            Some(DeckBuilderItem {
+                id: 21,
+                rf: Some(10),
+                mas: Some(7),
+            })
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tsify::Tsify;
+
+use crate::{
+    error::ImportError,
+    master_data::{MasterData, MasterShip, StatInterval},
+    types::{GearState, GearVecState, ShipState, SlotSizeVecState},
+};
+
+use super::alv_to_exp;
+
+/// api_get_member/ship などに含まれる艦娘
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiShip {
+    pub api_id: u32,
+    pub api_ship_id: u16,
+    pub api_lv: u16,
+    pub api_nowhp: u16,
+    pub api_cond: u8,
+    pub api_fuel: u16,
+    pub api_bull: u16,
+    pub api_slot: Vec<i32>,
+    pub api_slot_ex: i32,
+    pub api_onslot: Vec<u8>,
+    /// 火力, 雷装, 対空, 装甲, 運, 耐久, 対潜
+    pub api_kyouka: Vec<i16>,
+}
+
+/// api_get_member/slot_item などに含まれる装備
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiSlotItem {
+    pub api_id: u32,
+    pub api_slotitem_id: u16,
+    /// 改修値
+    pub api_level: u8,
+    /// 熟練度
+    pub api_alv: Option<u8>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct PlayerInventory {
+    pub ships: Vec<ShipState>,
+    pub gears: Vec<GearState>,
+    /// マスターデータに存在しない艦娘ID
+    pub unknown_ship_ids: Vec<u16>,
+    /// マスターデータに存在しない装備ID
+    pub unknown_gear_ids: Vec<u16>,
+}
+
+/// 生のレスポンス、api_data、配列のいずれかから配列を取り出す
+fn extract_array(value: Value, key: &str) -> Result<Vec<Value>, ImportError> {
+    match value {
+        Value::Array(array) => Ok(array),
+        Value::Object(mut map) => {
+            if let Some(inner) = map.remove(key) {
+                return extract_array(inner, key);
+            }
+
+            if let Some(inner) = map.remove("api_data") {
+                return extract_array(inner, key);
+            }
+
+            Err(ImportError::UnsupportedFormat(key.to_string()))
+        }
+        _ => Err(ImportError::UnsupportedFormat(key.to_string())),
+    }
+}
+
+fn parse_array<T: serde::de::DeserializeOwned>(
+    json: &str,
+    key: &str,
+) -> Result<Vec<T>, ImportError> {
+    let value = serde_json::from_str::<Value>(json)?;
+
+    extract_array(value, key)?
+        .into_iter()
+        .map(|item| serde_json::from_value(item).map_err(ImportError::from))
+        .collect()
+}
+
+impl ApiSlotItem {
+    fn to_gear_state(&self) -> GearState {
+        GearState {
+            id: Some(self.api_id.to_string()),
+            gear_id: self.api_slotitem_id,
+            exp: self.api_alv.map(alv_to_exp),
+            stars: Some(self.api_level),
+        }
+    }
+}
+
+impl ApiShip {
+    fn to_ship_state(&self, master: &MasterShip, gears: &HashMap<u32, GearState>) -> ShipState {
+        let kyouka = |index: usize| self.api_kyouka.get(index).cloned().unwrap_or_default();
+
+        // 火力などは最大値からの差分で保持する
+        let mod_from_max = |stat: &StatInterval, value: i16| {
+            let (min, max) = stat.zipped()?;
+            let diff = min as i16 + value - max as i16;
+            (diff != 0).then_some(diff)
+        };
+
+        let non_zero = |value: i16| (value != 0).then_some(value);
+
+        let get_gear = |id: i32| {
+            u32::try_from(id)
+                .ok()
+                .and_then(|id| gears.get(&id))
+                .cloned()
+        };
+
+        let slot_gear = |index: usize| self.api_slot.get(index).and_then(|&id| get_gear(id));
+        let slot_size = |index: usize| {
+            (index < master.slotnum)
+                .then(|| self.api_onslot.get(index).cloned())
+                .flatten()
+        };
+
+        ShipState {
+            id: Some(self.api_id.to_string()),
+            ship_id: self.api_ship_id,
+            level: Some(self.api_lv),
+            current_hp: Some(self.api_nowhp),
+            morale: Some(self.api_cond),
+            ammo: Some(self.api_bull),
+            fuel: Some(self.api_fuel),
+
+            firepower_mod: mod_from_max(&master.firepower, kyouka(0)),
+            torpedo_mod: mod_from_max(&master.torpedo, kyouka(1)),
+            anti_air_mod: mod_from_max(&master.anti_air, kyouka(2)),
+            armor_mod: mod_from_max(&master.armor, kyouka(3)),
+            luck_mod: non_zero(kyouka(4)),
+            max_hp_mod: non_zero(kyouka(5)),
+            asw_mod: non_zero(kyouka(6)),
+
+            gears: GearVecState {
+                g1: slot_gear(0),
+                g2: slot_gear(1),
+                g3: slot_gear(2),
+                g4: slot_gear(3),
+                g5: slot_gear(4),
+                gx: get_gear(self.api_slot_ex),
+            },
+            slots: SlotSizeVecState {
+                ss1: slot_size(0),
+                ss2: slot_size(1),
+                ss3: slot_size(2),
+                ss4: slot_size(3),
+                ss5: slot_size(4),
+            },
+
+            ..Default::default()
+        }
+    }
+}
+
+impl PlayerInventory {
+    /// 艦娘と装備の一覧から所持状態を作成する
+    pub fn new(master_data: &MasterData, ships: &[ApiShip], slot_items: &[ApiSlotItem]) -> Self {
+        let mut unknown_gear_ids = vec![];
+
+        let gears = slot_items
+            .iter()
+            .filter(|item| {
+                let known = master_data
+                    .gears
+                    .iter()
+                    .any(|gear| gear.gear_id == item.api_slotitem_id);
+
+                if !known {
+                    unknown_gear_ids.push(item.api_slotitem_id);
+                }
+
+                known
+            })
+            .map(|item| (item.api_id, item.to_gear_state()))
+            .collect::<HashMap<_, _>>();
+
+        let mut unknown_ship_ids = vec![];
+
+        let ships = ships
+            .iter()
+            .filter_map(|ship| {
+                let master = master_data
+                    .ships
+                    .iter()
+                    .find(|master| master.ship_id == ship.api_ship_id);
+
+                if master.is_none() {
+                    unknown_ship_ids.push(ship.api_ship_id);
+                }
+
+                Some(ship.to_ship_state(master?, &gears))
+            })
+            .collect();
+
+        let mut gears = gears.into_values().collect::<Vec<_>>();
+        gears.sort_by_key(|gear| gear.id.as_ref().and_then(|id| id.parse::<u32>().ok()));
+
+        Self {
+            ships,
+            gears,
+            unknown_ship_ids,
+            unknown_gear_ids,
+        }
+    }
+
+    /// api_get_member/ship2 や api_port/port の api_ship と api_get_member/slot_item のJSONから作成する
+    pub fn from_api_json(
+        master_data: &MasterData,
+        ships_json: &str,
+        slot_items_json: &str,
+    ) -> Result<Self, ImportError> {
+        let ships = parse_array::<ApiShip>(ships_json, "api_ship")?;
+        let slot_items = parse_array::<ApiSlotItem>(slot_items_json, "api_slot_item")?;
+
+        Ok(Self::new(master_data, &ships, &slot_items))
+    }
+}
@@ -0,0 +1,652 @@
+//! 共有用の編成コード
+//!
+//! protobuf に近い形式で、各フィールドを番号と型のタグ付きで書き込む。
+//! 未知のフィールドは読み飛ばすので、フィールドの追加は旧バージョンでも読み込める。
+//! 互換性のない変更を行う場合のみ先頭のバージョンを上げる。
+//! 艦娘や装備の `id` は共有に不要なので含めない。
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use crate::{
+    error::ImportError,
+    types::{
+        AirSquadronMode, AirSquadronState, AttackPowerModifier, CustomPowerModifiers, FleetKey,
+        FleetState, GearState, GearVecState, OrgState, OrgType, ShipState, SlotSizeVecState,
+    },
+};
+
+const SHARE_CODE_VERSION: u8 = 1;
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_BYTES: u64 = 1;
+const WIRE_FIXED64: u64 = 2;
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, field: u64, wire: u64) {
+        self.varint(field << 2 | wire);
+    }
+
+    fn uint<T: Into<u64>>(&mut self, field: u64, value: Option<T>) {
+        if let Some(value) = value {
+            self.key(field, WIRE_VARINT);
+            self.varint(value.into());
+        }
+    }
+
+    fn sint(&mut self, field: u64, value: Option<i16>) {
+        if let Some(value) = value {
+            let value = value as i64;
+            self.key(field, WIRE_VARINT);
+            self.varint(((value << 1) ^ (value >> 63)) as u64);
+        }
+    }
+
+    fn float(&mut self, field: u64, value: Option<f64>) {
+        if let Some(value) = value {
+            self.key(field, WIRE_FIXED64);
+            self.buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn message<T: Encode>(&mut self, field: u64, value: Option<&T>) {
+        if let Some(value) = value {
+            let mut inner = Writer::default();
+            value.encode(&mut inner);
+
+            self.key(field, WIRE_BYTES);
+            self.varint(inner.buf.len() as u64);
+            self.buf.extend(inner.buf);
+        }
+    }
+}
+
+enum FieldValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed64(f64),
+}
+
+impl FieldValue<'_> {
+    fn uint<T: TryFrom<u64>>(&self) -> Result<Option<T>, ImportError> {
+        match self {
+            Self::Varint(value) => T::try_from(*value)
+                .map(Some)
+                .map_err(|_| ImportError::InvalidShareCode("value out of range")),
+            _ => Err(ImportError::InvalidShareCode("unexpected wire type")),
+        }
+    }
+
+    fn sint(&self) -> Result<Option<i16>, ImportError> {
+        let value = self.uint::<u64>()?.unwrap_or_default();
+        let value = ((value >> 1) as i64) ^ -((value & 1) as i64);
+
+        i16::try_from(value)
+            .map(Some)
+            .map_err(|_| ImportError::InvalidShareCode("value out of range"))
+    }
+
+    fn float(&self) -> Result<Option<f64>, ImportError> {
+        match self {
+            Self::Fixed64(value) => Ok(Some(*value)),
+            _ => Err(ImportError::InvalidShareCode("unexpected wire type")),
+        }
+    }
+
+    fn message<T: Decode>(&self) -> Result<Option<T>, ImportError> {
+        match self {
+            Self::Bytes(bytes) => decode_message(bytes).map(Some),
+            _ => Err(ImportError::InvalidShareCode("unexpected wire type")),
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ImportError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(ImportError::InvalidShareCode("unexpected end of data"))?;
+
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, ImportError> {
+        let mut value = 0_u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(ImportError::InvalidShareCode("varint too long"))
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, FieldValue<'a>)>, ImportError> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let field = key >> 2;
+
+        let value = match key & 0b11 {
+            WIRE_VARINT => FieldValue::Varint(self.varint()?),
+            WIRE_BYTES => {
+                let len = usize::try_from(self.varint()?)
+                    .map_err(|_| ImportError::InvalidShareCode("value out of range"))?;
+                FieldValue::Bytes(self.take(len)?)
+            }
+            WIRE_FIXED64 => {
+                let bytes = self.take(8)?.try_into().unwrap_or_default();
+                FieldValue::Fixed64(f64::from_le_bytes(bytes))
+            }
+            _ => return Err(ImportError::InvalidShareCode("unknown wire type")),
+        };
+
+        Ok(Some((field, value)))
+    }
+}
+
+trait Encode {
+    fn encode(&self, w: &mut Writer);
+}
+
+trait Decode: Default {
+    /// 未知のフィールドは無視する
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError>;
+}
+
+fn decode_message<T: Decode>(bytes: &[u8]) -> Result<T, ImportError> {
+    let mut reader = Reader::new(bytes);
+    let mut message = T::default();
+
+    while let Some((field, value)) = reader.next_field()? {
+        message.decode_field(field, value)?;
+    }
+
+    Ok(message)
+}
+
+fn org_type_to_u8(org_type: OrgType) -> u8 {
+    match org_type {
+        OrgType::Single => 0,
+        OrgType::CarrierTaskForce => 1,
+        OrgType::SurfaceTaskForce => 2,
+        OrgType::TransportEscort => 3,
+        OrgType::EnemySingle => 4,
+        OrgType::EnemyCombined => 5,
+    }
+}
+
+fn org_type_from_u8(value: u8) -> Option<OrgType> {
+    match value {
+        0 => Some(OrgType::Single),
+        1 => Some(OrgType::CarrierTaskForce),
+        2 => Some(OrgType::SurfaceTaskForce),
+        3 => Some(OrgType::TransportEscort),
+        4 => Some(OrgType::EnemySingle),
+        5 => Some(OrgType::EnemyCombined),
+        _ => None,
+    }
+}
+
+fn fleet_key_from_u8(value: u8) -> Option<FleetKey> {
+    num_traits::FromPrimitive::from_u8(value)
+}
+
+impl Encode for GearState {
+    /// 改修値と熟練度は1つの値にまとめる
+    fn encode(&self, w: &mut Writer) {
+        let stars = self.stars.map_or(0, |stars| stars.min(14) as u64 + 1);
+        let exp = self.exp.map_or(0, |exp| exp as u64 + 1);
+        let packed = stars | exp << 4;
+
+        w.uint(1, Some(self.gear_id));
+        w.uint(2, (packed != 0).then_some(packed));
+    }
+}
+
+impl Decode for GearState {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        match field {
+            1 => self.gear_id = value.uint()?.unwrap_or_default(),
+            2 => {
+                let packed = value.uint::<u64>()?.unwrap_or_default();
+                let stars = packed & 0b1111;
+                let exp = packed >> 4;
+
+                self.stars = (stars > 0).then(|| (stars - 1) as u8);
+                self.exp = (exp > 0).then(|| (exp - 1).min(u8::MAX as u64) as u8);
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for AttackPowerModifier {
+    fn encode(&self, w: &mut Writer) {
+        w.float(1, (self.a != 1.0).then_some(self.a));
+        w.float(2, (self.b != 0.0).then_some(self.b));
+    }
+}
+
+impl Decode for AttackPowerModifier {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        match field {
+            1 => self.a = value.float()?.unwrap_or(1.0),
+            2 => self.b = value.float()?.unwrap_or_default(),
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for CustomPowerModifiers {
+    fn encode(&self, w: &mut Writer) {
+        let default = AttackPowerModifier::default();
+        let non_default = |m: &AttackPowerModifier| (*m != default).then_some(*m);
+
+        w.message(1, non_default(&self.precap_mod).as_ref());
+        w.message(2, non_default(&self.postcap_mod).as_ref());
+        w.message(3, non_default(&self.basic_power_mod).as_ref());
+        w.message(4, non_default(&self.historical_mod).as_ref());
+    }
+}
+
+impl Decode for CustomPowerModifiers {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        let target = match field {
+            1 => &mut self.precap_mod,
+            2 => &mut self.postcap_mod,
+            3 => &mut self.basic_power_mod,
+            4 => &mut self.historical_mod,
+            _ => return Ok(()),
+        };
+
+        *target = value.message()?.unwrap_or_default();
+
+        Ok(())
+    }
+}
+
+fn encode_gears(w: &mut Writer, start: u64, gears: &GearVecState) {
+    gears
+        .iter()
+        .zip(start..)
+        .for_each(|(gear, field)| w.message(field, gear));
+}
+
+fn decode_gear(gears: &mut GearVecState, index: u64, value: FieldValue) -> Result<(), ImportError> {
+    let gear = value.message()?;
+
+    match index {
+        0 => gears.g1 = gear,
+        1 => gears.g2 = gear,
+        2 => gears.g3 = gear,
+        3 => gears.g4 = gear,
+        4 => gears.g5 = gear,
+        _ => gears.gx = gear,
+    }
+
+    Ok(())
+}
+
+fn encode_slots(w: &mut Writer, start: u64, slots: &SlotSizeVecState) {
+    slots
+        .clone()
+        .into_iter()
+        .zip(start..)
+        .for_each(|(size, field)| w.uint(field, size));
+}
+
+fn decode_slot(
+    slots: &mut SlotSizeVecState,
+    index: u64,
+    value: FieldValue,
+) -> Result<(), ImportError> {
+    let size = value.uint()?;
+
+    match index {
+        0 => slots.ss1 = size,
+        1 => slots.ss2 = size,
+        2 => slots.ss3 = size,
+        3 => slots.ss4 = size,
+        _ => slots.ss5 = size,
+    }
+
+    Ok(())
+}
+
+impl Encode for ShipState {
+    fn encode(&self, w: &mut Writer) {
+        w.uint(1, Some(self.ship_id));
+        w.uint(2, self.level);
+        w.uint(3, self.current_hp);
+        w.uint(4, self.morale);
+        w.uint(5, self.ammo);
+        w.uint(6, self.fuel);
+
+        w.sint(7, self.max_hp_mod);
+        w.sint(8, self.firepower_mod);
+        w.sint(9, self.torpedo_mod);
+        w.sint(10, self.armor_mod);
+        w.sint(11, self.anti_air_mod);
+        w.sint(12, self.evasion_mod);
+        w.sint(13, self.asw_mod);
+        w.sint(14, self.los_mod);
+        w.sint(15, self.luck_mod);
+
+        encode_gears(w, 16, &self.gears);
+        encode_slots(w, 22, &self.slots);
+
+        w.float(27, self.day_gunfit_accuracy.map(|v| v.0));
+        w.float(28, self.night_gunfit_accuracy.map(|v| v.0));
+
+        let custom_power_mods = &self.custom_power_mods;
+        let has_custom_power_mods = [
+            custom_power_mods.precap_mod,
+            custom_power_mods.postcap_mod,
+            custom_power_mods.basic_power_mod,
+            custom_power_mods.historical_mod,
+        ]
+        .iter()
+        .any(|m| *m != AttackPowerModifier::default());
+
+        w.message(29, has_custom_power_mods.then_some(custom_power_mods));
+    }
+}
+
+impl Decode for ShipState {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        match field {
+            1 => self.ship_id = value.uint()?.unwrap_or_default(),
+            2 => self.level = value.uint()?,
+            3 => self.current_hp = value.uint()?,
+            4 => self.morale = value.uint()?,
+            5 => self.ammo = value.uint()?,
+            6 => self.fuel = value.uint()?,
+            7 => self.max_hp_mod = value.sint()?,
+            8 => self.firepower_mod = value.sint()?,
+            9 => self.torpedo_mod = value.sint()?,
+            10 => self.armor_mod = value.sint()?,
+            11 => self.anti_air_mod = value.sint()?,
+            12 => self.evasion_mod = value.sint()?,
+            13 => self.asw_mod = value.sint()?,
+            14 => self.los_mod = value.sint()?,
+            15 => self.luck_mod = value.sint()?,
+            16..=21 => decode_gear(&mut self.gears, field - 16, value)?,
+            22..=26 => decode_slot(&mut self.slots, field - 22, value)?,
+            27 => self.day_gunfit_accuracy = value.float()?.map(Into::into),
+            28 => self.night_gunfit_accuracy = value.float()?.map(Into::into),
+            29 => self.custom_power_mods = value.message()?.unwrap_or_default(),
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for FleetState {
+    fn encode(&self, w: &mut Writer) {
+        w.uint(1, self.len.map(|len| len as u64));
+
+        [
+            &self.s1, &self.s2, &self.s3, &self.s4, &self.s5, &self.s6, &self.s7,
+        ]
+        .into_iter()
+        .zip(2..)
+        .for_each(|(ship, field)| w.message(field, ship.as_ref()));
+    }
+}
+
+impl Decode for FleetState {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        let target = match field {
+            1 => {
+                self.len = value.uint()?;
+                return Ok(());
+            }
+            2 => &mut self.s1,
+            3 => &mut self.s2,
+            4 => &mut self.s3,
+            5 => &mut self.s4,
+            6 => &mut self.s5,
+            7 => &mut self.s6,
+            8 => &mut self.s7,
+            _ => return Ok(()),
+        };
+
+        *target = value.message()?;
+
+        Ok(())
+    }
+}
+
+impl Encode for AirSquadronState {
+    fn encode(&self, w: &mut Writer) {
+        w.uint(
+            1,
+            self.mode.map(|mode| match mode {
+                AirSquadronMode::Sortie => 0_u8,
+                AirSquadronMode::AirDefense => 1,
+            }),
+        );
+
+        encode_gears(w, 2, &self.gears);
+        encode_slots(w, 8, &self.slots);
+    }
+}
+
+impl Decode for AirSquadronState {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        match field {
+            1 => {
+                self.mode = value.uint::<u8>()?.map(|mode| match mode {
+                    1 => AirSquadronMode::AirDefense,
+                    _ => AirSquadronMode::Sortie,
+                })
+            }
+            2..=7 => decode_gear(&mut self.gears, field - 2, value)?,
+            8..=12 => decode_slot(&mut self.slots, field - 8, value)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for OrgState {
+    fn encode(&self, w: &mut Writer) {
+        w.message(1, self.f1.as_ref());
+        w.message(2, self.f2.as_ref());
+        w.message(3, self.f3.as_ref());
+        w.message(4, self.f4.as_ref());
+        w.message(5, self.a1.as_ref());
+        w.message(6, self.a2.as_ref());
+        w.message(7, self.a3.as_ref());
+        w.uint(8, self.hq_level);
+        w.uint(9, self.org_type.map(org_type_to_u8));
+        w.uint(10, self.sortie.map(|key| key as u8));
+        w.uint(11, self.route_sup.map(|key| key as u8));
+        w.uint(12, self.boss_sup.map(|key| key as u8));
+    }
+}
+
+impl Decode for OrgState {
+    fn decode_field(&mut self, field: u64, value: FieldValue) -> Result<(), ImportError> {
+        match field {
+            1 => self.f1 = value.message()?,
+            2 => self.f2 = value.message()?,
+            3 => self.f3 = value.message()?,
+            4 => self.f4 = value.message()?,
+            5 => self.a1 = value.message()?,
+            6 => self.a2 = value.message()?,
+            7 => self.a3 = value.message()?,
+            8 => self.hq_level = value.uint()?,
+            9 => self.org_type = value.uint()?.and_then(org_type_from_u8),
+            10 => self.sortie = value.uint()?.and_then(fleet_key_from_u8),
+            11 => self.route_sup = value.uint()?.and_then(fleet_key_from_u8),
+            12 => self.boss_sup = value.uint()?.and_then(fleet_key_from_u8),
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// 編成を共有用の文字列に変換する
+pub fn encode_share_code(state: &OrgState) -> String {
+    let mut w = Writer::default();
+    w.buf.push(SHARE_CODE_VERSION);
+    state.encode(&mut w);
+
+    URL_SAFE_NO_PAD.encode(w.buf)
+}
+
+/// 共有用の文字列から編成を復元する
+pub fn decode_share_code(code: &str) -> Result<OrgState, ImportError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code.trim().trim_end_matches('='))
+        .map_err(|_| ImportError::InvalidShareCode("invalid base64"))?;
+
+    let (&version, body) = bytes
+        .split_first()
+        .ok_or(ImportError::InvalidShareCode("empty code"))?;
+
+    if version == 0 || version > SHARE_CODE_VERSION {
+        return Err(ImportError::UnsupportedVersion(version));
+    }
+
+    decode_message(body)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, u16::MAX as u64, u64::MAX] {
+            let mut w = Writer::default();
+            w.varint(value);
+            assert_eq!(Reader::new(&w.buf).varint().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_skip_unknown_fields() {
+        let mut w = Writer::default();
+        w.uint(8, Some(100_u8));
+        w.uint(100, Some(5_u8));
+        w.float(101, Some(1.5));
+        w.message(102, Some(&GearState::default()));
+
+        let state: OrgState = decode_message(&w.buf).unwrap();
+        assert_eq!(state.hq_level, Some(100));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let gear = GearState {
+            gear_id: 21,
+            exp: Some(120),
+            stars: Some(10),
+            ..Default::default()
+        };
+
+        let ship = ShipState {
+            ship_id: 418,
+            level: Some(175),
+            morale: Some(85),
+            luck_mod: Some(-3),
+            asw_mod: Some(9),
+            day_gunfit_accuracy: Some(0.5.into()),
+            gears: GearVecState {
+                g1: Some(gear.clone()),
+                gx: Some(GearState {
+                    gear_id: 2,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            slots: SlotSizeVecState {
+                ss1: Some(18),
+                ..Default::default()
+            },
+            custom_power_mods: CustomPowerModifiers {
+                historical_mod: AttackPowerModifier::new(1.2, 3.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let state = OrgState {
+            id: Some("org".into()),
+            f1: Some(FleetState {
+                len: Some(7),
+                s1: Some(ship),
+                ..Default::default()
+            }),
+            a2: Some(AirSquadronState {
+                mode: Some(AirSquadronMode::AirDefense),
+                gears: GearVecState {
+                    g4: Some(gear),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            hq_level: Some(120),
+            org_type: Some(OrgType::SurfaceTaskForce),
+            route_sup: Some(FleetKey::F3),
+            ..Default::default()
+        };
+
+        let code = encode_share_code(&state);
+        let decoded = decode_share_code(&code).unwrap();
+
+        let expected = OrgState { id: None, ..state };
+
+        assert_eq!(
+            serde_json::to_value(decoded).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+
+        assert!(matches!(
+            decode_share_code("AgA"),
+            Err(ImportError::UnsupportedVersion(2))
+        ));
+        assert!(decode_share_code("!!").is_err());
+    }
+}
@@ -0,0 +1,648 @@
+use hashbrown::HashMap;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::ImportError,
+    master_data::{MasterData, MasterIBonuses, MstEquipExslotShip, MstEquipShip, NationalityDef},
+    types::{ctype, ShipType, SpeedGroup},
+};
+
+/// api_start2/getData の api_mst_ship
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiMstShip {
+    pub api_id: u16,
+    pub api_sort_id: u16,
+    pub api_name: String,
+    pub api_yomi: String,
+    pub api_stype: u8,
+    pub api_ctype: u16,
+    pub api_soku: u8,
+    pub api_slot_num: usize,
+    pub api_maxeq: Option<Vec<u8>>,
+    pub api_taik: Option<[u16; 2]>,
+    pub api_souk: Option<[u16; 2]>,
+    pub api_houg: Option<[u16; 2]>,
+    pub api_raig: Option<[u16; 2]>,
+    pub api_tyku: Option<[u16; 2]>,
+    pub api_luck: Option<[u16; 2]>,
+    pub api_leng: Option<u8>,
+    pub api_fuel_max: Option<u16>,
+    pub api_bull_max: Option<u16>,
+    pub api_aftershipid: Option<String>,
+    pub api_afterlv: Option<u16>,
+}
+
+/// api_start2/getData の api_mst_slotitem
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiMstSlotitem {
+    pub api_id: u16,
+    pub api_name: String,
+    pub api_type: Vec<u8>,
+    pub api_taik: i16,
+    pub api_souk: i16,
+    pub api_houg: i16,
+    pub api_raig: i16,
+    pub api_soku: i16,
+    pub api_baku: i16,
+    pub api_tyku: i16,
+    pub api_tais: i16,
+    pub api_saku: i16,
+    pub api_luck: i16,
+    pub api_houm: i16,
+    pub api_houk: i16,
+    pub api_leng: u8,
+    pub api_distance: Option<u8>,
+    pub api_cost: Option<u8>,
+}
+
+/// api_start2/getData の api_mst_stype
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiMstStype {
+    pub api_id: u8,
+    /// 装備種別IDごとの装備可否
+    pub api_equip_type: HashMap<String, u8>,
+}
+
+/// 補強増設に装備できる艦の条件
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiExslotShipRule {
+    pub api_ship_ids: Option<HashMap<String, u8>>,
+    pub api_stypes: Option<HashMap<String, u8>>,
+    pub api_ctypes: Option<HashMap<String, u8>>,
+    /// 装備に必要な艦のレベル
+    pub api_req_level: u16,
+}
+
+/// api_mst_equip_ship は配列と艦娘IDをキーとするオブジェクトの2つの形式がある
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ApiMstEquipShip {
+    List(Vec<MstEquipShip>),
+    Map(HashMap<String, Vec<u8>>),
+}
+
+impl Default for ApiMstEquipShip {
+    fn default() -> Self {
+        Self::List(vec![])
+    }
+}
+
+/// api_mst_equip_exslot_ship は配列と装備IDをキーとするオブジェクトの2つの形式がある
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ApiMstEquipExslotShip {
+    List(Vec<MstEquipExslotShip>),
+    Map(HashMap<String, ApiExslotShipRule>),
+}
+
+impl Default for ApiMstEquipExslotShip {
+    fn default() -> Self {
+        Self::List(vec![])
+    }
+}
+
+/// api_start2/getData の api_data
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Start2 {
+    pub api_mst_ship: Vec<ApiMstShip>,
+    pub api_mst_slotitem: Vec<ApiMstSlotitem>,
+    pub api_mst_stype: Vec<ApiMstStype>,
+    pub api_mst_equip_exslot: Vec<u8>,
+    pub api_mst_equip_ship: ApiMstEquipShip,
+    pub api_mst_equip_exslot_ship: ApiMstEquipExslotShip,
+}
+
+/// 深海棲艦の艦名から取り除く接尾辞
+const ABYSSAL_SUFFIXES: [&str; 27] = [
+    "甲",
+    "乙",
+    "丙",
+    "丁",
+    "戊",
+    "特",
+    "改二",
+    "改II",
+    "乙改",
+    "丁改",
+    "改",
+    "航",
+    "母",
+    "護",
+    " zwei",
+    " drei",
+    " due",
+    " andra",
+    " два",
+    " Mod.2",
+    " Mk.II",
+    "後期型II",
+    "後期型",
+    " バカンスmode",
+    " 夏季上陸mode",
+    " 夏mode",
+    "-壊",
+];
+
+fn strip_abyssal_suffixes(name: &str) -> &str {
+    let mut name = name;
+
+    while let Some(stripped) = ABYSSAL_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+    {
+        name = stripped;
+    }
+
+    name
+}
+
+impl ApiMstShip {
+    fn is_player_ship(&self) -> bool {
+        self.api_houg.is_some()
+    }
+
+    fn next_id(&self) -> u16 {
+        self.api_aftershipid
+            .as_deref()
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_default()
+    }
+
+    fn default_speed_group(&self) -> SpeedGroup {
+        let ship_type = ShipType::from(self.api_stype);
+        let is_fast_av = ship_type == ShipType::AV && self.api_soku == 10;
+
+        if is_fast_av
+            || matches!(ship_type, ShipType::SS | ShipType::SSV)
+            || matches!(self.api_name.as_str(), "夕張" | "夕張改")
+            || matches!(
+                self.api_ctype,
+                ctype!("加賀型") | ctype!("陸軍特種船(R1)") | ctype!("工作艦") | ctype!("改風早型")
+            )
+        {
+            return SpeedGroup::C;
+        }
+
+        if matches!(
+            self.api_ctype,
+            ctype!("島風型")
+                | ctype!("Ташкент級")
+                | ctype!("大鳳型")
+                | ctype!("翔鶴型")
+                | ctype!("利根型")
+                | ctype!("最上型")
+        ) {
+            return SpeedGroup::A;
+        }
+
+        if matches!(
+            self.api_ctype,
+            ctype!("阿賀野型")
+                | ctype!("蒼龍型")
+                | ctype!("飛龍型")
+                | ctype!("金剛型")
+                | ctype!("大和型")
+                | ctype!("Iowa級")
+        ) || matches!(
+            self.api_yomi.as_str(),
+            "あまつかぜ" | "うんりゅう" | "あまぎ"
+        ) || self.api_name == "長門改二"
+        {
+            return SpeedGroup::B1;
+        }
+
+        SpeedGroup::B2
+    }
+}
+
+impl Start2 {
+    /// 生のレスポンス (svdata=...) と api_data のどちらからも読み込む
+    pub fn from_json(json: &str) -> Result<Self, ImportError> {
+        let json = json.trim_start().trim_start_matches("svdata=");
+        let mut value = serde_json::from_str::<Value>(json)?;
+
+        if let Some(api_data) = value.get_mut("api_data") {
+            value = api_data.take();
+        }
+
+        if value.get("api_mst_ship").is_none() {
+            return Err(ImportError::UnsupportedFormat("api_mst_ship".into()));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn ship_values(&self, nationalities: &HashMap<u16, u8>) -> Vec<Value> {
+        let stat = |stat: Option<[u16; 2]>| match stat {
+            Some([min, max]) => json!([min, max]),
+            None => json!([null, null]),
+        };
+
+        let next_ids = self
+            .api_mst_ship
+            .iter()
+            .map(|ship| (ship.api_id, ship.next_id()))
+            .collect::<HashMap<_, _>>();
+
+        self.api_mst_ship
+            .iter()
+            .map(|mst| {
+                let mut ship = json!({
+                    "ship_id": mst.api_id,
+                    "name": mst.api_name,
+                    "yomi": mst.api_yomi,
+                    "sort_id": mst.api_sort_id,
+                    "stype": mst.api_stype,
+                    "ctype": mst.api_ctype,
+                    "slotnum": mst.api_slot_num,
+                    "speed": mst.api_soku,
+                    "max_hp": stat(mst.api_taik),
+                    "firepower": stat(mst.api_houg),
+                    "armor": stat(mst.api_souk),
+                    "torpedo": stat(mst.api_raig),
+                    "evasion": [null, null],
+                    "anti_air": stat(mst.api_tyku),
+                    "asw": [null, null],
+                    "los": [null, null],
+                    "luck": stat(mst.api_luck),
+                    "slots": [],
+                    "stock": [],
+                });
+
+                let map = ship.as_object_mut().unwrap();
+
+                if mst.is_player_ship() {
+                    let slots = mst
+                        .api_maxeq
+                        .iter()
+                        .flatten()
+                        .take(mst.api_slot_num)
+                        .collect::<Vec<_>>();
+
+                    map.insert("slots".into(), json!(slots));
+                    map.insert("range".into(), json!(mst.api_leng));
+                    map.insert("fuel".into(), json!(mst.api_fuel_max.unwrap_or_default()));
+                    map.insert("ammo".into(), json!(mst.api_bull_max.unwrap_or_default()));
+                    map.insert("next_id".into(), json!(mst.next_id()));
+                    map.insert(
+                        "next_level".into(),
+                        json!(mst.api_afterlv.unwrap_or_default()),
+                    );
+                    map.insert("speed_group".into(), json!(mst.default_speed_group()));
+                    map.insert(
+                        "useful".into(),
+                        json!(is_convertible(&next_ids, mst.api_id)),
+                    );
+
+                    if let Some(nationality) = nationalities.get(&mst.api_ctype) {
+                        map.insert("nationality".into(), json!(nationality));
+                    }
+                } else {
+                    // 深海棲艦の艦型は接尾辞を除いた艦名の艦のIDとする
+                    let base_name = strip_abyssal_suffixes(&mst.api_name);
+                    let abyssal_ctype = self
+                        .api_mst_ship
+                        .iter()
+                        .find(|ship| ship.api_name == base_name)
+                        .map(|ship| ship.api_id)
+                        .unwrap_or_default();
+
+                    map.insert("ctype".into(), json!(abyssal_ctype));
+                }
+
+                ship
+            })
+            .collect()
+    }
+
+    fn gear_values(&self) -> Vec<Value> {
+        self.api_mst_slotitem
+            .iter()
+            .map(|mst| {
+                json!({
+                    "gear_id": mst.api_id,
+                    "name": mst.api_name,
+                    "types": mst.api_type,
+                    "max_hp": mst.api_taik,
+                    "armor": mst.api_souk,
+                    "firepower": mst.api_houg,
+                    "torpedo": mst.api_raig,
+                    "speed": mst.api_soku,
+                    "bombing": mst.api_baku,
+                    "anti_air": mst.api_tyku,
+                    "asw": mst.api_tais,
+                    "los": mst.api_saku,
+                    "luck": mst.api_luck,
+                    "accuracy": mst.api_houm,
+                    "evasion": mst.api_houk,
+                    "range": mst.api_leng,
+                    "radius": mst.api_distance.unwrap_or_default(),
+                    "cost": mst.api_cost.unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    fn equippability_value(&self) -> Value {
+        let equip_stype = self
+            .api_mst_stype
+            .iter()
+            .map(|stype| {
+                let mut equip_type = stype
+                    .api_equip_type
+                    .iter()
+                    .filter(|(_, &equippable)| equippable == 1)
+                    .filter_map(|(gear_type, _)| gear_type.parse::<u8>().ok())
+                    .collect::<Vec<_>>();
+
+                equip_type.sort_unstable();
+
+                json!({ "id": stype.api_id, "equip_type": equip_type })
+            })
+            .collect::<Vec<_>>();
+
+        let mut equip_ship = match &self.api_mst_equip_ship {
+            ApiMstEquipShip::List(list) => list
+                .iter()
+                .map(|es| json!({ "api_ship_id": es.api_ship_id, "api_equip_type": es.api_equip_type }))
+                .collect::<Vec<_>>(),
+            ApiMstEquipShip::Map(map) => map
+                .iter()
+                .filter_map(|(ship_id, types)| {
+                    let ship_id = ship_id.parse::<u16>().ok()?;
+                    Some(json!({ "api_ship_id": ship_id, "api_equip_type": types }))
+                })
+                .collect(),
+        };
+
+        equip_ship.sort_by_key(|es| es["api_ship_id"].as_u64());
+
+        let mut equip_exslot_ship = match &self.api_mst_equip_exslot_ship {
+            ApiMstEquipExslotShip::List(list) => list
+                .iter()
+                .map(|ees| {
+                    json!({
+                        "api_slotitem_id": ees.api_slotitem_id,
+                        "api_ship_ids": ees.api_ship_ids,
+                        "api_req_level": ees.api_req_level,
+                    })
+                })
+                .collect::<Vec<_>>(),
+            ApiMstEquipExslotShip::Map(map) => map
+                .iter()
+                .filter_map(|(gear_id, rule)| {
+                    let gear_id = gear_id.parse::<u16>().ok()?;
+                    let ship_ids = self.exslot_ship_ids(rule);
+                    Some(json!({
+                        "api_slotitem_id": gear_id,
+                        "api_ship_ids": ship_ids,
+                        "api_req_level": rule.api_req_level,
+                    }))
+                })
+                .collect(),
+        };
+
+        equip_exslot_ship.sort_by_key(|ees| ees["api_slotitem_id"].as_u64());
+
+        json!({
+            "equip_stype": equip_stype,
+            "equip_exslot": self.api_mst_equip_exslot,
+            "equip_ship": equip_ship,
+            "equip_exslot_ship": equip_exslot_ship,
+            "rules": [],
+        })
+    }
+
+    /// 艦娘ID、艦種、艦型のいずれかで指定された補強増設の条件を艦娘IDに展開する
+    fn exslot_ship_ids(&self, rule: &ApiExslotShipRule) -> Vec<u16> {
+        let contains = |map: &Option<HashMap<String, u8>>, id: String| {
+            map.as_ref().and_then(|map| map.get(&id)) == Some(&1)
+        };
+
+        self.api_mst_ship
+            .iter()
+            .filter(|ship| ship.is_player_ship())
+            .filter(|ship| {
+                contains(&rule.api_ship_ids, ship.api_id.to_string())
+                    || contains(&rule.api_stypes, ship.api_stype.to_string())
+                    || contains(&rule.api_ctypes, ship.api_ctype.to_string())
+            })
+            .map(|ship| ship.api_id)
+            .collect()
+    }
+}
+
+/// 改装で元に戻れる艦 (改装先を辿ると自身に戻る艦)
+fn is_convertible(next_ids: &HashMap<u16, u16>, ship_id: u16) -> bool {
+    let mut current = next_ids.get(&ship_id).copied().unwrap_or_default();
+
+    for _ in 0..next_ids.len() {
+        if current == 0 {
+            return false;
+        }
+
+        if current == ship_id {
+            return true;
+        }
+
+        current = next_ids.get(&current).copied().unwrap_or_default();
+    }
+
+    false
+}
+
+/// `key` が一致する要素に上書きし、一致しない要素は追加する
+fn merge_by_key(base: &mut Vec<Value>, overrides: Value, key: &str) {
+    let Value::Array(overrides) = overrides else {
+        return;
+    };
+
+    for item in overrides {
+        let Value::Object(fields) = item else {
+            continue;
+        };
+
+        let target = base
+            .iter_mut()
+            .find(|value| value.get(key).is_some() && value.get(key) == fields.get(key))
+            .and_then(Value::as_object_mut);
+
+        match target {
+            Some(target) => target.extend(fields),
+            None => base.push(Value::Object(fields)),
+        }
+    }
+}
+
+impl MasterData {
+    /// ゲームの api_start2 と補足データからマスタデータを作成する
+    ///
+    /// 補足データは master_data.json と同じ形式で、艦や装備は ID を含む差分のみでよい
+    /// `ships` `gears` は ID ごとに上書きし、`equippability` は `rules` などのキーごとに上書きする
+    /// `nationalities` を含む場合は艦型から艦娘の国籍を設定する
+    /// 補足データに含まれない装備属性や改修効果などの定義は空とする
+    /// 読み込めない艦がある場合はエラーとする
+    pub fn from_start2(start2_json: &str, supplement_json: &str) -> Result<Self, ImportError> {
+        let start2 = Start2::from_json(start2_json)?;
+
+        let mut supplement = match serde_json::from_str::<Value>(supplement_json)? {
+            Value::Object(map) => map,
+            _ => return Err(ImportError::UnsupportedFormat("supplement".into())),
+        };
+
+        let nationalities = supplement
+            .remove("nationalities")
+            .map(serde_json::from_value::<Vec<NationalityDef>>)
+            .transpose()?
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|def| {
+                def.ctypes
+                    .into_iter()
+                    .map(move |ctype| (ctype as u16, def.id))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut ships = start2.ship_values(&nationalities);
+        let mut gears = start2.gear_values();
+        let mut equippability = start2.equippability_value();
+
+        if let Some(overrides) = supplement.remove("ships") {
+            merge_by_key(&mut ships, overrides, "ship_id");
+        }
+
+        if let Some(overrides) = supplement.remove("gears") {
+            merge_by_key(&mut gears, overrides, "gear_id");
+        }
+
+        if let Some(Value::Object(overrides)) = supplement.remove("equippability") {
+            if let Some(equippability) = equippability.as_object_mut() {
+                equippability.extend(overrides);
+            }
+        }
+
+        let ibonuses = MasterIBonuses::default()
+            .groups()
+            .into_iter()
+            .map(|(key, _)| (key.to_string(), json!([])))
+            .collect::<Map<_, _>>();
+
+        let mut master = Map::new();
+        master.insert("created_at".into(), json!(0));
+        master.insert("gear_attrs".into(), json!([]));
+        master.insert("ship_attrs".into(), json!([]));
+        master.insert("ibonuses".into(), Value::Object(ibonuses));
+        master.insert("formation".into(), json!([]));
+        master.insert("anti_air_cutin".into(), json!([]));
+        master.insert("day_cutin".into(), json!([]));
+        master.insert("night_cutin".into(), json!([]));
+        master.extend(supplement);
+        master.insert("ships".into(), Value::Array(ships));
+        master.insert("gears".into(), Value::Array(gears));
+        master.insert("equippability".into(), equippability);
+
+        let master_data = serde_json::from_value::<Self>(Value::Object(master))?;
+
+        if let Some((index, message)) = master_data.dropped_ships.first() {
+            return Err(ImportError::InvalidMasterData(format!(
+                "ships[{index}]: {message}"
+            )));
+        }
+
+        Ok(master_data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_start2() {
+        let start2 = json!({
+            "api_result": 1,
+            "api_data": {
+                "api_mst_ship": [
+                    {
+                        "api_id": 1, "api_sort_id": 11, "api_name": "睦月", "api_yomi": "むつき",
+                        "api_stype": 2, "api_ctype": 28, "api_soku": 10, "api_slot_num": 2,
+                        "api_maxeq": [0, 0, 0, 0, 0], "api_taik": [13, 24], "api_souk": [5, 18],
+                        "api_houg": [6, 29], "api_raig": [18, 59], "api_tyku": [7, 29],
+                        "api_luck": [12, 49], "api_leng": 1, "api_fuel_max": 15, "api_bull_max": 15,
+                        "api_aftershipid": "254", "api_afterlv": 20
+                    },
+                    {
+                        "api_id": 254, "api_sort_id": 12, "api_name": "睦月改", "api_yomi": "むつき",
+                        "api_stype": 2, "api_ctype": 28, "api_soku": 10, "api_slot_num": 2,
+                        "api_maxeq": [0, 0, 0, 0, 0], "api_taik": [24, 31], "api_souk": [7, 29],
+                        "api_houg": [7, 39], "api_raig": [24, 79], "api_tyku": [9, 39],
+                        "api_luck": [12, 49], "api_leng": 1, "api_fuel_max": 15, "api_bull_max": 15,
+                        "api_aftershipid": "0", "api_afterlv": 0
+                    },
+                    { "api_id": 1501, "api_sort_id": 0, "api_name": "駆逐イ級", "api_yomi": "", "api_stype": 2, "api_ctype": 1, "api_soku": 10, "api_slot_num": 1 },
+                    { "api_id": 1575, "api_sort_id": 0, "api_name": "駆逐イ級後期型", "api_yomi": "", "api_stype": 2, "api_ctype": 1, "api_soku": 10, "api_slot_num": 1 }
+                ],
+                "api_mst_slotitem": [
+                    { "api_id": 1, "api_name": "12cm単装砲", "api_type": [1, 1, 1, 1, 0], "api_houg": 1, "api_tyku": 1, "api_leng": 1 }
+                ],
+                "api_mst_stype": [{ "api_id": 2, "api_equip_type": { "1": 1, "2": 0, "5": 1 } }],
+                "api_mst_equip_exslot": [17],
+                "api_mst_equip_ship": { "254": [1, 2] },
+                "api_mst_equip_exslot_ship": {
+                    "1": { "api_ship_ids": null, "api_stypes": { "2": 1 }, "api_ctypes": null, "api_req_level": 0 },
+                    "2": { "api_ship_ids": { "254": 1 }, "api_stypes": null, "api_ctypes": null, "api_req_level": 30 }
+                }
+            }
+        });
+
+        let supplement = json!({
+            "created_at": 1,
+            "ships": [{ "ship_id": 1, "asw": [16, 39] }],
+            "gears": [{ "gear_id": 1, "improvable": true }],
+            "equippability": { "rules": [] },
+            "nationalities": [{ "id": 1, "ctypes": [28] }],
+        });
+
+        let master_data =
+            MasterData::from_start2(&format!("svdata={start2}"), &supplement.to_string()).unwrap();
+
+        let mutsuki = master_data.get_ship(1).unwrap();
+        assert_eq!(mutsuki.asw.0, Some(16));
+        assert_eq!(mutsuki.max_hp.1, Some(24));
+        assert_eq!(mutsuki.next_id, 254);
+        assert_eq!(mutsuki.nationality, 1);
+        assert_eq!(mutsuki.speed_group, SpeedGroup::B2);
+        assert!(!mutsuki.useful);
+
+        assert_eq!(master_data.get_ship(1575).unwrap().ctype, 1501);
+        assert!(master_data.get_gear(1).unwrap().improvable);
+
+        let equippability = &master_data.equippability;
+        assert_eq!(equippability.equip_stype[0].equip_type, vec![1, 5]);
+        assert_eq!(equippability.equip_ship[0].api_ship_id, 254);
+        assert_eq!(
+            equippability.equip_exslot_ship[0].api_ship_ids,
+            vec![1, 254]
+        );
+        assert_eq!(equippability.equip_exslot_ship[1].api_req_level, 30);
+
+        let mutsuki_kai = master_data.get_ship(254).unwrap();
+        let exslot_gear_ids = |level| {
+            master_data
+                .create_ship_equippability(mutsuki_kai, level)
+                .exslot_gear_ids
+        };
+        assert_eq!(exslot_gear_ids(29), vec![1]);
+        assert_eq!(exslot_gear_ids(30), vec![1, 2]);
+
+        let supplement = json!({ "ships": [{ "ship_id": 1, "asw": "unknown" }] });
+        let result = MasterData::from_start2(&start2.to_string(), &supplement.to_string());
+        assert!(matches!(result, Err(ImportError::InvalidMasterData(_))));
+    }
+}
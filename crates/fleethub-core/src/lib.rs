@@ -9,10 +9,12 @@ pub mod comp;
 pub mod console;
 pub mod error;
 pub mod estimation;
+pub mod expedition;
 pub mod factory;
 pub mod fleet;
 pub mod gear;
 pub mod gear_array;
+pub mod import;
 pub mod master_data;
 pub mod member;
 pub mod org;
@@ -27,19 +29,27 @@ use gloo_utils::format::JsValueSerdeExt;
 use wasm_bindgen::{prelude::*, JsCast};
 
 use air_squadron::AirSquadron;
-use analyzer::Analyzer;
+use analyzer::{
+    Analyzer, ImprovementAnalysis, ImprovementAnalyzer, TransportPointPlan, TransportPointPlanner,
+    TransportPointPlannerConfig,
+};
 use comp::Comp;
+use expedition::ExpeditionReport;
 use factory::Factory;
 use fleet::Fleet;
 use gear::Gear;
-use master_data::MasterData;
+use import::{decode_share_code, encode_share_code, DeckBuilder, PlayerInventory};
+use master_data::{MasterData, MasterDataDiff, MasterDataValidation, RouteEvaluation};
 use org::Org;
-use ship::Ship;
-use types::{AirSquadronState, FleetState, GearState, OrgState, ShipState};
+use ship::{OpeningAswReport, Ship};
+use types::{
+    AirSquadronState, BattleDefinitions, CompiledEvaler, FleetState, GearState, OrgState, ShipState,
+};
 
 #[wasm_bindgen]
 pub struct FhCore {
     factory: Factory,
+    battle_defs: BattleDefinitions,
 }
 
 #[wasm_bindgen]
@@ -50,14 +60,21 @@ extern "C" {
 
 impl FhCore {
     pub fn from_master_data(master_data: MasterData) -> Self {
+        let battle_defs = master_data.battle_definitions();
+
         Self {
             factory: Factory::new(master_data),
+            battle_defs,
         }
     }
 
     pub fn master_data(&self) -> &MasterData {
         &self.factory.master_data
     }
+
+    pub fn factory(&self) -> &Factory {
+        &self.factory
+    }
 }
 
 #[wasm_bindgen]
@@ -68,9 +85,14 @@ impl FhCore {
             .into_serde::<MasterData>()
             .map_err(|err| JsValue::from(err.to_string()))?;
 
-        let factory = Factory::new(master_data);
+        Ok(Self::from_master_data(master_data))
+    }
+
+    pub fn from_start2(start2_json: &str, supplement_json: &str) -> Result<FhCore, JsValue> {
+        let master_data = MasterData::from_start2(start2_json, supplement_json)
+            .map_err(|err| JsValue::from(err.to_string()))?;
 
-        Ok(Self { factory })
+        Ok(Self::from_master_data(master_data))
     }
 
     pub fn create_gear(&self, input: Option<GearState>) -> Option<Gear> {
@@ -119,7 +141,171 @@ impl FhCore {
     }
 
     pub fn create_analyzer(&self) -> Analyzer {
-        Analyzer::new(self.factory.master_data.battle_definitions())
+        Analyzer::new(self.battle_defs.clone())
+    }
+
+    pub fn import_player_inventory(
+        &self,
+        ships_json: &str,
+        slot_items_json: &str,
+    ) -> Result<PlayerInventory, JsValue> {
+        PlayerInventory::from_api_json(&self.factory.master_data, ships_json, slot_items_json)
+            .map_err(|err| JsValue::from(err.to_string()))
+    }
+
+    pub fn import_deck_builder(&self, json: &str) -> Result<OrgState, JsValue> {
+        DeckBuilder::from_json(json)
+            .and_then(|deck| deck.to_org_state(&self.factory))
+            .map_err(|err| JsValue::from(err.to_string()))
+    }
+
+    pub fn export_deck_builder(&self, state: OrgState) -> Result<String, JsValue> {
+        DeckBuilder::from_org_state(&self.factory, &state)
+            .and_then(|deck| deck.to_json())
+            .map_err(|err| JsValue::from(err.to_string()))
+    }
+
+    pub fn encode_share_code(&self, state: OrgState) -> String {
+        encode_share_code(&state)
+    }
+
+    pub fn decode_share_code(&self, code: &str) -> Result<OrgState, JsValue> {
+        decode_share_code(code).map_err(|err| JsValue::from(err.to_string()))
+    }
+
+    pub fn validate_master_data(&self) -> MasterDataValidation {
+        self.factory.master_data.validate()
+    }
+
+    /// 読み込み前の JSON のマスタデータを検査する
+    pub fn validate_master_data_json(json: &str) -> MasterDataValidation {
+        MasterData::validate_json(json)
+    }
+
+    /// 古いマスタデータからの差分
+    pub fn diff_master_data(
+        &self,
+        js_old: <MasterData as tsify::Tsify>::JsType,
+    ) -> Result<MasterDataDiff, JsValue> {
+        let old = js_old
+            .into_serde::<MasterData>()
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+        Ok(old.diff(&self.factory.master_data))
+    }
+
+    pub fn evaluate_route(&self, comp: &Comp, map: i16, node: String) -> Option<RouteEvaluation> {
+        self.factory.master_data.evaluate_route(map, &node, comp)
+    }
+
+    pub fn find_ship_id_by_name(&self, name: &str) -> Option<u16> {
+        self.factory
+            .master_data
+            .find_ship_by_name(name)
+            .map(|ship| ship.ship_id)
+    }
+
+    pub fn find_gear_id_by_name(&self, name: &str) -> Option<u16> {
+        self.factory
+            .master_data
+            .find_gear_by_name(name)
+            .map(|gear| gear.gear_id)
+    }
+
+    pub fn search_ship_ids(&self, query: &str, limit: usize) -> Vec<u16> {
+        self.factory
+            .master_data
+            .search_ships(query, limit)
+            .into_iter()
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    pub fn search_gear_ids(&self, query: &str, limit: usize) -> Vec<u16> {
+        self.factory
+            .master_data
+            .search_gears(query, limit)
+            .into_iter()
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    pub fn filter_ship_ids(&self, expr: String) -> Result<Vec<u16>, JsValue> {
+        let expr = CompiledEvaler::new(expr).map_err(|err| JsValue::from(err.to_string()))?;
+
+        let ids = self
+            .factory
+            .master_data
+            .filter_ships(&expr)
+            .into_iter()
+            .map(|ship| ship.ship_id)
+            .collect();
+
+        Ok(ids)
+    }
+
+    pub fn filter_gear_ids(&self, expr: String) -> Result<Vec<u16>, JsValue> {
+        let expr = CompiledEvaler::new(expr).map_err(|err| JsValue::from(err.to_string()))?;
+
+        let ids = self
+            .factory
+            .master_data
+            .filter_gears(&expr)
+            .into_iter()
+            .map(|gear| gear.gear_id)
+            .collect();
+
+        Ok(ids)
+    }
+
+    pub fn check_expedition(&self, fleet: &Fleet, expedition_id: u16) -> Option<ExpeditionReport> {
+        self.factory
+            .master_data
+            .expeditions
+            .get(expedition_id)
+            .map(|def| def.check(fleet))
+    }
+
+    /// 先制対潜の条件と、条件を満たすための装備の候補
+    pub fn opening_asw_report(&self, ship: &Ship) -> OpeningAswReport {
+        let mut report = ship.opening_asw_report();
+        report.suggestion = ship.suggest_opening_asw_gear(&self.factory);
+        report
+    }
+
+    /// 装備の改修値を 0 から 10 まで変えた場合の比較
+    pub fn analyze_improvement(&self, ship: &Ship, key: &str) -> Option<ImprovementAnalysis> {
+        ImprovementAnalyzer {
+            battle_defs: &self.battle_defs,
+            factory: &self.factory,
+            ship,
+            key,
+        }
+        .analyze()
+    }
+
+    pub fn plan_transport_point(
+        &self,
+        comp: &Comp,
+        config: TransportPointPlannerConfig,
+    ) -> TransportPointPlan {
+        let candidates = config
+            .candidate_gear_ids
+            .iter()
+            .filter_map(|&gear_id| {
+                self.factory.create_gear(Some(GearState {
+                    gear_id,
+                    ..Default::default()
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        TransportPointPlanner {
+            comp,
+            config: &config,
+            candidates: &candidates,
+        }
+        .plan()
     }
 }
 
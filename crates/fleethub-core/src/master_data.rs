@@ -1,20 +1,61 @@
+/// 組み込みの定義を既定値とする
+///
+/// 初回のみ解析し、以降は複製を返す
+macro_rules! impl_builtin_default {
+    ($ty: ty, $json: expr, $name: literal) => {
+        impl Default for $ty {
+            fn default() -> Self {
+                static BUILTIN: std::sync::OnceLock<$ty> = std::sync::OnceLock::new();
+
+                BUILTIN
+                    .get_or_init(|| {
+                        serde_json::from_str($json).expect(concat!("invalid builtin ", $name))
+                    })
+                    .clone()
+            }
+        }
+    };
+}
+
+mod count_table;
+mod diff;
 mod master_battle_definitions;
+mod master_ebonus;
 mod master_equippability;
+mod master_expedition;
 mod master_gear;
+mod master_gunfit_accuracy;
 mod master_ibonus;
+mod master_route;
 mod master_ship;
+mod master_special_enemy_modifier;
+mod search;
+mod validation;
 
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_with::{serde_as, DefaultOnError};
 use tsify::Tsify;
 
+pub use count_table::*;
+pub use diff::*;
 pub use master_battle_definitions::*;
+pub use master_ebonus::*;
 pub use master_equippability::*;
+pub use master_expedition::*;
 pub use master_gear::*;
+pub use master_gunfit_accuracy::*;
 pub use master_ibonus::*;
+pub use master_route::*;
 pub use master_ship::*;
+pub use master_special_enemy_modifier::*;
+pub use search::*;
+pub use validation::*;
 
-use crate::types::{BattleDefinitions, CompiledEvaler, GearAttr, ShipAttr};
+use crate::{
+    comp::Comp,
+    gear_array::GearArray,
+    types::{BattleDefinitions, CompiledEvaler, EBonuses, GearAttr, ShipAttr},
+};
 
 #[serde_as]
 #[derive(Debug, Default, Clone, Deserialize, Tsify)]
@@ -37,14 +78,23 @@ struct MasterDataShadow {
     created_at: f64,
     gears: Vec<MasterGear>,
     gear_attrs: Vec<MasterAttrRule<GearAttr>>,
-    #[serde_as(as = "DefaultOnError")]
-    ships: Vec<MasterShip>,
+    ships: Vec<serde_json::Value>,
     ship_attrs: Vec<MasterAttrRule<ShipAttr>>,
     ibonuses: MasterIBonuses,
+    #[serde(default)]
+    ebonuses: MasterEBonuses,
     #[serde(alias = "equippable")]
     equippability: MasterEquippability,
     #[serde(flatten)]
     battle_definitions: MasterBattleDefinitions,
+    #[serde(default)]
+    expeditions: MasterExpeditions,
+    #[serde(default)]
+    routes: Vec<MasterNodeRoute>,
+    #[serde(default)]
+    special_enemy_modifiers: MasterSpecialEnemyModifiers,
+    #[serde(default)]
+    gunfit_accuracy: MasterGunfitAccuracy,
 }
 
 #[serde_as]
@@ -57,9 +107,49 @@ pub struct MasterData {
     pub ships: Vec<MasterShip>,
     pub ship_attrs: Vec<MasterAttrRule<ShipAttr>>,
     pub ibonuses: MasterIBonuses,
+    #[serde(default)]
+    #[tsify(type = "{ rules: MasterEBonusRule[] }")]
+    pub ebonuses: MasterEBonuses,
     pub equippability: MasterEquippability,
     #[serde(flatten)]
     pub battle_definitions: MasterBattleDefinitions,
+    #[serde(default)]
+    #[tsify(type = "ExpeditionDef[]")]
+    pub expeditions: MasterExpeditions,
+    #[serde(default)]
+    pub routes: Vec<MasterNodeRoute>,
+    #[serde(default)]
+    #[tsify(type = "SpecialEnemyModifierGroup[]")]
+    pub special_enemy_modifiers: MasterSpecialEnemyModifiers,
+    #[serde(default)]
+    #[tsify(type = "GunfitAccuracyTable")]
+    pub gunfit_accuracy: MasterGunfitAccuracy,
+    #[serde(skip)]
+    index: MasterDataIndex,
+    /// 読み込めなかった艦の位置とエラー
+    #[serde(skip)]
+    pub(crate) dropped_ships: Vec<(usize, String)>,
+}
+
+/// 艦を1件ずつ読み込み、読み込めないものは除外する
+fn parse_ships(values: Vec<serde_json::Value>) -> (Vec<MasterShip>, Vec<(usize, String)>) {
+    let mut dropped = vec![];
+
+    let ships = values
+        .into_iter()
+        .enumerate()
+        .filter_map(
+            |(index, value)| match serde_json::from_value::<MasterShip>(value) {
+                Ok(ship) => Some(ship),
+                Err(err) => {
+                    dropped.push((index, err.to_string()));
+                    None
+                }
+            },
+        )
+        .collect();
+
+    (ships, dropped)
 }
 
 impl From<MasterDataShadow> for MasterData {
@@ -68,13 +158,20 @@ impl From<MasterDataShadow> for MasterData {
             created_at,
             mut gears,
             gear_attrs,
-            mut ships,
+            ships,
             ship_attrs,
             ibonuses,
+            ebonuses,
             equippability,
             battle_definitions,
+            expeditions,
+            routes,
+            special_enemy_modifiers,
+            gunfit_accuracy,
         } = def;
 
+        let (mut ships, dropped_ships) = parse_ships(ships);
+
         ships.iter_mut().for_each(|ship| {
             ship_attrs.iter().for_each(|rule| {
                 if rule.expr.matches(&mut ship.ns()) {
@@ -91,6 +188,8 @@ impl From<MasterDataShadow> for MasterData {
             });
         });
 
+        let index = MasterDataIndex::new(&ships, &gears);
+
         Self {
             created_at,
             gears,
@@ -98,8 +197,15 @@ impl From<MasterDataShadow> for MasterData {
             ships,
             ship_attrs,
             ibonuses,
+            ebonuses,
             equippability,
             battle_definitions,
+            expeditions,
+            routes,
+            special_enemy_modifiers,
+            gunfit_accuracy,
+            index,
+            dropped_ships,
         }
     }
 }
@@ -109,8 +215,54 @@ impl MasterData {
         self.ibonuses.eval(gear, stars)
     }
 
-    pub fn create_ship_equippability(&self, ship: &MasterShip) -> ShipEquippability {
-        self.equippability.create_ship_equippability(ship)
+    pub fn get_ebonuses(&self, ship: &MasterShip, gears: &GearArray) -> EBonuses {
+        EBonuses::new(ship, gears, &self.ebonuses)
+    }
+
+    pub fn create_ship_equippability(&self, ship: &MasterShip, level: u16) -> ShipEquippability {
+        self.equippability.create_ship_equippability(ship, level)
+    }
+
+    pub fn evaluate_route(&self, map: i16, node: &str, comp: &Comp) -> Option<RouteEvaluation> {
+        self.routes
+            .iter()
+            .find(|route| route.map == map && route.node.as_str() == node)
+            .map(|route| route.evaluate(comp))
+    }
+
+    /// 未判明のステータスを同型艦、なければ同艦種の平均値で補う
+    pub fn get_fallback_stats(&self, ship: &MasterShip) -> FallbackStats {
+        let estimate = |get: fn(&MasterShip) -> Option<u16>| -> Option<u16> {
+            if get(ship).is_some() {
+                return None;
+            }
+
+            let average = |same_group: &dyn Fn(&MasterShip) -> bool| {
+                let (sum, count) = self
+                    .ships
+                    .iter()
+                    .filter(|other| {
+                        other.ship_id != ship.ship_id
+                            && other.is_abyssal() == ship.is_abyssal()
+                            && same_group(other)
+                    })
+                    .filter_map(get)
+                    .fold((0_u32, 0_u32), |(sum, count), value| {
+                        (sum + value as u32, count + 1)
+                    });
+
+                (count > 0).then(|| (sum as f64 / count as f64).round() as u16)
+            };
+
+            average(&|other| other.ctype == ship.ctype)
+                .or_else(|| average(&|other| other.stype == ship.stype))
+        };
+
+        FallbackStats {
+            max_hp: estimate(|ship| ship.max_hp.0),
+            armor: estimate(|ship| ship.armor.1),
+            evasion: estimate(|ship| ship.evasion.zipped().map(|(_, at99)| at99)),
+        }
     }
 
     pub fn battle_definitions(&self) -> BattleDefinitions {
@@ -0,0 +1,69 @@
+use fasteval::EvalNamespace;
+use serde::Deserialize;
+use tsify::Tsify;
+
+use crate::types::CompiledEvaler;
+
+/// 表の値
+///
+/// 配列の場合はその積
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[serde(untagged)]
+pub enum CountTableFactor {
+    Value(f64),
+    Product(Vec<f64>),
+}
+
+impl CountTableFactor {
+    fn value(&self) -> f64 {
+        match self {
+            Self::Value(v) => *v,
+            Self::Product(values) => values.iter().product(),
+        }
+    }
+}
+
+/// 個数に応じて決まる補正値
+///
+/// 表の場合は個数番目の値を用い、個数が表の長さを超える場合は最後の値を用いる
+#[derive(Debug, Clone, Deserialize, Tsify)]
+#[serde(untagged)]
+pub enum CountTableValue {
+    Value(f64),
+    Table(Vec<CountTableFactor>),
+    Expr(Box<CompiledEvaler>),
+}
+
+impl CountTableValue {
+    pub(crate) fn resolve<T: EvalNamespace>(&self, count: usize, ns: &mut T) -> Option<f64> {
+        match self {
+            Self::Value(v) => Some(*v),
+            Self::Table(table) => {
+                let index = count.min(table.len()).checked_sub(1)?;
+                Some(table[index].value())
+            }
+            Self::Expr(expr) => expr.eval(ns).ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_table_value() {
+        let value: CountTableValue =
+            serde_json::from_value(serde_json::json!([1.2, [1.2, 1.1]])).unwrap();
+        let mut ns = |_: &str, _: Vec<f64>| None;
+
+        assert_eq!(value.resolve(0, &mut ns), None);
+        assert_eq!(value.resolve(1, &mut ns), Some(1.2));
+        assert_eq!(value.resolve(3, &mut ns), Some(1.2 * 1.1));
+
+        let value: CountTableValue =
+            serde_json::from_value(serde_json::json!("count * 2")).unwrap();
+        let mut ns = |name: &str, _: Vec<f64>| (name == "count").then_some(3.0);
+        assert_eq!(value.resolve(3, &mut ns), Some(6.0));
+    }
+}
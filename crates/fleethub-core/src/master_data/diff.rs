@@ -0,0 +1,430 @@
+use hashbrown::HashMap;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tsify::Tsify;
+
+use super::{MasterData, MasterGear, MasterShip, StatInterval};
+
+/// 値の変更
+///
+/// 追加された場合は `old` が、削除された場合は `new` が null となる
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct MasterValueChange {
+    pub key: String,
+    #[tsify(type = "unknown")]
+    pub old: Value,
+    #[tsify(type = "unknown")]
+    pub new: Value,
+    /// 数値の場合の差分
+    #[tsify(optional)]
+    pub delta: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct MasterItemRef {
+    pub id: u16,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct MasterItemChange {
+    pub id: u16,
+    pub name: String,
+    pub changes: Vec<MasterValueChange>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Tsify)]
+pub struct MasterItemsDiff {
+    pub added: Vec<MasterItemRef>,
+    pub removed: Vec<MasterItemRef>,
+    pub changed: Vec<MasterItemChange>,
+}
+
+impl MasterItemsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// マスタデータの差分
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct MasterDataDiff {
+    pub old_created_at: f64,
+    pub new_created_at: f64,
+    pub ships: MasterItemsDiff,
+    pub gears: MasterItemsDiff,
+    pub equippability: Vec<MasterValueChange>,
+    pub ibonuses: Vec<MasterValueChange>,
+    pub battle_definitions: Vec<MasterValueChange>,
+}
+
+impl MasterDataDiff {
+    pub fn is_empty(&self) -> bool {
+        self.ships.is_empty()
+            && self.gears.is_empty()
+            && self.equippability.is_empty()
+            && self.ibonuses.is_empty()
+            && self.battle_definitions.is_empty()
+    }
+}
+
+type Entries = Vec<(String, Value)>;
+
+macro_rules! field_entries {
+    ($item: expr, [$($field: ident),* $(,)?]) => {
+        vec![$((stringify!($field).to_string(), json!($item.$field)),)*]
+    };
+}
+
+/// 初期値と上限値を別の項目とする
+fn stat_entries(entries: &mut Entries, name: &str, stat: StatInterval) {
+    entries.push((format!("{name}[0]"), json!(stat.0)));
+    entries.push((format!("{name}[1]"), json!(stat.1)));
+}
+
+fn ship_entries(ship: &MasterShip) -> Entries {
+    let mut entries = field_entries!(ship, [name, yomi, stype, ctype, nationality, sort_id]);
+
+    for (name, stat) in [
+        ("max_hp", ship.max_hp),
+        ("firepower", ship.firepower),
+        ("armor", ship.armor),
+        ("torpedo", ship.torpedo),
+        ("evasion", ship.evasion),
+        ("anti_air", ship.anti_air),
+        ("asw", ship.asw),
+        ("los", ship.los),
+        ("luck", ship.luck),
+    ] {
+        stat_entries(&mut entries, name, stat);
+    }
+
+    entries.extend(field_entries!(
+        ship,
+        [
+            torpedo_accuracy,
+            basic_evasion_term,
+            speed,
+            range,
+            fuel,
+            ammo,
+            next_id,
+            next_level,
+            slotnum,
+            slots,
+            stock,
+            speed_group,
+            useful,
+        ]
+    ));
+
+    entries.push(("attrs".into(), json!(ship.attrs.iter().collect::<Vec<_>>())));
+    entries
+}
+
+fn gear_entries(gear: &MasterGear) -> Entries {
+    let mut entries = field_entries!(
+        gear,
+        [
+            name,
+            types,
+            special_type,
+            max_hp,
+            firepower,
+            armor,
+            torpedo,
+            anti_air,
+            speed,
+            bombing,
+            asw,
+            los,
+            luck,
+            accuracy,
+            evasion,
+            range,
+            radius,
+            cost,
+            improvable,
+            ship_anti_air_resist,
+            fleet_anti_air_resist,
+            historical_aircraft_group,
+        ]
+    );
+
+    entries.push(("attrs".into(), json!(gear.attrs.iter().collect::<Vec<_>>())));
+    entries
+}
+
+fn push_change(changes: &mut Vec<MasterValueChange>, key: String, old: Value, new: Value) {
+    let delta = old.as_f64().zip(new.as_f64()).map(|(old, new)| new - old);
+
+    changes.push(MasterValueChange {
+        key,
+        old,
+        new,
+        delta,
+    });
+}
+
+/// オブジェクトは項目ごとに比較し、それ以外は値全体を比較する
+fn diff_value(changes: &mut Vec<MasterValueChange>, key: String, old: Value, new: Value) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let old = old.into_iter().collect();
+            let new = new.into_iter().collect();
+            diff_entries(changes, &format!("{key}."), old, new);
+        }
+        (old, new) => push_change(changes, key, old, new),
+    }
+}
+
+/// キーごとに比較する
+///
+/// 変更と追加は新しい方の順に、削除はその後に古い方の順に並べる
+fn diff_entries(changes: &mut Vec<MasterValueChange>, prefix: &str, old: Entries, new: Entries) {
+    let mut old_map = old.iter().cloned().collect::<HashMap<_, _>>();
+
+    for (key, new_value) in new {
+        let old_value = old_map.remove(&key).unwrap_or(Value::Null);
+        diff_value(changes, format!("{prefix}{key}"), old_value, new_value);
+    }
+
+    for (key, _) in old {
+        if let Some(old_value) = old_map.remove(&key) {
+            diff_value(changes, format!("{prefix}{key}"), old_value, Value::Null);
+        }
+    }
+}
+
+/// 配列の要素を `key` の値で対応付ける
+fn keyed_entries<T: Serialize>(name: &str, items: &[T], key: &str) -> Entries {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let value = json!(item);
+
+            let id = match value.get(key) {
+                Some(Value::String(id)) => id.clone(),
+                Some(id) if !id.is_null() => id.to_string(),
+                // 対応付けられない要素は位置で比較する
+                _ => index.to_string(),
+            };
+
+            (format!("{name}[{id}]"), value)
+        })
+        .collect()
+}
+
+fn indexed_entries<T: Serialize>(name: &str, items: &[T]) -> Entries {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| (format!("{name}[{index}]"), json!(item)))
+        .collect()
+}
+
+fn diff_items<T>(
+    old: &[T],
+    new: &[T],
+    get_id: fn(&T) -> u16,
+    get_name: fn(&T) -> &str,
+    get_entries: fn(&T) -> Entries,
+) -> MasterItemsDiff {
+    let to_ref = |item: &T| MasterItemRef {
+        id: get_id(item),
+        name: get_name(item).to_string(),
+    };
+
+    let old_map = old
+        .iter()
+        .map(|item| (get_id(item), item))
+        .collect::<HashMap<_, _>>();
+    let new_map = new
+        .iter()
+        .map(|item| (get_id(item), item))
+        .collect::<HashMap<_, _>>();
+
+    let mut diff = MasterItemsDiff::default();
+
+    for new_item in new {
+        let Some(old_item) = old_map.get(&get_id(new_item)) else {
+            diff.added.push(to_ref(new_item));
+            continue;
+        };
+
+        let mut changes = vec![];
+        diff_entries(
+            &mut changes,
+            "",
+            get_entries(old_item),
+            get_entries(new_item),
+        );
+
+        if !changes.is_empty() {
+            diff.changed.push(MasterItemChange {
+                id: get_id(new_item),
+                name: get_name(new_item).to_string(),
+                changes,
+            });
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|item| !new_map.contains_key(&get_id(item)))
+        .map(to_ref)
+        .collect();
+
+    diff
+}
+
+impl MasterData {
+    fn equippability_entries(&self) -> Entries {
+        let equippability = &self.equippability;
+
+        let mut entries = keyed_entries("equip_stype", &equippability.equip_stype, "id");
+        entries.push(("equip_exslot".into(), json!(equippability.equip_exslot)));
+        entries.extend(keyed_entries(
+            "equip_ship",
+            &equippability.equip_ship,
+            "api_ship_id",
+        ));
+        entries.extend(keyed_entries(
+            "equip_exslot_ship",
+            &equippability.equip_exslot_ship,
+            "api_slotitem_id",
+        ));
+        entries.extend(indexed_entries("rules", &equippability.rules));
+        entries
+    }
+
+    fn ibonus_entries(&self) -> Entries {
+        self.ibonuses
+            .groups()
+            .into_iter()
+            .flat_map(|(name, rules)| indexed_entries(name, rules))
+            .collect()
+    }
+
+    fn battle_definition_entries(&self) -> Entries {
+        let defs = &self.battle_definitions;
+
+        let mut entries = keyed_entries("formation", &defs.formation, "tag");
+        entries.extend(keyed_entries("anti_air_cutin", &defs.anti_air_cutin, "id"));
+        entries.extend(keyed_entries("day_cutin", &defs.day_cutin, "tag"));
+        entries.extend(keyed_entries("night_cutin", &defs.night_cutin, "tag"));
+        entries.extend(keyed_entries("fleet_cutin", &defs.fleet_cutin, "tag"));
+        entries.extend(indexed_entries(
+            "historical_bonuses",
+            &defs.historical_bonuses,
+        ));
+        entries
+    }
+
+    /// `self` を古い方として `new` との差分を求める
+    pub fn diff(&self, new: &MasterData) -> MasterDataDiff {
+        let diff_all = |get: fn(&MasterData) -> Entries| {
+            let mut changes = vec![];
+            diff_entries(&mut changes, "", get(self), get(new));
+            changes
+        };
+
+        MasterDataDiff {
+            old_created_at: self.created_at,
+            new_created_at: new.created_at,
+            ships: diff_items(
+                &self.ships,
+                &new.ships,
+                |ship| ship.ship_id,
+                |ship| &ship.name,
+                ship_entries,
+            ),
+            gears: diff_items(
+                &self.gears,
+                &new.gears,
+                |gear| gear.gear_id,
+                |gear| &gear.name,
+                gear_entries,
+            ),
+            equippability: diff_all(Self::equippability_entries),
+            ibonuses: diff_all(Self::ibonus_entries),
+            battle_definitions: diff_all(Self::battle_definition_entries),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::master_data::MasterIBonuses;
+
+    #[test]
+    fn test_diff() {
+        let ship = |ship_id: u16, firepower: u16| MasterShip {
+            ship_id,
+            name: format!("ship{ship_id}"),
+            firepower: StatInterval(Some(10), Some(firepower)),
+            ..Default::default()
+        };
+
+        let ibonuses = |formula: &str| {
+            let mut value = MasterIBonuses::default()
+                .groups()
+                .into_iter()
+                .map(|(name, _)| (name.to_string(), json!([])))
+                .collect::<serde_json::Map<_, _>>();
+
+            value.insert(
+                "shelling_power".into(),
+                json!([{ "expr": "gear_type == 1", "formula": formula }]),
+            );
+
+            serde_json::from_value::<MasterIBonuses>(Value::Object(value)).unwrap()
+        };
+
+        let old = MasterData {
+            created_at: 1.0,
+            ships: vec![ship(1, 30), ship(2, 40)],
+            ibonuses: ibonuses("sqrt(x)"),
+            ..Default::default()
+        };
+
+        let mut new = MasterData {
+            created_at: 2.0,
+            ships: vec![ship(1, 35), ship(3, 50)],
+            ibonuses: ibonuses("1.5 * sqrt(x)"),
+            ..Default::default()
+        };
+
+        new.equippability.equip_exslot = vec![17];
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.ships.added[0].id, 3);
+        assert_eq!(diff.ships.removed[0].id, 2);
+        assert_eq!(
+            diff.ships.changed,
+            vec![MasterItemChange {
+                id: 1,
+                name: "ship1".into(),
+                changes: vec![MasterValueChange {
+                    key: "firepower[1]".into(),
+                    old: json!(30),
+                    new: json!(35),
+                    delta: Some(5.0),
+                }],
+            }]
+        );
+        assert_eq!(diff.equippability[0].key, "equip_exslot");
+        assert_eq!(diff.ibonuses[0].key, "shelling_power[0].formula");
+        assert!(diff.battle_definitions.is_empty());
+
+        assert!(old.diff(&old).is_empty());
+    }
+}
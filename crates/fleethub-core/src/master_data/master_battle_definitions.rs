@@ -1,11 +1,42 @@
+use std::sync::OnceLock;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
 use serde::Deserialize;
 use tsify::Tsify;
 
 use crate::types::{
-    AntiAirCutinDef, BattleDefinitions, DayCutinDef, HistoricalBonusDef, NestedFormationDef,
-    NightCutinDef,
+    AntiAirCutinDef, BattleDefinitions, DayCutinDef, FleetCutinDef, HistoricalBonusDef,
+    NestedFormationDef, NightCutinDef,
 };
 
+const BUILTIN_FLEET_CUTINS: &str = include_str!("fleet_cutins.json");
+
+const BUILTIN_ANTI_AIR_CUTIN_REQUIREMENTS: &str = include_str!("anti_air_cutin_requirements.json");
+
+/// 組み込みの対空カットイン発動条件
+///
+/// マスタデータの定義が発動条件を持たない場合に用いる
+fn builtin_anti_air_cutin_requirements() -> &'static [AntiAirCutinDef] {
+    static BUILTIN: OnceLock<Vec<AntiAirCutinDef>> = OnceLock::new();
+
+    BUILTIN.get_or_init(|| {
+        serde_json::from_str(BUILTIN_ANTI_AIR_CUTIN_REQUIREMENTS)
+            .expect("invalid builtin anti air cutin requirements")
+    })
+}
+
+/// 組み込みの艦隊特殊攻撃の定義
+///
+/// マスタデータに艦隊特殊攻撃の定義が含まれない場合に用いる
+fn builtin_fleet_cutins() -> &'static [FleetCutinDef] {
+    static BUILTIN: OnceLock<Vec<FleetCutinDef>> = OnceLock::new();
+
+    BUILTIN.get_or_init(|| {
+        serde_json::from_str(BUILTIN_FLEET_CUTINS).expect("invalid builtin fleet cutins")
+    })
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Tsify)]
 pub struct MasterBattleDefinitions {
     pub formation: Vec<NestedFormationDef>,
@@ -13,6 +44,8 @@ pub struct MasterBattleDefinitions {
     pub day_cutin: Vec<DayCutinDef>,
     pub night_cutin: Vec<NightCutinDef>,
     #[serde(default)]
+    pub fleet_cutin: Vec<FleetCutinDef>,
+    #[serde(default)]
     pub historical_bonuses: Vec<HistoricalBonusDef>,
 }
 
@@ -24,12 +57,31 @@ impl MasterBattleDefinitions {
             .map(|def| (def.tag(), def.clone()))
             .collect();
 
-        let anti_air_cutin = self
+        let mut anti_air_cutin: HashMap<u8, AntiAirCutinDef> = self
             .anti_air_cutin
             .iter()
             .map(|def| (def.id, def.clone()))
             .collect();
 
+        for builtin in builtin_anti_air_cutin_requirements() {
+            let def = anti_air_cutin
+                .entry(builtin.id)
+                .or_insert_with(|| builtin.clone());
+
+            if def.requirements.is_empty() && def.priority.is_none() {
+                def.priority = builtin.priority;
+                def.requirements = builtin.requirements.clone();
+                def.exclusive = builtin.exclusive.clone();
+            }
+        }
+
+        let anti_air_cutin_priority = anti_air_cutin
+            .values()
+            .filter(|def| !def.requirements.is_empty())
+            .sorted_by_key(|def| (def.priority.unwrap_or(u16::MAX), def.id))
+            .map(|def| def.id)
+            .collect();
+
         let day_cutin = self
             .day_cutin
             .iter()
@@ -42,12 +94,92 @@ impl MasterBattleDefinitions {
             .map(|def| (def.tag, def.clone()))
             .collect();
 
+        let fleet_cutin = if self.fleet_cutin.is_empty() {
+            builtin_fleet_cutins().to_vec()
+        } else {
+            self.fleet_cutin.clone()
+        };
+
         BattleDefinitions {
             formation,
             anti_air_cutin,
+            anti_air_cutin_priority,
             day_cutin,
             night_cutin,
+            fleet_cutin,
             historical_bonuses: self.historical_bonuses.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::types::FleetCutin;
+
+    use super::*;
+
+    #[test]
+    fn test_anti_air_cutin_requirements() {
+        let master: MasterBattleDefinitions = serde_json::from_value(serde_json::json!({
+            "formation": [],
+            "anti_air_cutin": [
+                { "id": 1, "type_factor": 65 },
+                {
+                    "id": 100,
+                    "priority": 0,
+                    "requirements": [{ "ship": "ship_id == 1", "gears": [{ "gear": "AirRadar" }] }]
+                }
+            ],
+            "day_cutin": [],
+            "night_cutin": [],
+        }))
+        .unwrap();
+
+        let defs = master.battle_definitions();
+        let aaci1 = defs.anti_air_cutin.get(&1).unwrap();
+
+        assert_eq!(aaci1.type_factor, Some(65));
+        assert!(!aaci1.requirements.is_empty());
+        assert_eq!(defs.anti_air_cutin_priority.first(), Some(&100));
+        assert_eq!(
+            defs.anti_air_cutin_priority.len(),
+            builtin_anti_air_cutin_requirements().len() + 1
+        );
+    }
+
+    #[test]
+    fn test_fleet_cutin_defs() {
+        let json = serde_json::json!({
+            "formation": [],
+            "anti_air_cutin": [],
+            "day_cutin": [],
+            "night_cutin": [],
+        });
+
+        let master: MasterBattleDefinitions = serde_json::from_value(json.clone()).unwrap();
+        let defs = master.battle_definitions();
+
+        assert_eq!(defs.fleet_cutin.len(), builtin_fleet_cutins().len());
+
+        let mut json = json;
+        json["fleet_cutin"] = serde_json::json!([{
+            "tag": "NelsonTouch",
+            "formations": ["DoubleLine"],
+            "participants": [{ "position": 0, "ship": "ship_class == 88" }],
+            "attacks": [{ "position": 0, "power_mods": [{ "a": 2.0 }] }],
+        }]);
+
+        let master: MasterBattleDefinitions = serde_json::from_value(json.clone()).unwrap();
+        let defs = master.battle_definitions();
+
+        assert_eq!(defs.fleet_cutin.len(), 1);
+        assert_eq!(defs.fleet_cutin[0].tag, FleetCutin::NELSON_TOUCH);
+        assert!(defs.fleet_cutin[0].rate.is_empty());
+
+        json["fleet_cutin"][0]["tag"] = "QueenElizabethTouch".into();
+        let master: MasterBattleDefinitions = serde_json::from_value(json).unwrap();
+        let defs = master.battle_definitions();
+
+        assert_eq!(defs.fleet_cutin[0].tag.as_str(), "QueenElizabethTouch");
+    }
+}
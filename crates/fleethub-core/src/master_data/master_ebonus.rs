@@ -0,0 +1,266 @@
+use std::{str::FromStr, sync::Arc};
+
+use fasteval::EvalNamespace;
+use serde::Deserialize;
+use tsify::Tsify;
+
+use crate::{
+    gear::Gear,
+    types::{CompiledEvaler, EBonuses, GearAttr},
+};
+
+use super::MasterShip;
+
+const BUILTIN_EBONUSES: &str = include_str!("ebonuses.json");
+
+#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[serde(default)]
+pub struct MasterEBonusRule {
+    pub name: String,
+    /// 対象艦の条件
+    pub ship: CompiledEvaler,
+    /// 対象装備の条件
+    ///
+    /// 空の場合は全ての装備が対象となる
+    pub gear: CompiledEvaler,
+    /// 装備構成に対する追加条件
+    pub requires: CompiledEvaler,
+    /// 対象装備がこの数以上の場合に適用する
+    pub min_count: usize,
+    /// 対象装備1つ毎に加算する
+    pub per_gear: bool,
+    /// 加算する対象装備の上限
+    pub max_count: Option<usize>,
+    pub bonus: EBonuses,
+}
+
+impl MasterEBonusRule {
+    fn matches_ship(&self, ship: &MasterShip) -> bool {
+        self.ship.is_empty() || self.ship.matches(&mut ship.ns())
+    }
+
+    fn matches_gear(&self, gear: &Gear) -> bool {
+        if self.gear.is_empty() {
+            return true;
+        }
+
+        let mut gear_ns = gear.ns();
+
+        let mut ns = |name: &str, args: Vec<f64>| match name {
+            "stars" => Some(gear.stars as f64),
+            "ace" => Some(gear.ace() as f64),
+            _ => gear_ns.lookup(name, args, &mut String::new()),
+        };
+
+        self.gear.matches(&mut ns)
+    }
+
+    fn satisfies_requires(&self, gears: &[&Gear]) -> bool {
+        if self.requires.is_empty() {
+            return true;
+        }
+
+        let count_by = |f: &dyn Fn(&Gear) -> bool| gears.iter().filter(|gear| f(gear)).count();
+
+        let mut ns = |name: &str, args: Vec<f64>| -> Option<f64> {
+            let count = match name {
+                "count_gear_id" => count_by(&|gear| args.contains(&(gear.gear_id as f64))),
+                "count_gear_type" => {
+                    count_by(&|gear| args.contains(&(gear.types.gear_type_id() as f64)))
+                }
+                _ => {
+                    let attr = GearAttr::from_str(name).ok()?;
+                    count_by(&|gear| gear.has_attr(attr))
+                }
+            };
+
+            Some(count as f64)
+        };
+
+        self.requires.matches(&mut ns)
+    }
+
+    fn times(&self, gears: &[&Gear]) -> usize {
+        let count = gears.iter().filter(|gear| self.matches_gear(gear)).count();
+
+        if count < self.min_count.max(1) || !self.satisfies_requires(gears) {
+            return 0;
+        }
+
+        if self.per_gear {
+            self.max_count.map_or(count, |max| count.min(max))
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MasterEBonusesDef {
+    rules: Vec<MasterEBonusRule>,
+    #[serde(default)]
+    complete: bool,
+}
+
+/// 装備ボーナスの定義
+///
+/// マスタデータに含まれない場合は組み込みの定義を用いる
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "MasterEBonusesDef")]
+pub struct MasterEBonuses {
+    pub rules: Arc<Vec<MasterEBonusRule>>,
+    /// `equipment-bonus` の全ての補正を網羅しているか
+    ///
+    /// 網羅していない場合は `equipment-bonus` が利用できればそちらを優先する
+    pub complete: bool,
+}
+
+impl From<MasterEBonusesDef> for MasterEBonuses {
+    fn from(def: MasterEBonusesDef) -> Self {
+        Self {
+            rules: Arc::new(def.rules),
+            complete: def.complete,
+        }
+    }
+}
+
+impl_builtin_default!(MasterEBonuses, BUILTIN_EBONUSES, "equipment bonuses");
+
+impl MasterEBonuses {
+    /// 艦と装備の組み合わせによるステータス補正
+    ///
+    /// 速力補正と航空攻撃の補正は含まない
+    pub fn eval<'a>(
+        &self,
+        ship: &MasterShip,
+        gears: impl IntoIterator<Item = &'a Gear>,
+    ) -> EBonuses {
+        let gears = gears.into_iter().collect::<Vec<_>>();
+        let mut ebonuses = EBonuses::default();
+
+        if gears.is_empty() {
+            return ebonuses;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches_ship(ship))
+            .for_each(|rule| {
+                let times = rule.times(&gears);
+
+                if times > 0 {
+                    ebonuses.add_times(&rule.bonus, times as i16);
+                }
+            });
+
+        ebonuses
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::{ctype, gear_id};
+
+    use super::*;
+
+    fn rules(json: serde_json::Value) -> MasterEBonuses {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let ebonuses = rules(serde_json::json!({
+            "rules": [
+                {
+                    "ship": "ship_class == 1",
+                    "gear": "gear_id == 10",
+                    "per_gear": true,
+                    "max_count": 2,
+                    "bonus": { "firepower": 1 }
+                },
+                {
+                    "ship": "ship_class == 1",
+                    "gear": "gear_id == 10 && stars >= 4",
+                    "bonus": { "anti_air": 2 }
+                },
+                {
+                    "gear": "gear_id == 10",
+                    "requires": "count_gear_id(20) >= 1",
+                    "bonus": { "evasion": 3, "range": 1 }
+                },
+                {
+                    "gear": "gear_id == 10",
+                    "min_count": 3,
+                    "bonus": { "torpedo": 5 }
+                },
+                {
+                    "requires": "count_gear_id(10) >= 1 && count_gear_id(20) >= 1",
+                    "bonus": { "los": 2 }
+                }
+            ]
+        }));
+
+        let ship = MasterShip {
+            ctype: 1,
+            ..Default::default()
+        };
+        let other_ship = MasterShip {
+            ctype: 2,
+            ..Default::default()
+        };
+
+        let gear = |gear_id: u16, stars: u8| Gear {
+            gear_id,
+            stars,
+            ..Default::default()
+        };
+
+        let g1 = gear(10, 0);
+        let g2 = gear(10, 4);
+        let g3 = gear(10, 0);
+        let g4 = gear(20, 0);
+
+        let result = ebonuses.eval(&ship, [&g1]);
+        assert_eq!(result.firepower, 1);
+        assert_eq!(result.anti_air, 0);
+
+        let result = ebonuses.eval(&ship, [&g1, &g2, &g3]);
+        assert_eq!(result.firepower, 2);
+        assert_eq!(result.anti_air, 2);
+        assert_eq!(result.torpedo, 5);
+        assert_eq!(result.evasion, 0);
+
+        let result = ebonuses.eval(&other_ship, [&g1, &g4]);
+        assert_eq!(result.firepower, 0);
+        assert_eq!(result.evasion, 3);
+        assert_eq!(result.range, 1);
+        assert_eq!(result.los, 2);
+
+        let result = ebonuses.eval(&other_ship, [&g4]);
+        assert_eq!(result.los, 0);
+    }
+
+    #[test]
+    fn test_builtin() {
+        let ebonuses = MasterEBonuses::default();
+        assert!(!ebonuses.rules.is_empty());
+        // 組み込みの定義は一部の補正のみ
+        assert!(!ebonuses.complete);
+
+        let yuugumo_class = MasterShip {
+            ctype: ctype!("夕雲型"),
+            ..Default::default()
+        };
+        let gun = Gear {
+            gear_id: gear_id!("12.7cm連装砲D型改二"),
+            ..Default::default()
+        };
+
+        let result = ebonuses.eval(&yuugumo_class, [&gun, &gun]);
+        assert_eq!(result.firepower, 4);
+        assert_eq!(result.evasion, 2);
+
+        let result = ebonuses.eval(&MasterShip::default(), [&gun]);
+        assert_eq!(result.firepower, 0);
+    }
+}
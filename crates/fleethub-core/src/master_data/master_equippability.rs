@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
 use crate::{
@@ -9,22 +9,25 @@ use crate::{
 
 use super::MasterShip;
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 pub struct EquipStype {
     pub id: u8,
     pub equip_type: Vec<u8>,
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 pub struct MstEquipShip {
     pub api_ship_id: u16,
     pub api_equip_type: Vec<u8>,
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 pub struct MstEquipExslotShip {
     pub api_slotitem_id: u16,
     pub api_ship_ids: Vec<u16>,
+    /// 補強増設に装備するために必要な艦のレベル
+    #[serde(default)]
+    pub api_req_level: u16,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Tsify)]
@@ -36,7 +39,7 @@ pub struct MasterEquippability {
     pub rules: Vec<EquippabilityRule>,
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 pub struct EquippabilityRule {
     pub ship: CompiledEvaler,
     #[serde(default)]
@@ -48,7 +51,7 @@ pub struct EquippabilityRule {
 }
 
 impl MasterEquippability {
-    pub fn create_ship_equippability(&self, ship: &MasterShip) -> ShipEquippability {
+    pub fn create_ship_equippability(&self, ship: &MasterShip, level: u16) -> ShipEquippability {
         if ship.is_abyssal() {
             return ShipEquippability::abyssal();
         }
@@ -72,7 +75,7 @@ impl MasterEquippability {
         let exslot_gear_ids = self
             .equip_exslot_ship
             .iter()
-            .filter(|ees| ees.api_ship_ids.contains(&ship.ship_id))
+            .filter(|ees| ees.api_ship_ids.contains(&ship.ship_id) && ees.api_req_level <= level)
             .map(|ees| ees.api_slotitem_id)
             .collect();
 
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::expedition::ExpeditionDef;
+
+const BUILTIN_EXPEDITIONS: &str = include_str!("expeditions.json");
+
+/// 遠征の定義
+///
+/// マスタデータに含まれない場合は組み込みの定義を用いる
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "Vec<ExpeditionDef>")]
+pub struct MasterExpeditions(Arc<Vec<ExpeditionDef>>);
+
+impl From<Vec<ExpeditionDef>> for MasterExpeditions {
+    fn from(defs: Vec<ExpeditionDef>) -> Self {
+        Self(Arc::new(defs))
+    }
+}
+
+impl_builtin_default!(MasterExpeditions, BUILTIN_EXPEDITIONS, "expeditions");
+
+impl MasterExpeditions {
+    pub fn defs(&self) -> &[ExpeditionDef] {
+        &self.0
+    }
+
+    pub fn get(&self, id: u16) -> Option<&ExpeditionDef> {
+        self.0.iter().find(|def| def.id == id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builtin_expeditions() {
+        let expeditions = MasterExpeditions::default();
+
+        let ids = expeditions
+            .defs()
+            .iter()
+            .map(|def| def.id)
+            .collect::<Vec<_>>();
+        let mut sorted = ids.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(ids, sorted);
+        assert!((1..=46).all(|id| expeditions.get(id).is_some()));
+
+        let def = expeditions.get(21).unwrap();
+        assert_eq!(def.name, "北方鼠輸送作戦");
+        assert_eq!(def.resources, [320, 270, 0, 0]);
+        assert_eq!(def.drum_ship_count, 3);
+
+        let def = expeditions.get(101).unwrap();
+        assert_eq!(def.name, "海峡警備行動");
+        assert_eq!(def.asw, Some(180));
+    }
+}
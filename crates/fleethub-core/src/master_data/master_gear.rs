@@ -11,6 +11,12 @@ use crate::types::{gear_id, GearAttr, GearType, GearTypeIdArray};
 pub struct MasterGear {
     pub gear_id: u16,
     pub name: String,
+    /// 検索用の読み
+    #[serde(default)]
+    pub yomi: String,
+    /// 検索用の別名 (例: `"46砲"`)
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub types: GearTypeIdArray,
     #[tsify(optional)]
     pub special_type: Option<u8>,
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use fasteval::EvalNamespace;
+use serde::Deserialize;
+use tsify::Tsify;
+
+use crate::{ship::Ship, types::CompiledEvaler};
+
+use super::CountTableValue;
+
+const BUILTIN_GUNFIT_ACCURACY: &str = include_str!("gunfit_accuracy.json");
+
+/// 艦のグループ
+#[derive(Debug, Clone, Deserialize, Tsify)]
+pub struct GunfitAccuracyShipGroup {
+    pub name: String,
+    /// 艦の条件式
+    pub ship: CompiledEvaler,
+}
+
+/// 砲のグループ
+#[derive(Debug, Clone, Deserialize, Tsify)]
+pub struct GunfitAccuracyGunGroup {
+    pub name: String,
+    pub gears: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Deserialize, Tsify)]
+pub struct GunfitAccuracyEntry {
+    pub ship_group: String,
+    pub gun_group: String,
+    /// 昼戦の補正値
+    #[serde(default)]
+    pub day: Option<CountTableValue>,
+    /// 夜戦の補正値
+    #[serde(default)]
+    pub night: Option<CountTableValue>,
+    /// ケッコンカッコカリ時の倍率
+    #[serde(default = "num_traits::one")]
+    pub married_mod: f64,
+}
+
+/// フィット砲補正の表
+///
+/// 艦のグループと砲のグループの組み合わせごとに、装備数に応じた補正値を定義する
+#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+pub struct GunfitAccuracyTable {
+    pub ship_groups: Vec<GunfitAccuracyShipGroup>,
+    pub gun_groups: Vec<GunfitAccuracyGunGroup>,
+    pub entries: Vec<GunfitAccuracyEntry>,
+}
+
+/// 表から求めた艦のフィット砲補正
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GunfitAccuracyBonus {
+    pub day: f64,
+    pub night: f64,
+}
+
+/// フィット砲補正の定義
+///
+/// マスタデータに含まれない場合は組み込みの定義を用いる
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "GunfitAccuracyTable")]
+pub struct MasterGunfitAccuracy(Arc<GunfitAccuracyTable>);
+
+impl From<GunfitAccuracyTable> for MasterGunfitAccuracy {
+    fn from(table: GunfitAccuracyTable) -> Self {
+        Self(Arc::new(table))
+    }
+}
+
+impl_builtin_default!(
+    MasterGunfitAccuracy,
+    BUILTIN_GUNFIT_ACCURACY,
+    "gunfit accuracy"
+);
+
+impl MasterGunfitAccuracy {
+    pub fn table(&self) -> &GunfitAccuracyTable {
+        &self.0
+    }
+
+    /// 艦の装備によるフィット砲補正
+    pub fn eval(&self, ship: &Ship) -> GunfitAccuracyBonus {
+        GunfitAccuracyBonus {
+            day: self.eval_phase(ship, false),
+            night: self.eval_phase(ship, true),
+        }
+    }
+
+    fn eval_phase(&self, ship: &Ship, is_night: bool) -> f64 {
+        let table = &self.0;
+        let gears = &ship.gears;
+        let is_married = ship.level > 99;
+
+        let mut ship_ns = ship.master.ns();
+        let mut ship_group_matches: Vec<Option<bool>> = vec![None; table.ship_groups.len()];
+
+        let mut matches_ship_group = |name: &str| -> bool {
+            let Some(index) = table.ship_groups.iter().position(|g| g.name == name) else {
+                return false;
+            };
+
+            *ship_group_matches[index].get_or_insert_with(|| {
+                let mut ns = |name: &str, args: Vec<f64>| -> Option<f64> {
+                    match name {
+                        "level" => Some(ship.level.into()),
+                        _ => ship_ns.lookup(name, args, &mut String::new()),
+                    }
+                };
+
+                table.ship_groups[index].ship.matches(&mut ns)
+            })
+        };
+
+        table
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let value = if is_night { &entry.night } else { &entry.day };
+                let value = value.as_ref()?;
+
+                if !matches_ship_group(&entry.ship_group) {
+                    return None;
+                }
+
+                let gun_group = table
+                    .gun_groups
+                    .iter()
+                    .find(|g| g.name == entry.gun_group)?;
+
+                let count = gears.count_by(|gear| gun_group.gears.contains(&gear.gear_id));
+
+                if count == 0 {
+                    return None;
+                }
+
+                let mut ns = |name: &str, args: Vec<f64>| -> Option<f64> {
+                    let result = match name {
+                        "count" => count as f64,
+                        "sqrt" => args.first()?.sqrt(),
+                        "count_gear_id" => {
+                            gears.count_by(|gear| args.contains(&(gear.gear_id as f64))) as f64
+                        }
+                        _ => return None,
+                    };
+
+                    Some(result)
+                };
+
+                let value = value.resolve(count, &mut ns)?;
+
+                if is_married {
+                    Some(value * entry.married_mod)
+                } else {
+                    Some(value)
+                }
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gear::Gear;
+
+    use super::*;
+
+    #[test]
+    fn test_gunfit_accuracy() {
+        let table: MasterGunfitAccuracy = serde_json::from_value(serde_json::json!({
+            "ship_groups": [{ "name": "戦艦", "ship": "ship_type == 9" }],
+            "gun_groups": [{ "name": "46cm砲", "gears": [9] }],
+            "entries": [
+                { "ship_group": "戦艦", "gun_group": "46cm砲", "day": [-3, -5], "married_mod": 0.5 },
+                { "ship_group": "戦艦", "gun_group": "46cm砲", "night": "-2 * count" },
+            ],
+        }))
+        .unwrap();
+
+        let mut ship = Ship::default();
+        ship.master.stype = 9;
+
+        assert_eq!(table.eval(&ship), GunfitAccuracyBonus::default());
+
+        for _ in 0..3 {
+            ship.gears.push(Gear {
+                gear_id: 9,
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(
+            table.eval(&ship),
+            GunfitAccuracyBonus {
+                day: -5.0,
+                night: -6.0
+            }
+        );
+
+        ship.level = 100;
+        assert_eq!(table.eval(&ship).day, -2.5);
+
+        ship.master.stype = 8;
+        assert_eq!(table.eval(&ship), GunfitAccuracyBonus::default());
+    }
+}
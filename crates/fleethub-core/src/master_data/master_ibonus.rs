@@ -5,7 +5,7 @@ use crate::types::CompiledEvaler;
 
 use super::master_gear::MasterGear;
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 pub struct MasterIBonusRule {
     pub expr: CompiledEvaler,
     pub formula: CompiledEvaler,
@@ -40,6 +40,11 @@ macro_rules! impl_ibonuses {
         }
 
         impl MasterIBonuses {
+            /// 補正の種類ごとのルール
+            pub fn groups(&self) -> Vec<(&'static str, &[MasterIBonusRule])> {
+                vec![$((stringify!($key), self.$key.as_slice()),)*]
+            }
+
             pub fn eval(&self, gear: &MasterGear, stars: u8) -> IBonuses {
                 let calc = |rules: &Vec<MasterIBonusRule>| {
                     rules
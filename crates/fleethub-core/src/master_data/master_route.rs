@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    comp::Comp,
+    types::{CompiledEvaler, NodeId},
+};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+pub struct RouteChance {
+    pub node: NodeId,
+    pub rate: f64,
+}
+
+/// 分岐条件
+///
+/// 条件式が空の場合は常に適用される
+#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[serde(default)]
+pub struct RouteBranchRule {
+    pub expr: CompiledEvaler,
+    pub routes: Vec<RouteChance>,
+}
+
+/// マスごとの分岐ルール
+///
+/// 上から順に判定し、最初に条件を満たしたルールの行き先となる
+#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[serde(default)]
+pub struct MasterNodeRoute {
+    pub map: i16,
+    pub node: NodeId,
+    pub rules: Vec<RouteBranchRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct RouteEvaluation {
+    pub map: i16,
+    pub node: NodeId,
+    /// 適用されたルールの位置
+    pub rule_index: Option<usize>,
+    pub routes: Vec<RouteChance>,
+    /// 評価できなかった条件式
+    pub errors: Vec<String>,
+}
+
+impl MasterNodeRoute {
+    pub fn evaluate(&self, comp: &Comp) -> RouteEvaluation {
+        let mut errors = vec![];
+
+        let found = self.rules.iter().enumerate().find(|(_, rule)| {
+            if rule.expr.is_empty() {
+                return true;
+            }
+
+            match rule.expr.eval(&mut comp.ns()) {
+                Ok(value) => value == 1.0,
+                Err(err) => {
+                    errors.push(err.to_string());
+                    false
+                }
+            }
+        });
+
+        let (rule_index, routes) = match found {
+            Some((index, rule)) => {
+                let total = rule.routes.iter().map(|route| route.rate).sum::<f64>();
+
+                let routes = rule
+                    .routes
+                    .iter()
+                    .map(|route| RouteChance {
+                        node: route.node,
+                        rate: if total > 0.0 { route.rate / total } else { 0.0 },
+                    })
+                    .collect();
+
+                (Some(index), routes)
+            }
+            None => (None, vec![]),
+        };
+
+        RouteEvaluation {
+            map: self.map,
+            node: self.node,
+            rule_index,
+            routes,
+            errors,
+        }
+    }
+}
@@ -3,12 +3,12 @@ use std::str::FromStr;
 use arrayvec::ArrayVec;
 use enumset::EnumSet;
 use fasteval::{bool_to_f64, EvalNamespace};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
 use crate::types::{GearState, ShipAttr, ShipType, SlotSizeVec, SpeedGroup};
 
-#[derive(Debug, Default, Clone, Copy, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Tsify)]
 pub struct StatInterval(pub Option<u16>, pub Option<u16>);
 
 impl StatInterval {
@@ -22,6 +22,14 @@ impl StatInterval {
     }
 }
 
+/// 未判明のステータスの代わりに用いる、類似艦から推定した素の値
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FallbackStats {
+    pub max_hp: Option<u16>,
+    pub armor: Option<u16>,
+    pub evasion: Option<u16>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Tsify)]
 pub struct MasterShip {
     pub ship_id: u16,
@@ -86,6 +94,9 @@ impl MasterShip {
                 "ship_class_in" => {
                     bool_to_f64!(args.contains(&(self.ctype.into())))
                 }
+                "nationality_in" => bool_to_f64!(args.contains(&(self.nationality.into()))),
+                "slotnum" => self.slotnum as f64,
+                "is_abyssal" => bool_to_f64!(self.is_abyssal()),
 
                 _ => {
                     let attr = ShipAttr::from_str(key).ok()?;
@@ -0,0 +1,235 @@
+use std::{str::FromStr, sync::Arc};
+
+use fasteval::{bool_to_f64, EvalNamespace};
+use serde::Deserialize;
+use tsify::Tsify;
+
+use crate::{
+    ship::Ship,
+    types::{
+        AttackPowerModifier, AttackType, CompiledEvaler, GearAttr, GearType, NightAttackType,
+        ShellingType, SpecialEnemyModifiers, SpecialEnemyType,
+    },
+};
+
+use super::CountTableValue;
+
+const BUILTIN_SPECIAL_ENEMY_MODIFIERS: &str = include_str!("special_enemy_modifiers.json");
+
+/// 適用先の補正
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialEnemyModifierKey {
+    PrecapGeneral,
+    Stype,
+    LandingCraftSynergy,
+    TokuDaihatsuTank,
+    M4a1dd,
+    Honi,
+    PostcapGeneral,
+    Pt,
+}
+
+#[derive(Debug, Clone, Deserialize, Tsify)]
+pub struct SpecialEnemyModifierRule {
+    pub key: SpecialEnemyModifierKey,
+    /// 適用条件
+    ///
+    /// 空の場合は常に適用される
+    #[serde(default)]
+    pub expr: CompiledEvaler,
+    /// 表の参照に用いる個数
+    ///
+    /// 空の場合は1として扱い、0以下の場合は適用しない
+    #[serde(default)]
+    pub count: CompiledEvaler,
+    /// 乗算する値
+    #[serde(default)]
+    pub a: Option<CountTableValue>,
+    /// 加算する値
+    #[serde(default)]
+    pub b: Option<CountTableValue>,
+}
+
+/// 特効補正のルール
+///
+/// 対象の特殊敵種別に対して、上から順に条件を満たしたルールを適用する
+#[derive(Debug, Clone, Deserialize, Tsify)]
+pub struct SpecialEnemyModifierGroup {
+    #[serde(default)]
+    pub name: String,
+    pub targets: Vec<SpecialEnemyType>,
+    /// 攻撃艦と攻撃種別の条件
+    #[serde(default)]
+    pub expr: CompiledEvaler,
+    pub rules: Vec<SpecialEnemyModifierRule>,
+}
+
+/// 特効補正の定義
+///
+/// マスタデータに含まれない場合は組み込みの定義を用いる
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "Vec<SpecialEnemyModifierGroup>")]
+pub struct MasterSpecialEnemyModifiers(Arc<Vec<SpecialEnemyModifierGroup>>);
+
+impl From<Vec<SpecialEnemyModifierGroup>> for MasterSpecialEnemyModifiers {
+    fn from(groups: Vec<SpecialEnemyModifierGroup>) -> Self {
+        Self(Arc::new(groups))
+    }
+}
+
+impl_builtin_default!(
+    MasterSpecialEnemyModifiers,
+    BUILTIN_SPECIAL_ENEMY_MODIFIERS,
+    "special enemy modifiers"
+);
+
+pub(super) fn ns<'a>(ship: &'a Ship, attack_type: AttackType) -> impl EvalNamespace + 'a {
+    let mut ship_ns = ship.master.ns();
+
+    move |name: &str, args: Vec<f64>| -> Option<f64> {
+        let gears = &ship.gears;
+        let matches_gear_id = |gear_id: u16| args.contains(&(gear_id as f64));
+        let matches_gear_type = |gear_type: GearType| args.contains(&(gear_type as u8 as f64));
+
+        let result = match name {
+            "shelling" => bool_to_f64!(matches!(attack_type, AttackType::Shelling(_))),
+            "night" => bool_to_f64!(matches!(attack_type, AttackType::Night(_))),
+            "torpedo" => bool_to_f64!(matches!(attack_type, AttackType::Torpedo)),
+            "support_shelling" => {
+                bool_to_f64!(matches!(attack_type, AttackType::SupportShelling(_)))
+            }
+            "asw" => bool_to_f64!(matches!(attack_type, AttackType::Asw(_))),
+            "aerial" => bool_to_f64!(matches!(
+                attack_type,
+                AttackType::Shelling(ShellingType::Aerial)
+                    | AttackType::Night(NightAttackType::Aerial)
+            )),
+
+            "count_gear_id" => gears.count_by(|gear| matches_gear_id(gear.gear_id)) as f64,
+            "count_gear_type" => gears.count_by(|gear| matches_gear_type(gear.gear_type)) as f64,
+            "mean_stars_gear_id" => gears
+                .mean_by(|gear| matches_gear_id(gear.gear_id).then_some(gear.stars as f64))
+                .unwrap_or_default(),
+            "mean_stars_gear_type" => gears
+                .mean_by(|gear| matches_gear_type(gear.gear_type).then_some(gear.stars as f64))
+                .unwrap_or_default(),
+
+            _ => match GearAttr::from_str(name) {
+                Ok(attr) => gears.count_attr(attr) as f64,
+                Err(_) => return ship_ns.lookup(name, args, &mut String::new()),
+            },
+        };
+
+        Some(result)
+    }
+}
+
+fn get_modifier(
+    mods: &mut SpecialEnemyModifiers,
+    key: SpecialEnemyModifierKey,
+) -> &mut AttackPowerModifier {
+    match key {
+        SpecialEnemyModifierKey::PrecapGeneral => &mut mods.precap_general_mod,
+        SpecialEnemyModifierKey::Stype => &mut mods.stype_mod,
+        SpecialEnemyModifierKey::LandingCraftSynergy => &mut mods.landing_craft_synergy_mod,
+        SpecialEnemyModifierKey::TokuDaihatsuTank => &mut mods.toku_daihatsu_tank_mod,
+        SpecialEnemyModifierKey::M4a1dd => &mut mods.m4a1dd_mod,
+        SpecialEnemyModifierKey::Honi => &mut mods.honi_mod,
+        SpecialEnemyModifierKey::PostcapGeneral => &mut mods.postcap_general_mod,
+        SpecialEnemyModifierKey::Pt => mods.pt_mod.get_or_insert_with(Default::default),
+    }
+}
+
+impl SpecialEnemyModifierRule {
+    fn apply<T: EvalNamespace>(&self, mods: &mut SpecialEnemyModifiers, ns: &mut T) {
+        if !self.expr.is_empty() && !self.expr.matches(ns) {
+            return;
+        }
+
+        let count = if self.count.is_empty() {
+            1
+        } else {
+            self.count.eval(ns).unwrap_or_default().max(0.0) as usize
+        };
+
+        if count == 0 {
+            return;
+        }
+
+        let a = self.a.as_ref().and_then(|a| a.resolve(count, ns));
+        let b = self.b.as_ref().and_then(|b| b.resolve(count, ns));
+
+        let modifier = get_modifier(mods, self.key);
+
+        if let Some(a) = a {
+            modifier.a *= a;
+        }
+
+        if let Some(b) = b {
+            modifier.b += b;
+        }
+    }
+}
+
+impl MasterSpecialEnemyModifiers {
+    pub fn groups(&self) -> &[SpecialEnemyModifierGroup] {
+        &self.0
+    }
+
+    /// 攻撃艦と特殊敵種別、攻撃種別による特効補正
+    pub fn eval(
+        &self,
+        attacker: &Ship,
+        special_enemy_type: SpecialEnemyType,
+        attack_type: AttackType,
+    ) -> SpecialEnemyModifiers {
+        let mut mods = SpecialEnemyModifiers::new();
+        let mut ns = ns(attacker, attack_type);
+
+        for group in self.0.iter() {
+            if !group.targets.contains(&special_enemy_type) {
+                continue;
+            }
+
+            if !group.expr.is_empty() && !group.expr.matches(&mut ns) {
+                continue;
+            }
+
+            for rule in &group.rules {
+                rule.apply(&mut mods, &mut ns);
+            }
+        }
+
+        mods
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_special_enemy_modifier_rule() {
+        assert!(!MasterSpecialEnemyModifiers::default().groups().is_empty());
+
+        let rule: SpecialEnemyModifierRule = serde_json::from_value(serde_json::json!({
+            "key": "pt",
+            "count": "count_gear_id(1)",
+            "a": [1.2, [1.2, 1.1]],
+            "b": "count_gear_id(1) * 2",
+        }))
+        .unwrap();
+
+        let apply = |count: f64| {
+            let mut mods = SpecialEnemyModifiers::new();
+            let mut ns = |_: &str, _: Vec<f64>| Some(count);
+            rule.apply(&mut mods, &mut ns);
+            mods.pt_mod
+        };
+
+        assert_eq!(apply(0.0), None);
+        assert_eq!(apply(1.0), Some(AttackPowerModifier::new(1.2, 2.0)));
+        assert_eq!(apply(3.0), Some(AttackPowerModifier::new(1.2 * 1.1, 6.0)));
+    }
+}
@@ -0,0 +1,578 @@
+use std::borrow::Cow;
+
+use fasteval::EvalNamespace;
+use hashbrown::HashMap;
+use serde::Serialize;
+use tsify::Tsify;
+
+use super::{MasterData, MasterGear, MasterShip, StatInterval};
+use crate::types::CompiledEvaler;
+
+/// 検索用に正規化した名前
+#[derive(Debug, Default, Clone)]
+struct SearchKeys {
+    name: String,
+    yomi: String,
+    romaji: String,
+    aliases: Vec<String>,
+}
+
+impl SearchKeys {
+    fn ship(ship: &MasterShip) -> Self {
+        let yomi = normalize(&ship.yomi);
+        let romaji = to_romaji(&yomi);
+
+        Self {
+            name: normalize(&ship.name),
+            yomi,
+            romaji,
+            aliases: vec![],
+        }
+    }
+
+    fn gear(gear: &MasterGear) -> Self {
+        let yomi = normalize(&gear.yomi);
+        let romaji = to_romaji(&yomi);
+
+        Self {
+            name: normalize(&gear.name),
+            yomi,
+            romaji,
+            aliases: gear.aliases.iter().map(|alias| normalize(alias)).collect(),
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &str> {
+        [&self.name, &self.yomi, &self.romaji]
+            .into_iter()
+            .chain(&self.aliases)
+            .map(String::as_str)
+            .filter(|key| !key.is_empty())
+    }
+
+    fn score(&self, query: &str) -> f64 {
+        self.keys()
+            .map(|key| similarity(query, key))
+            .fold(0.0, f64::max)
+    }
+}
+
+/// 艦と装備の ID と名前の索引
+///
+/// マスタデータの読み込み時に作成する
+#[derive(Debug, Default, Clone)]
+pub struct MasterDataIndex {
+    ships_by_id: HashMap<u16, usize>,
+    gears_by_id: HashMap<u16, usize>,
+    ship_keys: Vec<SearchKeys>,
+    gear_keys: Vec<SearchKeys>,
+}
+
+impl MasterDataIndex {
+    pub fn new(ships: &[MasterShip], gears: &[MasterGear]) -> Self {
+        Self {
+            ships_by_id: ships
+                .iter()
+                .enumerate()
+                .map(|(index, ship)| (ship.ship_id, index))
+                .collect(),
+            gears_by_id: gears
+                .iter()
+                .enumerate()
+                .map(|(index, gear)| (gear.gear_id, index))
+                .collect(),
+            ship_keys: ships.iter().map(SearchKeys::ship).collect(),
+            gear_keys: gears.iter().map(SearchKeys::gear).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct MasterSearchHit {
+    pub id: u16,
+    pub name: String,
+    /// 一致度 (0.0 - 1.0)
+    pub score: f64,
+}
+
+impl MasterData {
+    /// 艦や装備を変更した後に索引を作り直す
+    pub fn rebuild_index(&mut self) {
+        self.index = MasterDataIndex::new(&self.ships, &self.gears);
+    }
+
+    pub fn get_ship(&self, ship_id: u16) -> Option<&MasterShip> {
+        self.index
+            .ships_by_id
+            .get(&ship_id)
+            .and_then(|&index| self.ships.get(index))
+            .filter(|ship| ship.ship_id == ship_id)
+            .or_else(|| self.ships.iter().find(|ship| ship.ship_id == ship_id))
+    }
+
+    pub fn get_gear(&self, gear_id: u16) -> Option<&MasterGear> {
+        self.index
+            .gears_by_id
+            .get(&gear_id)
+            .and_then(|&index| self.gears.get(index))
+            .filter(|gear| gear.gear_id == gear_id)
+            .or_else(|| self.gears.iter().find(|gear| gear.gear_id == gear_id))
+    }
+
+    fn ship_keys(&self) -> Cow<'_, [SearchKeys]> {
+        if self.index.ship_keys.len() == self.ships.len() {
+            Cow::Borrowed(&self.index.ship_keys)
+        } else {
+            Cow::Owned(self.ships.iter().map(SearchKeys::ship).collect())
+        }
+    }
+
+    fn gear_keys(&self) -> Cow<'_, [SearchKeys]> {
+        if self.index.gear_keys.len() == self.gears.len() {
+            Cow::Borrowed(&self.index.gear_keys)
+        } else {
+            Cow::Owned(self.gears.iter().map(SearchKeys::gear).collect())
+        }
+    }
+
+    /// 艦名、読み、ローマ字のいずれかが一致する艦
+    ///
+    /// 読みやローマ字で複数の艦が一致する場合は未改造の艦を返す
+    pub fn find_ship_by_name(&self, name: &str) -> Option<&MasterShip> {
+        let query = normalize(name);
+        let keys = self.ship_keys();
+
+        if let Some(index) = keys.iter().position(|keys| keys.name == query) {
+            return self.ships.get(index);
+        }
+
+        let candidates = keys
+            .iter()
+            .zip(&self.ships)
+            .filter(|(keys, _)| keys.keys().any(|key| key == query))
+            .map(|(_, ship)| ship)
+            .collect::<Vec<_>>();
+
+        candidates
+            .iter()
+            .filter(|ship| !candidates.iter().any(|other| other.next_id == ship.ship_id))
+            .min_by_key(|ship| ship.ship_id)
+            .or_else(|| candidates.iter().min_by_key(|ship| ship.ship_id))
+            .copied()
+    }
+
+    /// 装備名、読み、ローマ字、別名のいずれかが一致する装備
+    ///
+    /// 装備名が一致しない場合は ID の小さい装備を優先する
+    pub fn find_gear_by_name(&self, name: &str) -> Option<&MasterGear> {
+        let query = normalize(name);
+        let keys = self.gear_keys();
+
+        let index = keys
+            .iter()
+            .position(|keys| keys.name == query)
+            .or_else(|| {
+                keys.iter()
+                    .enumerate()
+                    .filter(|(_, keys)| keys.keys().any(|key| key == query))
+                    .min_by_key(|&(index, _)| self.gears[index].gear_id)
+                    .map(|(index, _)| index)
+            })?;
+
+        self.gears.get(index)
+    }
+
+    /// 艦名、読み、ローマ字のあいまい検索
+    pub fn search_ships(&self, query: &str, limit: usize) -> Vec<MasterSearchHit> {
+        let query = normalize(query);
+        let keys = self.ship_keys();
+
+        let hits = keys
+            .iter()
+            .zip(&self.ships)
+            .map(|(keys, ship)| MasterSearchHit {
+                id: ship.ship_id,
+                name: ship.name.clone(),
+                score: keys.score(&query),
+            });
+
+        collect_hits(hits, limit)
+    }
+
+    /// 装備名、読み、ローマ字、別名のあいまい検索
+    pub fn search_gears(&self, query: &str, limit: usize) -> Vec<MasterSearchHit> {
+        let query = normalize(query);
+        let keys = self.gear_keys();
+
+        let hits = keys
+            .iter()
+            .zip(&self.gears)
+            .map(|(keys, gear)| MasterSearchHit {
+                id: gear.gear_id,
+                name: gear.name.clone(),
+                score: keys.score(&query),
+            });
+
+        collect_hits(hits, limit)
+    }
+
+    /// 条件式に一致する艦
+    ///
+    /// `MasterShip::ns` の変数に加えて、`stype` `ctype` と各ステータスの上限値を参照できる
+    /// ステータスの初期値は `firepower_min` のように `_min` を付ける
+    pub fn filter_ships(&self, expr: &CompiledEvaler) -> Vec<&MasterShip> {
+        self.ships
+            .iter()
+            .filter(|ship| expr.matches(&mut ship_filter_ns(ship)))
+            .collect()
+    }
+
+    /// 条件式に一致する装備
+    pub fn filter_gears(&self, expr: &CompiledEvaler) -> Vec<&MasterGear> {
+        self.gears
+            .iter()
+            .filter(|gear| expr.matches(&mut gear.ns()))
+            .collect()
+    }
+}
+
+fn collect_hits(hits: impl Iterator<Item = MasterSearchHit>, limit: usize) -> Vec<MasterSearchHit> {
+    let mut hits = hits.filter(|hit| hit.score > 0.0).collect::<Vec<_>>();
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    hits.truncate(limit);
+    hits
+}
+
+fn ship_filter_ns(ship: &MasterShip) -> impl EvalNamespace + '_ {
+    let mut ns = ship.ns();
+
+    move |name: &str, args: Vec<f64>| -> Option<f64> {
+        let (stat_name, is_min) = match name.strip_suffix("_min") {
+            Some(stat_name) => (stat_name, true),
+            None => (name, false),
+        };
+
+        let stat = match stat_name {
+            "max_hp" => Some(ship.max_hp),
+            "firepower" => Some(ship.firepower),
+            "armor" => Some(ship.armor),
+            "torpedo" => Some(ship.torpedo),
+            "evasion" => Some(ship.evasion),
+            "anti_air" => Some(ship.anti_air),
+            "asw" => Some(ship.asw),
+            "los" => Some(ship.los),
+            "luck" => Some(ship.luck),
+            _ => None,
+        };
+
+        if let Some(StatInterval(min, max)) = stat {
+            let value = if is_min { min } else { max.or(min) };
+            return value.map(f64::from);
+        }
+
+        let result = match name {
+            "stype" => ship.stype.into(),
+            "ctype" => ship.ctype.into(),
+            "range" => ship.range?.into(),
+            "slotnum" => ship.slotnum as f64,
+            "fuel" => ship.fuel.into(),
+            "ammo" => ship.ammo.into(),
+            _ => return ns.lookup(name, args, &mut String::new()),
+        };
+
+        Some(result)
+    }
+}
+
+/// 全角英数字を半角に、カタカナをひらがなに、英字を小文字に揃え、空白と区切り記号を除く
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter_map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0),
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60),
+            _ => Some(c),
+        })
+        .filter(|c| !c.is_whitespace() && !matches!(c, '・' | '-' | '_' | '.' | '\''))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[rustfmt::skip]
+fn kana_to_romaji(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'あ' => "a", 'い' => "i", 'う' => "u", 'え' => "e", 'お' => "o",
+        'か' => "ka", 'き' => "ki", 'く' => "ku", 'け' => "ke", 'こ' => "ko",
+        'さ' => "sa", 'し' => "shi", 'す' => "su", 'せ' => "se", 'そ' => "so",
+        'た' => "ta", 'ち' => "chi", 'つ' => "tsu", 'て' => "te", 'と' => "to",
+        'な' => "na", 'に' => "ni", 'ぬ' => "nu", 'ね' => "ne", 'の' => "no",
+        'は' => "ha", 'ひ' => "hi", 'ふ' => "fu", 'へ' => "he", 'ほ' => "ho",
+        'ま' => "ma", 'み' => "mi", 'む' => "mu", 'め' => "me", 'も' => "mo",
+        'や' => "ya", 'ゆ' => "yu", 'よ' => "yo",
+        'ら' => "ra", 'り' => "ri", 'る' => "ru", 'れ' => "re", 'ろ' => "ro",
+        'わ' => "wa", 'ゐ' => "i", 'ゑ' => "e", 'を' => "o", 'ん' => "n",
+        'が' => "ga", 'ぎ' => "gi", 'ぐ' => "gu", 'げ' => "ge", 'ご' => "go",
+        'ざ' => "za", 'じ' => "ji", 'ず' => "zu", 'ぜ' => "ze", 'ぞ' => "zo",
+        'だ' => "da", 'ぢ' => "ji", 'づ' => "zu", 'で' => "de", 'ど' => "do",
+        'ば' => "ba", 'び' => "bi", 'ぶ' => "bu", 'べ' => "be", 'ぼ' => "bo",
+        'ぱ' => "pa", 'ぴ' => "pi", 'ぷ' => "pu", 'ぺ' => "pe", 'ぽ' => "po",
+        'ゔ' => "vu",
+        'ぁ' => "a", 'ぃ' => "i", 'ぅ' => "u", 'ぇ' => "e", 'ぉ' => "o",
+        'ゃ' => "ya", 'ゅ' => "yu", 'ょ' => "yo", 'ゎ' => "wa",
+        _ => return None,
+    };
+
+    Some(romaji)
+}
+
+/// ひらがなをヘボン式ローマ字に変換する
+///
+/// かな以外の文字はそのまま残す
+fn to_romaji(kana: &str) -> String {
+    let mut result = String::new();
+    let mut chars = kana.chars().peekable();
+    let mut sokuon = false;
+
+    while let Some(c) = chars.next() {
+        if c == 'っ' {
+            sokuon = true;
+            continue;
+        }
+
+        if c == 'ー' {
+            continue;
+        }
+
+        let Some(base) = kana_to_romaji(c) else {
+            result.push(c);
+            sokuon = false;
+            continue;
+        };
+
+        let mut syllable = base.to_string();
+
+        match chars.peek() {
+            Some(&small @ ('ゃ' | 'ゅ' | 'ょ')) if base.len() > 1 && base.ends_with('i') => {
+                chars.next();
+                let vowel = &kana_to_romaji(small).unwrap_or_default()[1..];
+                syllable.pop();
+
+                if !(syllable.ends_with("sh") || syllable.ends_with("ch") || syllable == "j") {
+                    syllable.push('y');
+                }
+
+                syllable.push_str(vowel);
+            }
+            Some(&small @ ('ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ')) if base.len() > 1 => {
+                chars.next();
+                syllable.pop();
+                syllable.push_str(kana_to_romaji(small).unwrap_or_default());
+            }
+            _ => (),
+        }
+
+        if std::mem::take(&mut sokuon) {
+            let consonant = if syllable.starts_with("ch") {
+                't'
+            } else {
+                syllable.chars().next().unwrap_or_default()
+            };
+
+            if !"aiueon".contains(consonant) {
+                result.push(consonant);
+            }
+        }
+
+        result.push_str(&syllable);
+    }
+
+    result
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            let cost = usize::from(ca != *cb);
+            row[j + 1] = (prev + cost).min(row[j] + 1).min(current + 1);
+            prev = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// 完全一致、前方一致、部分一致、編集距離の順に高い一致度とする
+fn similarity(query: &str, key: &str) -> f64 {
+    if query.is_empty() || key.is_empty() {
+        return 0.0;
+    }
+
+    if query == key {
+        return 1.0;
+    }
+
+    let query_len = query.chars().count() as f64;
+    let key_len = key.chars().count() as f64;
+    let ratio = query_len / key_len;
+
+    if key.starts_with(query) {
+        return 0.8 + 0.1 * ratio;
+    }
+
+    if key.contains(query) {
+        return 0.6 + 0.1 * ratio;
+    }
+
+    let distance = levenshtein(query, key) as f64;
+    let sim = 1.0 - distance / query_len.max(key_len);
+
+    if sim >= 0.5 {
+        0.5 * sim
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ship(ship_id: u16, name: &str, yomi: &str, stype: u8, asw: u16, next_id: u16) -> MasterShip {
+        MasterShip {
+            ship_id,
+            name: name.into(),
+            yomi: yomi.into(),
+            stype,
+            asw: StatInterval(Some(0), Some(asw)),
+            next_id,
+            ..Default::default()
+        }
+    }
+
+    fn gear(gear_id: u16, name: &str, yomi: &str, aliases: &[&str]) -> MasterGear {
+        MasterGear {
+            gear_id,
+            name: name.into(),
+            yomi: yomi.into(),
+            aliases: aliases.iter().map(|&alias| alias.into()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn master_data() -> MasterData {
+        let mut master_data = MasterData {
+            ships: vec![
+                ship(1, "睦月", "むつき", 2, 39, 254),
+                ship(254, "睦月改", "むつき", 2, 59, 434),
+                ship(434, "睦月改二", "むつき", 2, 69, 0),
+                ship(411, "扶桑改二", "ふそう", 10, 40, 0),
+                ship(171, "Bismarck", "ビスマルク", 8, 0, 0),
+                ship(184, "大鯨", "たいげい", 20, 0, 0),
+                ship(145, "時雨改二", "しぐれ", 2, 69, 0),
+                ship(911, "大和改二", "やまと", 9, 0, 0),
+                ship(1000, "大和改", "やまと", 9, 0, 911),
+            ],
+            gears: vec![
+                gear(
+                    9,
+                    "46cm三連装砲",
+                    "よんじゅうろくせんちさんれんそうほう",
+                    &["46砲"],
+                ),
+                gear(276, "46cm三連装砲改", "", &["46砲改"]),
+                gear(
+                    21,
+                    "零式艦戦52型",
+                    "れいしきかんせんごじゅうにがた",
+                    &["零戦52型"],
+                ),
+            ],
+            ..Default::default()
+        };
+
+        master_data.rebuild_index();
+        master_data
+    }
+
+    #[test]
+    fn test_to_romaji() {
+        assert_eq!(to_romaji("むつき"), "mutsuki");
+        assert_eq!(to_romaji("しょうかく"), "shoukaku");
+        assert_eq!(to_romaji("じゅんよう"), "junyou");
+        assert_eq!(to_romaji("はっちゅう"), "hatchuu");
+        assert_eq!(to_romaji("びすまるく"), "bisumaruku");
+        assert_eq!(to_romaji("きゃっと"), "kyatto");
+    }
+
+    #[test]
+    fn test_find_ship_by_name() {
+        let master_data = master_data();
+        let find = |name| master_data.find_ship_by_name(name).map(|ship| ship.ship_id);
+
+        assert_eq!(
+            master_data.get_ship(434).map(|ship| ship.ship_id),
+            Some(434)
+        );
+        assert_eq!(find("睦月改二"), Some(434));
+        assert_eq!(find("ムツキ"), Some(1));
+        assert_eq!(find("Mutsuki"), Some(1));
+        assert_eq!(find("bismarck"), Some(171));
+        assert_eq!(find("ｂｉｓｍａｒｃｋ"), Some(171));
+        assert_eq!(find("unknown"), None);
+
+        // 未改造の艦が最小の ID を持たない場合
+        assert_eq!(find("やまと"), Some(1000));
+    }
+
+    #[test]
+    fn test_find_gear_by_name() {
+        let master_data = master_data();
+        let find = |name| master_data.find_gear_by_name(name).map(|gear| gear.gear_id);
+
+        assert_eq!(find("46cm三連装砲改"), Some(276));
+        assert_eq!(find("46砲"), Some(9));
+        assert_eq!(find("46砲改"), Some(276));
+        assert_eq!(find("レイシキカンセンゴジュウニガタ"), Some(21));
+        assert_eq!(find("reishikikansengojuunigata"), Some(21));
+        assert_eq!(find("零戦52型"), Some(21));
+        assert_eq!(find("unknown"), None);
+    }
+
+    #[test]
+    fn test_search_ships() {
+        let master_data = master_data();
+        let ids = |query| {
+            master_data
+                .search_ships(query, 3)
+                .into_iter()
+                .map(|hit| hit.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("睦月"), vec![1, 254, 434]);
+        assert_eq!(ids("fusou"), vec![411]);
+        assert_eq!(ids("taigei"), vec![184]);
+        assert_eq!(ids("taigee"), vec![184]);
+        assert_eq!(ids("shigure"), vec![145]);
+    }
+
+    #[test]
+    fn test_filter_ships() {
+        let master_data = master_data();
+        let expr = CompiledEvaler::new("stype == 2 && asw >= 60".into()).unwrap();
+        let ids = master_data
+            .filter_ships(&expr)
+            .into_iter()
+            .map(|ship| ship.ship_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![434, 145]);
+    }
+}
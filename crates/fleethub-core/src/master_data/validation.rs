@@ -0,0 +1,838 @@
+use std::{collections::BTreeSet, fmt::Debug};
+
+use fasteval::EvalNamespace;
+use serde::{de::DeserializeOwned, Serialize};
+use tsify::Tsify;
+
+use crate::{
+    attack::{fleet_cutin_ns, lookup_fleet_cutin_ship},
+    comp::Comp,
+    fleet::{Fleet, ShipArray},
+    member::is_fleet_function,
+    ship::Ship,
+    types::{
+        AswAttackType, AttackType, CompiledEvaler, Engagement, GearAttr, NightAttackType,
+        ShellingType, Time,
+    },
+};
+
+use super::{
+    master_special_enemy_modifier, CountTableValue, MasterAttrRule, MasterData, MasterGear,
+    MasterShip,
+};
+
+/// 特効補正の評価に用いる攻撃種別
+const SPECIAL_ENEMY_ATTACK_TYPES: [AttackType; 6] = [
+    AttackType::Shelling(ShellingType::Normal),
+    AttackType::Shelling(ShellingType::Aerial),
+    AttackType::Night(NightAttackType::Normal),
+    AttackType::Night(NightAttackType::Aerial),
+    AttackType::Torpedo,
+    AttackType::Asw(AswAttackType::DepthCharge),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub enum MasterDataDiagnosticKind {
+    /// 解析できない式
+    InvalidExpression,
+    /// 名前空間に存在しない変数や関数の参照
+    UndefinedVariable,
+    UnknownShipId,
+    UnknownGearId,
+    /// 同じタグを持つ属性ルールの重複
+    DuplicateTag,
+    /// 存在しないグループの参照
+    UnknownGroup,
+    /// 読み込めずに除外された艦
+    InvalidShip,
+    /// 史実補正以外での艦隊を参照する関数の使用
+    FleetFunctionNotAllowed,
+    /// 読み込めないデータ
+    InvalidData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
+pub struct MasterDataDiagnostic {
+    pub kind: MasterDataDiagnosticKind,
+    /// 問題のある箇所 (例: `ebonuses.rules[3].gear`)
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct MasterDataValidation {
+    pub diagnostics: Vec<MasterDataDiagnostic>,
+}
+
+impl MasterDataValidation {
+    pub fn is_valid(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn count(&self, kind: MasterDataDiagnosticKind) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.kind == kind)
+            .count()
+    }
+}
+
+/// 式の評価中に参照された ID
+#[derive(Default)]
+struct ReferencedIds {
+    ship_ids: BTreeSet<u16>,
+    gear_ids: BTreeSet<u16>,
+}
+
+impl ReferencedIds {
+    fn record(&mut self, name: &str, args: &[f64]) {
+        let ids = args.iter().map(|&v| v as u16);
+
+        match name {
+            "ship_id_in" => self.ship_ids.extend(ids),
+            "gear_id_in" | "count_gear_id" => self.gear_ids.extend(ids),
+            _ => (),
+        }
+    }
+
+    /// `ship_id == 1` や `gear_id != 2` のような比較に現れる ID を記録する
+    fn record_comparisons(&mut self, expr: &str) {
+        let targets = [
+            ("ship_id", &mut self.ship_ids),
+            ("gear_id", &mut self.gear_ids),
+        ];
+
+        for (key, ids) in targets {
+            for (start, _) in expr.match_indices(key) {
+                let is_suffix = expr[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+                if is_suffix {
+                    continue;
+                }
+
+                let rest = expr[start + key.len()..].trim_start();
+                let Some(rest) = rest.strip_prefix("==").or_else(|| rest.strip_prefix("!=")) else {
+                    continue;
+                };
+
+                let digits = rest
+                    .trim_start()
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect::<String>();
+
+                if let Ok(id) = digits.parse() {
+                    ids.insert(id);
+                }
+            }
+        }
+    }
+}
+
+fn ship_lookup(ship: &MasterShip, name: &str, args: Vec<f64>) -> Option<f64> {
+    ship.ns().lookup(name, args, &mut String::new())
+}
+
+fn gear_lookup(gear: &MasterGear, name: &str, args: Vec<f64>) -> Option<f64> {
+    gear.ns().lookup(name, args, &mut String::new())
+}
+
+/// 名前空間の評価に用いる装備なしの艦
+fn sample_ship(master: &MasterShip) -> Ship {
+    let mut ship = Ship::default();
+    ship.ship_id = master.ship_id;
+    ship.ship_type = master.ship_type();
+    ship.ctype = master.ctype;
+    ship.master = master.clone();
+    ship
+}
+
+/// 艦隊の名前空間の評価に用いる6隻の艦隊
+///
+/// 運と索敵が判明している味方艦のみを用いる
+fn sample_fleets(ships: &[MasterShip]) -> Vec<Fleet> {
+    let ships = ships
+        .iter()
+        .filter(|ship| !ship.is_abyssal() && ship.luck.0.is_some() && ship.los.zipped().is_some())
+        .map(sample_ship)
+        .collect::<Vec<_>>();
+
+    ships
+        .chunks_exact(6)
+        .map(|chunk| Fleet {
+            len: chunk.len(),
+            ships: chunk.iter().cloned().map(Some).collect::<ShipArray>(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+struct Validator<'a> {
+    master_data: &'a MasterData,
+    diagnostics: Vec<MasterDataDiagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, kind: MasterDataDiagnosticKind, location: &str, message: String) {
+        self.diagnostics.push(MasterDataDiagnostic {
+            kind,
+            location: location.to_string(),
+            message,
+        })
+    }
+
+    /// 全ての評価対象で式を評価し、未定義の参照と存在しない ID を報告する
+    fn check_evaler<T, F>(
+        &mut self,
+        location: &str,
+        evaler: &CompiledEvaler,
+        items: &[T],
+        lookup: F,
+    ) where
+        F: Fn(&T, &str, Vec<f64>) -> Option<f64>,
+    {
+        if evaler.is_empty() {
+            return;
+        }
+
+        if let Some(err) = evaler.error() {
+            self.push(
+                MasterDataDiagnosticKind::InvalidExpression,
+                location,
+                format!("\"{}\" を解析できません: {err:?}", evaler.expr()),
+            );
+            return;
+        }
+
+        let mut undefined = BTreeSet::new();
+        let mut ids = ReferencedIds::default();
+        ids.record_comparisons(evaler.expr());
+
+        for item in items {
+            let mut ns = |name: &str, args: Vec<f64>| -> Option<f64> {
+                ids.record(name, &args);
+                lookup(item, name, args)
+            };
+
+            if let Err(fasteval::Error::Undefined(name)) = evaler.eval(&mut ns) {
+                undefined.insert(name);
+            }
+        }
+
+        for name in undefined {
+            if is_fleet_function(&name) {
+                self.push(
+                    MasterDataDiagnosticKind::FleetFunctionNotAllowed,
+                    location,
+                    format!("艦隊を参照する \"{name}\" は史実補正でのみ使用できます"),
+                );
+                continue;
+            }
+
+            self.push(
+                MasterDataDiagnosticKind::UndefinedVariable,
+                location,
+                format!(
+                    "\"{}\" に未定義の \"{name}\" が含まれています",
+                    evaler.expr()
+                ),
+            );
+        }
+
+        self.check_ids(location, ids.ship_ids, ids.gear_ids);
+    }
+
+    fn check_ids(
+        &mut self,
+        location: &str,
+        ship_ids: impl IntoIterator<Item = u16>,
+        gear_ids: impl IntoIterator<Item = u16>,
+    ) {
+        let master_data = self.master_data;
+
+        for ship_id in ship_ids {
+            if !master_data.ships.iter().any(|ship| ship.ship_id == ship_id) {
+                self.push(
+                    MasterDataDiagnosticKind::UnknownShipId,
+                    location,
+                    format!("艦 ID {ship_id} は存在しません"),
+                );
+            }
+        }
+
+        for gear_id in gear_ids {
+            if !master_data.gears.iter().any(|gear| gear.gear_id == gear_id) {
+                self.push(
+                    MasterDataDiagnosticKind::UnknownGearId,
+                    location,
+                    format!("装備 ID {gear_id} は存在しません"),
+                );
+            }
+        }
+    }
+
+    fn check_expr_str<T, F>(&mut self, location: &str, expr: &str, items: &[T], lookup: F)
+    where
+        F: Fn(&T, &str, Vec<f64>) -> Option<f64>,
+    {
+        let evaler = CompiledEvaler::new_lenient(expr.to_string());
+        self.check_evaler(location, &evaler, items, lookup);
+    }
+
+    fn check_attr_rules<Tag, T, F>(
+        &mut self,
+        key: &str,
+        rules: &[MasterAttrRule<Tag>],
+        items: &[T],
+        lookup: F,
+    ) where
+        Tag: Default + DeserializeOwned + Copy + PartialEq + Debug,
+        F: Fn(&T, &str, Vec<f64>) -> Option<f64>,
+    {
+        let mut seen = vec![];
+
+        for (index, rule) in rules.iter().enumerate() {
+            let location = format!("{key}[{index}]");
+
+            if seen.contains(&rule.tag) {
+                self.push(
+                    MasterDataDiagnosticKind::DuplicateTag,
+                    &location,
+                    format!("タグ {:?} ({}) が重複しています", rule.tag, rule.name),
+                );
+            } else {
+                seen.push(rule.tag);
+            }
+
+            self.check_evaler(&format!("{location}.expr"), &rule.expr, items, &lookup);
+        }
+    }
+
+    fn check_equippability(&mut self) {
+        let master_data = self.master_data;
+        let equippability = &master_data.equippability;
+        let ships = &master_data.ships;
+        let gears = &master_data.gears;
+
+        for (index, es) in equippability.equip_ship.iter().enumerate() {
+            let location = format!("equippability.equip_ship[{index}]");
+            self.check_ids(&location, [es.api_ship_id], []);
+        }
+
+        for (index, ees) in equippability.equip_exslot_ship.iter().enumerate() {
+            let location = format!("equippability.equip_exslot_ship[{index}]");
+            self.check_ids(
+                &location,
+                ees.api_ship_ids.iter().copied(),
+                [ees.api_slotitem_id],
+            );
+        }
+
+        for (index, rule) in equippability.rules.iter().enumerate() {
+            let location = format!("equippability.rules[{index}]");
+
+            self.check_evaler(&format!("{location}.ship"), &rule.ship, ships, ship_lookup);
+
+            if !rule.include.is_empty() {
+                let location = format!("{location}.include");
+                self.check_expr_str(&location, &rule.include, gears, gear_lookup);
+            }
+
+            if !rule.exclude.is_empty() {
+                let location = format!("{location}.exclude");
+                self.check_expr_str(&location, &rule.exclude, gears, gear_lookup);
+            }
+        }
+    }
+
+    fn check_ebonuses(&mut self) {
+        let master_data = self.master_data;
+        let ships = &master_data.ships;
+        let gears = &master_data.gears;
+
+        for (index, rule) in master_data.ebonuses.rules.iter().enumerate() {
+            let location = format!("ebonuses.rules[{index}]");
+
+            self.check_evaler(&format!("{location}.ship"), &rule.ship, ships, ship_lookup);
+
+            self.check_evaler(
+                &format!("{location}.gear"),
+                &rule.gear,
+                gears,
+                |gear, name, args| match name {
+                    "stars" | "ace" => Some(0.0),
+                    _ => gear_lookup(gear, name, args),
+                },
+            );
+
+            self.check_evaler(
+                &format!("{location}.requires"),
+                &rule.requires,
+                &[()],
+                |_, name, _| match name {
+                    "count_gear_id" | "count_gear_type" => Some(0.0),
+                    _ => name.parse::<GearAttr>().ok().map(|_| 0.0),
+                },
+            );
+        }
+    }
+
+    fn check_ibonuses(&mut self) {
+        let master_data = self.master_data;
+        let gears = &master_data.gears;
+
+        for (key, rules) in master_data.ibonuses.groups() {
+            for (index, rule) in rules.iter().enumerate() {
+                let location = format!("ibonuses.{key}[{index}]");
+
+                self.check_evaler(&format!("{location}.expr"), &rule.expr, gears, gear_lookup);
+
+                self.check_evaler(
+                    &format!("{location}.formula"),
+                    &rule.formula,
+                    &[()],
+                    |_, name, args| match name {
+                        "x" => Some(0.0),
+                        "sqrt" => args.first().map(|v| v.sqrt()),
+                        _ => None,
+                    },
+                );
+            }
+        }
+    }
+
+    fn check_anti_air_cutin(&mut self) {
+        let master_data = self.master_data;
+        let ships = &master_data.ships;
+        let gears = &master_data.gears;
+
+        for (index, def) in master_data
+            .battle_definitions
+            .anti_air_cutin
+            .iter()
+            .enumerate()
+        {
+            let location = format!("battle_definitions.anti_air_cutin[{index}]");
+
+            let requirements = def
+                .requirements
+                .iter()
+                .enumerate()
+                .map(|(i, requirement)| (format!("{location}.requirements[{i}]"), requirement))
+                .chain(
+                    def.exclusive
+                        .iter()
+                        .map(|requirement| (format!("{location}.exclusive"), requirement)),
+                );
+
+            for (location, requirement) in requirements {
+                self.check_evaler(
+                    &format!("{location}.ship"),
+                    &requirement.ship,
+                    ships,
+                    ship_lookup,
+                );
+
+                for (i, gear_requirement) in requirement.gears.iter().enumerate() {
+                    let location = format!("{location}.gears[{i}]");
+                    self.check_evaler(&location, &gear_requirement.gear, gears, gear_lookup);
+                }
+            }
+        }
+    }
+
+    fn check_gunfit_accuracy(&mut self) {
+        let master_data = self.master_data;
+        let table = master_data.gunfit_accuracy.table();
+
+        for (index, group) in table.ship_groups.iter().enumerate() {
+            let location = format!("gunfit_accuracy.ship_groups[{index}].ship");
+
+            self.check_evaler(
+                &location,
+                &group.ship,
+                &master_data.ships,
+                |ship, name, args| match name {
+                    "level" => Some(99.0),
+                    _ => ship_lookup(ship, name, args),
+                },
+            );
+        }
+
+        for (index, group) in table.gun_groups.iter().enumerate() {
+            let location = format!("gunfit_accuracy.gun_groups[{index}]");
+            self.check_ids(&location, [], group.gears.iter().copied());
+        }
+
+        for (index, entry) in table.entries.iter().enumerate() {
+            let location = format!("gunfit_accuracy.entries[{index}]");
+
+            if !table.ship_groups.iter().any(|g| g.name == entry.ship_group) {
+                self.push(
+                    MasterDataDiagnosticKind::UnknownGroup,
+                    &location,
+                    format!("艦グループ \"{}\" は存在しません", entry.ship_group),
+                );
+            }
+
+            if !table.gun_groups.iter().any(|g| g.name == entry.gun_group) {
+                self.push(
+                    MasterDataDiagnosticKind::UnknownGroup,
+                    &location,
+                    format!("砲グループ \"{}\" は存在しません", entry.gun_group),
+                );
+            }
+        }
+    }
+
+    fn check_count_table_value<T, F>(
+        &mut self,
+        location: &str,
+        value: &Option<CountTableValue>,
+        items: &[T],
+        lookup: F,
+    ) where
+        F: Fn(&T, &str, Vec<f64>) -> Option<f64>,
+    {
+        if let Some(CountTableValue::Expr(evaler)) = value {
+            self.check_evaler(location, evaler, items, lookup);
+        }
+    }
+
+    fn check_special_enemy_modifiers(&mut self) {
+        let ships = self
+            .master_data
+            .ships
+            .iter()
+            .map(sample_ship)
+            .collect::<Vec<_>>();
+
+        let items = (0..ships.len())
+            .flat_map(|index| SPECIAL_ENEMY_ATTACK_TYPES.map(|attack_type| (index, attack_type)))
+            .collect::<Vec<_>>();
+
+        let lookup = |&(index, attack_type): &(usize, AttackType), name: &str, args| {
+            master_special_enemy_modifier::ns(&ships[index], attack_type).lookup(
+                name,
+                args,
+                &mut String::new(),
+            )
+        };
+
+        let groups = self.master_data.special_enemy_modifiers.groups();
+
+        for (index, group) in groups.iter().enumerate() {
+            let location = format!("special_enemy_modifiers[{index}]");
+
+            self.check_evaler(&format!("{location}.expr"), &group.expr, &items, lookup);
+
+            for (i, rule) in group.rules.iter().enumerate() {
+                let location = format!("{location}.rules[{i}]");
+
+                self.check_evaler(&format!("{location}.expr"), &rule.expr, &items, lookup);
+                self.check_evaler(&format!("{location}.count"), &rule.count, &items, lookup);
+                self.check_count_table_value(&format!("{location}.a"), &rule.a, &items, lookup);
+                self.check_count_table_value(&format!("{location}.b"), &rule.b, &items, lookup);
+            }
+        }
+    }
+
+    fn check_fleet_cutins(&mut self) {
+        let master_data = self.master_data;
+        let fleets = sample_fleets(&master_data.ships);
+        let ships = fleets
+            .iter()
+            .flat_map(|fleet| fleet.ships.values().cloned())
+            .collect::<Vec<_>>();
+
+        let ship_lookup = |ship: &Ship, name: &str, args| lookup_fleet_cutin_ship(ship, name, args);
+        let fleet_lookup = |fleet: &Fleet, name: &str, args| {
+            fleet_cutin_ns(fleet, Engagement::default(), Time::Day).lookup(
+                name,
+                args,
+                &mut String::new(),
+            )
+        };
+
+        let mut seen = vec![];
+
+        for (index, def) in master_data
+            .battle_definitions
+            .fleet_cutin
+            .iter()
+            .enumerate()
+        {
+            let location = format!("battle_definitions.fleet_cutin[{index}]");
+
+            if seen.contains(&&def.tag) {
+                self.push(
+                    MasterDataDiagnosticKind::DuplicateTag,
+                    &location,
+                    format!("タグ {} が重複しています", def.tag.as_str()),
+                );
+            } else {
+                seen.push(&def.tag);
+            }
+
+            for (i, participant) in def.participants.iter().enumerate() {
+                let location = format!("{location}.participants[{i}].ship");
+                self.check_evaler(&location, &participant.ship, &ships, ship_lookup);
+            }
+
+            self.check_evaler(
+                &format!("{location}.expr"),
+                &def.expr,
+                &fleets,
+                fleet_lookup,
+            );
+
+            for (i, attack) in def.attacks.iter().enumerate() {
+                for (j, rule) in attack.power_mods.iter().enumerate() {
+                    let location = format!("{location}.attacks[{i}].power_mods[{j}].expr");
+                    self.check_evaler(&location, &rule.expr, &fleets, fleet_lookup);
+                }
+            }
+
+            for (i, rule) in def.equipment_mods.iter().enumerate() {
+                let location = format!("{location}.equipment_mods[{i}].expr");
+                self.check_evaler(&location, &rule.expr, &ships, ship_lookup);
+            }
+
+            self.check_evaler(
+                &format!("{location}.rate"),
+                &def.rate,
+                &fleets,
+                fleet_lookup,
+            );
+        }
+    }
+
+    fn check_routes(&mut self) {
+        let master_data = self.master_data;
+        let comps = sample_fleets(&master_data.ships)
+            .into_iter()
+            .map(|main| Comp {
+                org_type: Default::default(),
+                hq_level: 120,
+                main,
+                escort: None,
+                route_sup: None,
+                boss_sup: None,
+            })
+            .collect::<Vec<_>>();
+
+        for (index, route) in master_data.routes.iter().enumerate() {
+            for (i, rule) in route.rules.iter().enumerate() {
+                let location = format!("routes[{index}].rules[{i}].expr");
+
+                self.check_evaler(&location, &rule.expr, &comps, |comp, name, args| {
+                    comp.ns().lookup(name, args, &mut String::new())
+                });
+            }
+        }
+    }
+
+    fn check_ships(&mut self) {
+        let master_data = self.master_data;
+
+        for (index, message) in &master_data.dropped_ships {
+            self.push(
+                MasterDataDiagnosticKind::InvalidShip,
+                &format!("ships[{index}]"),
+                format!("艦を読み込めません: {message}"),
+            );
+        }
+
+        for (index, ship) in master_data.ships.iter().enumerate() {
+            let location = format!("ships[{index}]");
+            let next_id = (ship.next_id != 0).then_some(ship.next_id);
+            let stock = ship.stock.iter().map(|gear| gear.gear_id);
+
+            self.check_ids(&location, next_id, stock);
+        }
+    }
+}
+
+impl MasterData {
+    /// マスタデータの整合性を検査する
+    ///
+    /// 読み込み時に無視される式の誤りや存在しない ID の参照を報告する
+    pub fn validate(&self) -> MasterDataValidation {
+        let mut validator = Validator {
+            master_data: self,
+            diagnostics: vec![],
+        };
+
+        validator.check_attr_rules("ship_attrs", &self.ship_attrs, &self.ships, ship_lookup);
+        validator.check_attr_rules("gear_attrs", &self.gear_attrs, &self.gears, gear_lookup);
+        validator.check_ships();
+        validator.check_equippability();
+        validator.check_ebonuses();
+        validator.check_ibonuses();
+        validator.check_anti_air_cutin();
+        validator.check_gunfit_accuracy();
+        validator.check_special_enemy_modifiers();
+        validator.check_fleet_cutins();
+        validator.check_routes();
+
+        MasterDataValidation {
+            diagnostics: validator.diagnostics,
+        }
+    }
+
+    /// JSON のマスタデータを読み込んで検査する
+    ///
+    /// 読み込めない場合もエラーにせず診断として返す
+    pub fn validate_json(json: &str) -> MasterDataValidation {
+        match serde_json::from_str::<MasterData>(json) {
+            Ok(master_data) => master_data.validate(),
+            Err(err) => MasterDataValidation {
+                diagnostics: vec![MasterDataDiagnostic {
+                    kind: MasterDataDiagnosticKind::InvalidData,
+                    location: String::new(),
+                    message: format!("マスタデータを読み込めません: {err}"),
+                }],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::master_data::parse_ships;
+
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let master_data = MasterData {
+            ship_attrs: serde_json::from_value(serde_json::json!([
+                { "tag": "Abyssal", "name": "深海", "expr": "ship_id > 1500" },
+                { "tag": "Abyssal", "name": "深海", "expr": "ship_id > 1500" },
+                { "tag": "Kai2", "name": "改二", "expr": "fleet_count_stype(2) >= 1" },
+            ]))
+            .unwrap(),
+            ebonuses: serde_json::from_value(serde_json::json!({
+                "rules": [{
+                    "ship": "ship_id == 2",
+                    "gear": "gear_id != 10",
+                    "requires": "count_gear_id(10) + unknown_var >= 1",
+                }],
+            }))
+            .unwrap(),
+            equippability: serde_json::from_value(serde_json::json!({
+                "equip_stype": [],
+                "equip_exslot": [],
+                "equip_ship": [{ "api_ship_id": 1, "api_equip_type": [] }],
+                "equip_exslot_ship": [],
+                "rules": [{ "ship": "", "include": "gear_id ==" }],
+            }))
+            .unwrap(),
+            gunfit_accuracy: serde_json::from_value(serde_json::json!({
+                "ship_groups": [],
+                "gun_groups": [],
+                "entries": [{ "ship_group": "戦艦", "gun_group": "41cm砲", "day": 1 }],
+            }))
+            .unwrap(),
+            special_enemy_modifiers: serde_json::from_value(serde_json::json!([])).unwrap(),
+            ships: vec![MasterShip::default()],
+            ..Default::default()
+        };
+
+        let (ships, dropped_ships) = parse_ships(vec![
+            serde_json::json!({ "ship_id": "1" }),
+            serde_json::json!(1),
+        ]);
+        assert!(ships.is_empty());
+
+        let master_data = MasterData {
+            dropped_ships,
+            ..master_data
+        };
+
+        let validation = master_data.validate();
+
+        use MasterDataDiagnosticKind::*;
+
+        assert!(!validation.is_valid());
+        assert_eq!(validation.count(DuplicateTag), 1);
+        assert_eq!(validation.count(InvalidExpression), 1);
+        assert_eq!(validation.count(UndefinedVariable), 1);
+        assert_eq!(validation.count(UnknownShipId), 2);
+        assert_eq!(validation.count(UnknownGearId), 2);
+        assert_eq!(validation.count(UnknownGroup), 2);
+        assert_eq!(validation.count(InvalidShip), 2);
+        assert_eq!(validation.count(FleetFunctionNotAllowed), 1);
+    }
+
+    #[test]
+    fn test_validate_battle_data() {
+        let master_data = MasterData {
+            ships: vec![MasterShip::default()],
+            ebonuses: serde_json::from_value(serde_json::json!({ "rules": [] })).unwrap(),
+            special_enemy_modifiers: serde_json::from_value(serde_json::json!([{
+                "targets": ["PtImp"],
+                "expr": "shelling && unknown_var",
+                "rules": [{ "key": "pt", "a": "count_gear_id(10)" }],
+            }]))
+            .unwrap(),
+            battle_definitions: serde_json::from_value(serde_json::json!({
+                "formation": [],
+                "anti_air_cutin": [],
+                "day_cutin": [],
+                "night_cutin": [],
+                "fleet_cutin": [
+                    { "tag": "NelsonTouch", "formations": [], "attacks": [] },
+                    {
+                        "tag": "NelsonTouch",
+                        "formations": [],
+                        "expr": "surface_ship_count >=",
+                        "attacks": [],
+                    },
+                ],
+            }))
+            .unwrap(),
+            routes: serde_json::from_value(serde_json::json!([
+                { "rules": [{ "expr": "ship_count >" }] },
+            ]))
+            .unwrap(),
+            gunfit_accuracy: serde_json::from_value(serde_json::json!({
+                "ship_groups": [],
+                "gun_groups": [],
+                "entries": [],
+            }))
+            .unwrap(),
+            ..Default::default()
+        };
+
+        let validation = master_data.validate();
+
+        use MasterDataDiagnosticKind::*;
+
+        assert_eq!(validation.count(UndefinedVariable), 1);
+        assert_eq!(validation.count(UnknownGearId), 1);
+        assert_eq!(validation.count(DuplicateTag), 1);
+        assert_eq!(validation.count(InvalidExpression), 2);
+    }
+
+    #[test]
+    fn test_validate_json() {
+        let validation = MasterData::validate_json("{\"ships\": 1}");
+        assert_eq!(validation.count(MasterDataDiagnosticKind::InvalidData), 1);
+    }
+
+    #[test]
+    fn test_record_comparisons() {
+        let mut ids = ReferencedIds::default();
+        ids.record_comparisons(
+            "(ship_id == 1 || ship_id != 2) && ship_id_in(3) && s1_ship_id == 4 && gear_id==5 && count_gear_id(6)",
+        );
+
+        assert_eq!(ids.ship_ids, BTreeSet::from([1, 2]));
+        assert_eq!(ids.gear_ids, BTreeSet::from([5]));
+    }
+}
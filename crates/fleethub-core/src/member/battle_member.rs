@@ -3,9 +3,12 @@ use std::{
     str::FromStr,
 };
 
+use fasteval::EvalNamespace;
+
 use crate::{
+    comp::Comp,
     ship::Ship,
-    types::{Formation, ShipAttr, ShipConditions, ShipPosition},
+    types::{FleetType, Formation, GearAttr, ShipAttr, ShipConditions, ShipPosition},
 };
 
 use super::comp_member::CompMember;
@@ -86,7 +89,7 @@ where
         }
     }
 
-    pub fn ns(&self) -> impl fasteval::EvalNamespace + '_ {
+    pub fn ns(&self) -> impl EvalNamespace + '_ {
         use fasteval::bool_to_f64;
         |name: &str, args: Vec<f64>| -> Option<f64> {
             let result = match name {
@@ -103,15 +106,23 @@ where
                 "nationality_in" => bool_to_f64!(args.contains(&(self.master.nationality.into()))),
 
                 "is_flagship" => bool_to_f64!(self.position.is_main_flagship()),
+                "is_main_fleet" => bool_to_f64!(self.position.is_main()),
+                "position" => (self.position.index + 1) as f64,
 
                 "has" => bool_to_f64!(self.gears.has(*args.first()? as u16)),
-                "has_any" => bool_to_f64!(args.into_iter().any(|arg| self.gears.has(arg as u16))),
+                "has_any" | "has_gear" => {
+                    bool_to_f64!(args.into_iter().any(|arg| self.gears.has(arg as u16)))
+                }
                 "has_gear_type" => bool_to_f64!(self.gears.has_type((*args.first()?).into())),
                 "has_any_gear_type" => {
                     bool_to_f64!(args.into_iter().any(|arg| self.gears.has_type(arg.into())))
                 }
 
                 "count" => self.gears.count(*args.first()? as u16) as f64,
+                "count_gear" => args
+                    .into_iter()
+                    .map(|arg| self.gears.count(arg as u16))
+                    .sum::<usize>() as f64,
                 "count_gear_type" => self.gears.count_type((*args.first()?).into()) as f64,
 
                 "has_historical_aircraft" => {
@@ -131,9 +142,66 @@ where
                 }
 
                 _ => {
-                    let attr = ShipAttr::from_str(name).ok()?;
-                    bool_to_f64!(self.has_attr(attr))
+                    if let Some(attr) = name.strip_prefix("count_gear_attr_") {
+                        let attr = GearAttr::from_str(attr).ok()?;
+                        self.gears.count_attr(attr) as f64
+                    } else if let Some(attr) = name.strip_prefix("has_gear_attr_") {
+                        let attr = GearAttr::from_str(attr).ok()?;
+                        bool_to_f64!(self.gears.has_attr(attr))
+                    } else {
+                        let attr = ShipAttr::from_str(name).ok()?;
+                        bool_to_f64!(self.has_attr(attr))
+                    }
+                }
+            };
+
+            Some(result)
+        }
+    }
+
+    /// 所属する艦隊も参照できる名前空間
+    ///
+    /// 連合艦隊の場合は第一艦隊と第二艦隊の両方を対象とし、自身も数える。
+    /// `comp` が無い場合、艦隊の変数は未定義となる。
+    /// 艦隊を参照できるのは史実補正の条件のみで、属性や装備可否のルールでは使えない
+    pub fn ns_in<'a>(&'a self, comp: Option<&'a Comp>) -> impl EvalNamespace + 'a {
+        use fasteval::bool_to_f64;
+        let mut ns = self.ns();
+
+        move |name: &str, args: Vec<f64>| -> Option<f64> {
+            let count_by = |f: &dyn Fn(&Ship) -> bool| -> Option<f64> {
+                let count = comp?
+                    .members_by(FleetType::Main | FleetType::Escort)
+                    .filter(|ship| f(ship))
+                    .count();
+
+                Some(count as f64)
+            };
+
+            let result = match name {
+                "in_fleet" => {
+                    bool_to_f64!(count_by(&|ship| args.contains(&ship.ship_id.into()))? > 0.0)
+                }
+                "fleet_count_ship_id" => count_by(&|ship| args.contains(&ship.ship_id.into()))?,
+                "fleet_count_stype" => {
+                    count_by(&|ship| args.contains(&(ship.ship_type as u8).into()))?
                 }
+                "fleet_count_ship_class" => count_by(&|ship| args.contains(&ship.ctype.into()))?,
+                "fleet_count_gear" => comp?
+                    .members_by(FleetType::Main | FleetType::Escort)
+                    .map(|ship| {
+                        ship.gears
+                            .count_by(|gear| args.contains(&gear.gear_id.into()))
+                    })
+                    .sum::<usize>() as f64,
+
+                _ => match name.strip_prefix("fleet_count_ship_attr_") {
+                    Some(attr) => {
+                        let attr = ShipAttr::from_str(attr).ok()?;
+                        count_by(&|ship| ship.has_attr(attr))?
+                    }
+                    None => return ns.lookup(name, args, &mut String::new()),
+                },
             };
 
             Some(result)
@@ -141,5 +209,12 @@ where
     }
 }
 
+/// 艦隊を参照する関数か
+///
+/// `BattleMember::ns_in` でのみ定義される
+pub fn is_fleet_function(name: &str) -> bool {
+    name == "in_fleet" || name.starts_with("fleet_count_")
+}
+
 pub type BattleMemberRef<'a> = BattleMember<&'a Ship>;
 pub type BattleMemberMut<'a> = BattleMember<&'a mut Ship>;
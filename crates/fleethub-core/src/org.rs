@@ -8,7 +8,7 @@ use crate::{
     comp::Comp,
     fleet::Fleet,
     ship::Ship,
-    types::{FleetKey, FleetType, GearAttr, OrgType, ShipKey, Side},
+    types::{AirSquadronKey, FleetKey, FleetType, GearAttr, OrgType, ShipKey, Side},
 };
 
 #[wasm_bindgen]
@@ -60,6 +60,14 @@ impl Org {
     pub fn get_ship(&self, fleet_key: FleetKey, ship_key: ShipKey) -> Option<&Ship> {
         self.get_fleet(fleet_key).ships.get_by_key(ship_key)
     }
+
+    pub fn get_air_squadron_by_key(&self, key: AirSquadronKey) -> &AirSquadron {
+        match key {
+            AirSquadronKey::A1 => &self.a1,
+            AirSquadronKey::A2 => &self.a2,
+            AirSquadronKey::A3 => &self.a3,
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -2,6 +2,7 @@ mod anti_air_cutin;
 mod day_cutin;
 mod gunfit_accuracy;
 mod night_cutin;
+mod opening_asw;
 mod proficiency;
 mod special_enemy_modifiers;
 
@@ -11,20 +12,27 @@ use paste::paste;
 use wasm_bindgen::prelude::*;
 
 use crate::{
+    estimation::Estimation,
     gear::Gear,
     gear_array::{into_gear_key, GearArray},
-    master_data::{MasterShip, ShipEquippability},
+    master_data::{
+        FallbackStats, GunfitAccuracyBonus, MasterShip, MasterSpecialEnemyModifiers,
+        ShipEquippability,
+    },
     plane::{Plane, PlaneImpl, PlaneMut},
     types::{
-        ctype, gear_id, matches_gear_id, matches_ship_id, ship_id, AirStateRank, AirWaveType,
-        AswAttackType, AswPhase, CustomPowerModifiers, DamageState, DayPhaseAttackType,
-        DefensePower, EBonuses, GearAttr, GearType, MoraleState, NightAttackType,
-        NightPhaseAttackType, ShellingType, ShipAttr, ShipCategory, ShipMeta, ShipState, ShipType,
-        Side, SlotSizeVec, SpecialEnemyType,
+        ctype, find_speed_gear_set, gear_id, matches_gear_id, matches_ship_id, ship_id,
+        AirStateRank, AirWaveType, AswAttackType, AswPhase, CustomPowerModifiers, DamageState,
+        DayPhaseAttackType, DefensePower, EBonuses, GearAttr, GearType, MoraleState,
+        NightAttackType, NightPhaseAttackType, ShellingType, ShipAttr, ShipCategory, ShipMeta,
+        ShipState, ShipType, Side, SlotSizeVec, SpecialEnemyType, Speed, SpeedGearSet,
     },
 };
 
 pub use night_cutin::NightCutinTermParams;
+pub use opening_asw::{
+    OpeningAswGearSuggestion, OpeningAswReport, OpeningAswRequirement, OpeningAswRule,
+};
 
 #[wasm_bindgen]
 #[derive(Debug, Default, Clone)]
@@ -63,6 +71,13 @@ pub struct Ship {
     pub master: MasterShip,
     #[wasm_bindgen(skip)]
     pub equippability: ShipEquippability,
+    #[wasm_bindgen(skip)]
+    pub fallback_stats: FallbackStats,
+    #[wasm_bindgen(skip)]
+    pub special_enemy_modifiers: MasterSpecialEnemyModifiers,
+    /// マスタデータの表から求めたフィット砲補正
+    #[wasm_bindgen(skip)]
+    pub gunfit_accuracy_bonus: GunfitAccuracyBonus,
 }
 
 fn get_marriage_bonus(left: u16) -> u16 {
@@ -122,6 +137,15 @@ macro_rules! impl_naked_stats_with_level {
                 $(
                     #[wasm_bindgen(getter)]
                     pub fn [<naked_ $key>](&self) -> Option<u16> {
+                        self.[<naked_ $key _at_level>](self.level)
+                    }
+                )*
+            }
+
+            impl Ship {
+                $(
+                    /// 指定したレベルでの素の値
+                    pub fn [<naked_ $key _at_level>](&self, level: u16) -> Option<u16> {
                         let stat_mod = self.state.[<$key _mod>];
 
                         self.master
@@ -130,7 +154,7 @@ macro_rules! impl_naked_stats_with_level {
                             .map(|(at1, at99)| {
                                 let at1 = at1 as f64;
                                 let at99 = at99 as f64;
-                                let level = self.level as f64;
+                                let level = level as f64;
                                 let stat_mod = stat_mod.unwrap_or_default();
 
                                 let value = (((at99 - at1) * level) / 99.0 + at1).floor() as i16;
@@ -205,6 +229,7 @@ impl Ship {
         equippability: ShipEquippability,
         gears: GearArray,
         ebonuses: EBonuses,
+        fallback_stats: FallbackStats,
     ) -> Self {
         let ctype = master.ctype;
         let is_nisshin = ctype == ctype!("日進型");
@@ -247,11 +272,14 @@ impl Ship {
             ebonuses,
             equippability,
             master: master.clone(),
+            fallback_stats,
+            special_enemy_modifiers: Default::default(),
+            gunfit_accuracy_bonus: Default::default(),
             state,
         };
 
         if ship.current_hp == 0 {
-            ship.current_hp = ship.max_hp().unwrap_or_default();
+            ship.current_hp = ship.estimated_max_hp().value().unwrap_or_default();
         }
 
         ship
@@ -608,87 +636,21 @@ impl Ship {
         attack_type.map(NightPhaseAttackType::Night)
     }
 
+    /// 目標の速力に必要な追加の缶・タービン
+    pub fn speed_gear_set(&self, target: Speed) -> Option<SpeedGearSet> {
+        find_speed_gear_set(&self.master, &self.equippability, &self.gears, target)
+    }
+
     pub fn prioritizes_shelling(&self) -> bool {
         matches_ship_id!(self.ship_id, "鈴谷航改二" | "熊野航改二")
     }
 
     pub fn can_do_opening_asw(&self) -> bool {
-        let &Self {
-            ship_id,
-            ship_type,
-            ctype,
-            ..
-        } = self;
-
-        if matches_ship_id!(ship_id, "五十鈴改二" | "龍田改二" | "夕張改二丁")
-            || ctype == ctype!("Fletcher級")
-            || (ctype == ctype!("J級") && self.remodel_rank() >= 2)
-            || (ctype == ctype!("John C.Butler級") && self.remodel_rank() >= 2)
-        {
-            return true;
-        }
-
-        if ship_id == ship_id!("日向改二") {
-            return self
-                .gears
-                .has_by(|gear| matches_gear_id!(gear.gear_id, "S-51J" | "S-51J改"))
-                || self.gears.count_type(GearType::Rotorcraft) >= 2;
-        }
-
-        let has_sonar =
-            self.gears.has_type(GearType::Sonar) || self.gears.has_type(GearType::LargeSonar);
-        let asw = self.asw().unwrap_or_default();
-
-        if asw >= 100 && has_sonar {
-            return true;
-        }
-
-        let is_taiyou_class_kai_after = (ctype == ctype!("大鷹型") && self.remodel_rank() >= 3)
-            || ship_id == ship_id!("神鷹改");
-
-        if ship_id == ship_id!("加賀改二護") || is_taiyou_class_kai_after {
-            return self.has_non_zero_slot_gear_by(|gear| gear.has_attr(GearAttr::AntiSubAircraft));
-        }
-
-        let threshold = match ship_type {
-            ShipType::CVL => {
-                let has_high_asw_aircraft = self.gears.has_by(|gear| {
-                    gear.asw >= 7
-                        && matches!(
-                            gear.gear_type,
-                            GearType::CbTorpedoBomber
-                                | GearType::Rotorcraft
-                                | GearType::AntiSubPatrolAircraft
-                        )
-                });
-
-                if !has_high_asw_aircraft {
-                    return false;
-                }
-
-                if has_sonar && !matches_ship_id!(ship_id, "鈴谷航改二" | "熊野航改二") {
-                    50
-                } else {
-                    65
-                }
-            }
-            ShipType::DE => {
-                let equip_asw = self.gears.sum_by(|gear| gear.asw);
+        let (rule, required_asw, missing) = self.opening_asw_rule();
 
-                if has_sonar {
-                    60
-                } else if equip_asw >= 4 {
-                    75
-                } else {
-                    return false;
-                }
-            }
-            _ => {
-                return false;
-            }
-        };
-
-        threshold <= asw
+        rule != OpeningAswRule::Unavailable
+            && missing.is_empty()
+            && required_asw.is_none_or(|required| required <= self.asw().unwrap_or_default())
     }
 
     pub fn asw_synergy_mod(&self) -> f64 {
@@ -1064,6 +1026,10 @@ impl Ship {
         self.naked_speed() + self.ebonuses.speed
     }
 
+    pub fn speed_class(&self) -> Speed {
+        Speed::from_value(self.speed())
+    }
+
     #[wasm_bindgen(getter)]
     pub fn accuracy(&self) -> i16 {
         let accuracy = self.gears.sum_by(|gear| gear.accuracy);
@@ -1262,28 +1228,14 @@ impl Ship {
         postcap_additive: f64,
         postcap_multiplicative: f64,
     ) -> Option<f64> {
-        let base = (self.basic_evasion_term()? * formation_mod).floor();
-
-        let postcap = if base >= 65.0 {
-            (55.0 + 2.0 * (base - 65.0).sqrt()).floor()
-        } else if base >= 40.0 {
-            (40.0 + 3.0 * (base - 40.0).sqrt()).floor()
-        } else {
-            base
-        };
-
-        let total_stars = self.gears.sum_by(|gear| {
-            if gear.gear_type == GearType::Engine {
-                gear.stars
-            } else {
-                0
-            }
-        }) as f64;
-
-        let ibonus = (1.5 * total_stars.sqrt()).floor();
-        let post_multiplicative = (postcap + ibonus + postcap_additive) * postcap_multiplicative;
-
-        Some(post_multiplicative.floor() - self.remaining_fuel_mod())
+        self.basic_evasion_term().map(|basic| {
+            self.calc_evasion_term(
+                basic,
+                formation_mod,
+                postcap_additive,
+                postcap_multiplicative,
+            )
+        })
     }
 
     pub fn basic_defense_power(&self, armor_penetration: f64) -> Option<f64> {
@@ -1302,19 +1254,7 @@ impl Ship {
             0
         };
 
-        let total = self.gears.sum_by(|gear| {
-            if gear.gear_type == GearType::LandingCraft {
-                8
-            } else if gear.gear_type == GearType::CombatRation {
-                1
-            } else if gear.gear_id == gear_id!("ドラム缶(輸送用)") {
-                5
-            } else if gear.gear_id == gear_id!("特二式内火艇") {
-                2
-            } else {
-                0
-            }
-        });
+        let total = self.gears.sum_by(|gear| gear.transport_point());
 
         ship_type_tp + ship_bonus + total
     }
@@ -1341,10 +1281,104 @@ impl Ship {
     }
 
     pub fn set_damage_state(&mut self, damage_state: DamageState) {
-        let max_hp = self.max_hp().unwrap_or_default();
+        let max_hp = self.estimated_max_hp().value().unwrap_or_default();
         let bound = damage_state.bound(max_hp);
         self.current_hp = bound;
     }
+
+    fn calc_evasion_term(
+        &self,
+        basic_evasion_term: f64,
+        formation_mod: f64,
+        postcap_additive: f64,
+        postcap_multiplicative: f64,
+    ) -> f64 {
+        let base = (basic_evasion_term * formation_mod).floor();
+
+        let postcap = if base >= 65.0 {
+            (55.0 + 2.0 * (base - 65.0).sqrt()).floor()
+        } else if base >= 40.0 {
+            (40.0 + 3.0 * (base - 40.0).sqrt()).floor()
+        } else {
+            base
+        };
+
+        let total_stars = self.gears.sum_by(|gear| {
+            if gear.gear_type == GearType::Engine {
+                gear.stars
+            } else {
+                0
+            }
+        }) as f64;
+
+        let ibonus = (1.5 * total_stars.sqrt()).floor();
+        let post_multiplicative = (postcap + ibonus + postcap_additive) * postcap_multiplicative;
+
+        post_multiplicative.floor() - self.remaining_fuel_mod()
+    }
+
+    /// 最大耐久が不明な場合は類似艦から推定する
+    pub fn estimated_max_hp(&self) -> Estimation<u16> {
+        Estimation::with_fallback(self.max_hp(), || {
+            let base = self.fallback_stats.max_hp? as i16;
+            Some((base + self.state.max_hp_mod.unwrap_or_default()).max(0) as u16)
+        })
+    }
+
+    pub fn estimated_armor(&self) -> Estimation<u16> {
+        Estimation::with_fallback(self.armor(), || {
+            let naked =
+                self.fallback_stats.armor? as i16 + self.state.armor_mod.unwrap_or_default();
+            let total = self.gears.sum_by(|gear| gear.armor);
+            Some((naked + self.ebonuses.armor + total).max(0) as u16)
+        })
+    }
+
+    pub fn estimated_evasion(&self) -> Estimation<u16> {
+        Estimation::with_fallback(self.evasion(), || {
+            let naked =
+                self.fallback_stats.evasion? as i16 + self.state.evasion_mod.unwrap_or_default();
+            let total = self.gears.sum_by(|gear| gear.evasion);
+            Some((naked + self.ebonuses.evasion + total).max(0) as u16)
+        })
+    }
+
+    pub fn estimated_basic_evasion_term(&self) -> Estimation<f64> {
+        if let Some(value) = self.master.basic_evasion_term {
+            return Estimation::Just(value);
+        }
+
+        self.estimated_evasion()
+            .zip(Estimation::from(self.luck()))
+            .map(|(evasion, luck)| evasion as f64 + (2.0 * luck as f64).sqrt())
+    }
+
+    /// 回避が不明な場合は類似艦から推定した回避項
+    pub fn estimated_evasion_term(
+        &self,
+        formation_mod: f64,
+        postcap_additive: f64,
+        postcap_multiplicative: f64,
+    ) -> Estimation<f64> {
+        self.estimated_basic_evasion_term().map(|basic| {
+            self.calc_evasion_term(
+                basic,
+                formation_mod,
+                postcap_additive,
+                postcap_multiplicative,
+            )
+        })
+    }
+
+    /// 装甲が不明な場合は類似艦から推定した基本防御力
+    pub fn estimated_basic_defense_power(&self, armor_penetration: f64) -> Estimation<f64> {
+        self.estimated_armor().map(|armor| {
+            let value = armor as f64 + self.gears.sum_by(|gear| gear.ibonuses.defense_power)
+                - armor_penetration;
+
+            value.max(1.0)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1385,6 +1419,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_estimated_stats() {
+        let mut ship = Ship {
+            master: MasterShip {
+                ship_id: 1501,
+                luck: StatInterval(Some(10), None),
+                ..Default::default()
+            },
+            fallback_stats: FallbackStats {
+                max_hp: Some(500),
+                armor: Some(100),
+                evasion: Some(30),
+            },
+            ..Default::default()
+        };
+
+        ship.gears.push(Gear {
+            armor: 5,
+            ..Default::default()
+        });
+
+        assert_eq!(ship.basic_defense_power(0.0), None);
+        assert_eq!(ship.estimated_max_hp(), Estimation::Rough(500));
+        assert_eq!(ship.estimated_armor(), Estimation::Rough(105));
+        assert_eq!(
+            ship.estimated_basic_defense_power(5.0),
+            Estimation::Rough(100.0)
+        );
+        assert!(ship.estimated_evasion_term(1.0, 0.0, 1.0).is_rough());
+
+        ship.master.armor = StatInterval(None, Some(80));
+        assert_eq!(ship.estimated_armor(), Estimation::Just(85));
+    }
+
     #[test]
     fn test_stats() {
         macro_rules! def_stats_test {
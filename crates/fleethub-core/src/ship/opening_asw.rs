@@ -0,0 +1,293 @@
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    factory::Factory,
+    gear_array::{into_gear_key, GearArray},
+    types::{
+        ctype, matches_gear_id, matches_ship_id, ship_id, GearAttr, GearState, GearType, ShipType,
+    },
+};
+
+use super::Ship;
+
+/// 艦娘の最大レベル
+const MAX_SHIP_LEVEL: u16 = 185;
+
+/// 適用される先制対潜の条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub enum OpeningAswRule {
+    /// 無条件で先制対潜が可能な艦
+    Unconditional,
+    /// 日向改二
+    HyugaKaiNi,
+    /// 加賀改二護, 大鷹型改以降
+    EscortCarrier,
+    /// 軽空母
+    LightCarrier,
+    /// 海防艦
+    EscortShip,
+    /// 対潜値100以上とソナー
+    Standard,
+    /// 先制対潜が不可能な艦種
+    Unavailable,
+}
+
+/// 先制対潜に不足している装備条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub enum OpeningAswRequirement {
+    /// ソナーまたは大型ソナー
+    Sonar,
+    /// S-51J系またはオートジャイロ2つ以上
+    HyugaRotorcraft,
+    /// 対潜値7以上の艦攻, オートジャイロ, 対潜哨戒機
+    HighAswAircraft,
+    /// 搭載数が0でない対潜攻撃可能な航空機
+    AntiSubAircraft,
+}
+
+/// 先制対潜を可能にする装備の候補
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
+pub struct OpeningAswGearSuggestion {
+    /// 装備するスロット
+    pub key: String,
+    pub gear_id: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct OpeningAswReport {
+    pub rule: OpeningAswRule,
+    pub can_do: bool,
+    /// 装備を含む対潜値
+    pub asw: u16,
+    /// 必要な対潜値
+    pub required_asw: Option<u16>,
+    /// 必要な対潜値までの不足分
+    pub asw_shortfall: u16,
+    /// 現在の装備で必要な対潜値に到達するレベル
+    pub required_level: Option<u16>,
+    pub missing: Vec<OpeningAswRequirement>,
+    pub asw_synergy_mod: f64,
+    /// 対潜値の不足分と装備条件を補う装備
+    ///
+    /// `Ship::opening_asw_report` では常に `None` となり、`FhCore::opening_asw_report` で設定される
+    pub suggestion: Option<OpeningAswGearSuggestion>,
+}
+
+#[wasm_bindgen]
+impl Ship {
+    pub fn opening_asw_report(&self) -> OpeningAswReport {
+        let (rule, required_asw, missing) = self.opening_asw_rule();
+        let asw = self.asw().unwrap_or_default();
+
+        let asw_shortfall = required_asw
+            .map(|required| required.saturating_sub(asw))
+            .unwrap_or_default();
+
+        let can_do = self.can_do_opening_asw();
+
+        let required_level = required_asw.and_then(|required| {
+            (self.level..=MAX_SHIP_LEVEL.max(self.level))
+                .find(|&level| self.asw_at_level(level).unwrap_or_default() >= required)
+        });
+
+        OpeningAswReport {
+            rule,
+            can_do,
+            asw,
+            required_asw,
+            asw_shortfall,
+            required_level,
+            missing,
+            asw_synergy_mod: self.asw_synergy_mod(),
+            suggestion: None,
+        }
+    }
+}
+
+impl Ship {
+    fn asw_at_level(&self, level: u16) -> Option<u16> {
+        let naked = self.naked_asw_at_level(level)? as i16;
+        let total = naked + self.ebonuses.asw + self.gears.sum_by(|gear| gear.asw);
+
+        Some(total.max(0) as u16)
+    }
+
+    /// 装備1つの追加または入れ替えで先制対潜が可能になる装備を探す
+    ///
+    /// 対潜値の高い装備から順に、空きスロット、対潜値の低い装備のスロットの順で試す
+    pub fn suggest_opening_asw_gear(&self, factory: &Factory) -> Option<OpeningAswGearSuggestion> {
+        let (rule, ..) = self.opening_asw_rule();
+
+        if self.can_do_opening_asw()
+            || matches!(
+                rule,
+                OpeningAswRule::Unconditional | OpeningAswRule::Unavailable
+            )
+        {
+            return None;
+        }
+
+        let mut keys = (0..GearArray::CAPACITY)
+            .filter(|&index| index < self.slotnum() || index == GearArray::EXSLOT_INDEX)
+            .filter_map(|index| {
+                let key = into_gear_key(index)?;
+                let asw = self.gears.get(index).map(|gear| gear.asw);
+                Some((key, asw))
+            })
+            .collect::<Vec<_>>();
+        keys.sort_by_key(|&(_, asw)| asw);
+
+        let mut candidates = factory
+            .master_data
+            .gears
+            .iter()
+            .filter(|gear| !gear.is_abyssal() && gear.asw > 0)
+            .filter_map(|gear| {
+                factory.create_gear(Some(GearState {
+                    gear_id: gear.gear_id,
+                    ..Default::default()
+                }))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|gear| (std::cmp::Reverse(gear.asw), gear.gear_id));
+
+        candidates.iter().find_map(|gear| {
+            keys.iter().find_map(|&(key, current_asw)| {
+                if current_asw.is_some_and(|asw| asw >= gear.asw) || !self.can_equip(gear, key) {
+                    return None;
+                }
+
+                let mut state = self.state();
+                *state.gears.slot_mut(key)? = Some(GearState {
+                    gear_id: gear.gear_id,
+                    ..Default::default()
+                });
+
+                factory
+                    .create_ship(Some(state))?
+                    .can_do_opening_asw()
+                    .then(|| OpeningAswGearSuggestion {
+                        key: key.to_string(),
+                        gear_id: gear.gear_id,
+                    })
+            })
+        })
+    }
+
+    pub(super) fn opening_asw_rule(
+        &self,
+    ) -> (OpeningAswRule, Option<u16>, Vec<OpeningAswRequirement>) {
+        let &Self {
+            ship_id,
+            ship_type,
+            ctype,
+            ..
+        } = self;
+
+        if matches_ship_id!(ship_id, "五十鈴改二" | "龍田改二" | "夕張改二丁")
+            || ctype == ctype!("Fletcher級")
+            || (ctype == ctype!("J級") && self.remodel_rank() >= 2)
+            || (ctype == ctype!("John C.Butler級") && self.remodel_rank() >= 2)
+        {
+            return (OpeningAswRule::Unconditional, None, vec![]);
+        }
+
+        if ship_id == ship_id!("日向改二") {
+            let has_rotorcraft = self
+                .gears
+                .has_by(|gear| matches_gear_id!(gear.gear_id, "S-51J" | "S-51J改"))
+                || self.gears.count_type(GearType::Rotorcraft) >= 2;
+
+            let missing = if has_rotorcraft {
+                vec![]
+            } else {
+                vec![OpeningAswRequirement::HyugaRotorcraft]
+            };
+
+            return (OpeningAswRule::HyugaKaiNi, None, missing);
+        }
+
+        let has_sonar =
+            self.gears.has_type(GearType::Sonar) || self.gears.has_type(GearType::LargeSonar);
+        let asw = self.asw().unwrap_or_default();
+
+        if asw >= 100 && has_sonar {
+            return (OpeningAswRule::Standard, Some(100), vec![]);
+        }
+
+        let is_taiyou_class_kai_after = (ctype == ctype!("大鷹型") && self.remodel_rank() >= 3)
+            || ship_id == ship_id!("神鷹改");
+
+        if ship_id == ship_id!("加賀改二護") || is_taiyou_class_kai_after {
+            let missing = if self
+                .has_non_zero_slot_gear_by(|gear| gear.has_attr(GearAttr::AntiSubAircraft))
+            {
+                vec![]
+            } else {
+                vec![OpeningAswRequirement::AntiSubAircraft]
+            };
+
+            return (OpeningAswRule::EscortCarrier, None, missing);
+        }
+
+        match ship_type {
+            ShipType::CVL => {
+                let has_high_asw_aircraft = self.gears.has_by(|gear| {
+                    gear.asw >= 7
+                        && matches!(
+                            gear.gear_type,
+                            GearType::CbTorpedoBomber
+                                | GearType::Rotorcraft
+                                | GearType::AntiSubPatrolAircraft
+                        )
+                });
+
+                let missing = if has_high_asw_aircraft {
+                    vec![]
+                } else {
+                    vec![OpeningAswRequirement::HighAswAircraft]
+                };
+
+                let threshold = if has_sonar
+                    && !matches_ship_id!(ship_id, "鈴谷航改二" | "熊野航改二")
+                {
+                    50
+                } else {
+                    65
+                };
+
+                (OpeningAswRule::LightCarrier, Some(threshold), missing)
+            }
+            ShipType::DE => {
+                let equip_asw = self.gears.sum_by(|gear| gear.asw);
+
+                if has_sonar {
+                    (OpeningAswRule::EscortShip, Some(60), vec![])
+                } else if equip_asw >= 4 {
+                    (OpeningAswRule::EscortShip, Some(75), vec![])
+                } else {
+                    (
+                        OpeningAswRule::EscortShip,
+                        Some(60),
+                        vec![OpeningAswRequirement::Sonar],
+                    )
+                }
+            }
+            _ if self.is_submarine() || self.master.asw.0.unwrap_or_default() == 0 => {
+                (OpeningAswRule::Unavailable, None, vec![])
+            }
+            _ => {
+                let missing = if has_sonar {
+                    vec![]
+                } else {
+                    vec![OpeningAswRequirement::Sonar]
+                };
+
+                (OpeningAswRule::Standard, Some(100), missing)
+            }
+        }
+    }
+}
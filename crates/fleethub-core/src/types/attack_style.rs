@@ -272,7 +272,7 @@ mod test {
         assert_eq!(
             ShellingStyle {
                 attack_type: ShellingType::Normal,
-                cutin: Some(DayCutinLike::FleetCutin(FleetCutin::NagatoClassCutin)),
+                cutin: Some(DayCutinLike::FleetCutin(FleetCutin::NAGATO_CLASS_CUTIN)),
                 ..Default::default()
             }
             .key(),
@@ -302,7 +302,7 @@ mod test {
         assert_eq!(
             NightAttackStyle {
                 attack_type: NightAttackType::Normal,
-                cutin: Some(NightCutinLike::FleetCutin(FleetCutin::KongouClassCutin)),
+                cutin: Some(NightCutinLike::FleetCutin(FleetCutin::KONGOU_CLASS_CUTIN)),
                 ..Default::default()
             }
             .key(),
@@ -7,11 +7,11 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnError};
 use tsify::Tsify;
 
-use crate::member::BattleMemberRef;
+use crate::{comp::Comp, member::BattleMemberRef};
 
 use super::{
-    AttackType, CompiledEvaler, DayCutin, DayCutinLike, Formation, NightCutin, NightCutinLike,
-    NodeState, ShipConditions,
+    AttackType, CompiledEvaler, DayCutin, DayCutinLike, FleetCutin, Formation, NightCutin,
+    NightCutinLike, NodeState, ShipConditions,
 };
 
 #[serde_as]
@@ -101,12 +101,46 @@ impl NestedFormationDef {
 pub struct AntiAirCutinDef {
     pub id: u8,
     #[serde_as(as = "DefaultOnError")]
+    #[serde(default)]
     pub type_factor: Option<u8>,
     #[serde_as(as = "DefaultOnError")]
+    #[serde(default)]
     pub multiplier: Option<f64>,
     #[serde_as(as = "DefaultOnError")]
+    #[serde(default)]
     pub guaranteed: Option<u8>,
     pub sequential: Option<bool>,
+    /// 判定順 (昇順)
+    #[serde(default)]
+    pub priority: Option<u16>,
+    /// 発動条件 (いずれかを満たす場合に発動可能)
+    #[serde(default)]
+    pub requirements: Vec<AntiAirCutinRequirement>,
+    /// 満たす場合、以降の種別を判定しない
+    #[serde(default)]
+    pub exclusive: Option<AntiAirCutinRequirement>,
+}
+
+/// 対空カットインの装備条件
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct AntiAirCutinGearRequirement {
+    /// 装備の条件式
+    pub gear: CompiledEvaler,
+    /// 必要な個数
+    #[serde(default = "num_traits::one")]
+    pub count: usize,
+}
+
+/// 対空カットインの発動条件
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct AntiAirCutinRequirement {
+    /// 艦の条件式
+    ///
+    /// 空の場合は全ての艦が対象
+    #[serde(default)]
+    pub ship: CompiledEvaler,
+    #[serde(default)]
+    pub gears: Vec<AntiAirCutinGearRequirement>,
 }
 
 impl AntiAirCutinDef {
@@ -120,7 +154,7 @@ impl AntiAirCutinDef {
 }
 
 #[serde_as]
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(from_wasm_abi)]
 pub struct DayCutinDef {
     pub tag: DayCutin,
@@ -160,7 +194,7 @@ impl DayCutinDef {
     }
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 #[serde(default)]
 pub struct HistoricalBonusDef {
     map: i16,
@@ -178,18 +212,47 @@ pub struct HistoricalBonusDef {
 }
 
 impl HistoricalBonusDef {
+    /// `ship_comp` と `enemy_comp` はそれぞれが所属する艦隊
     pub fn matches(
         &self,
         node_state: NodeState,
         ship: &BattleMemberRef,
         enemy: &BattleMemberRef,
+        ship_comp: Option<&Comp>,
+        enemy_comp: Option<&Comp>,
     ) -> bool {
+        self.matches_node(node_state)
+            && self.matches_ship(ship, ship_comp)
+            && (self.enemy.is_empty() || self.enemy.matches(&mut enemy.ns_in(enemy_comp)))
+    }
+
+    /// 海域、マス、攻略段階、ギミックの条件を満たすか
+    pub fn matches_node(&self, node_state: NodeState) -> bool {
         self.map == node_state.map
             && (self.node.is_empty() || self.node.matches(&mut node_state.node.ns()))
             && (self.phase == 0 || self.phase == node_state.phase)
             && (!self.debuff || self.debuff == node_state.debuff)
-            && (self.ship.is_empty() || self.ship.matches(&mut ship.ns()))
-            && (self.enemy.is_empty() || self.enemy.matches(&mut enemy.ns()))
+    }
+
+    pub fn matches_ship(&self, ship: &BattleMemberRef, ship_comp: Option<&Comp>) -> bool {
+        self.ship.is_empty() || self.ship.matches(&mut ship.ns_in(ship_comp))
+    }
+
+    /// 艦の条件を最上位の `&&` で区切った項のうち、1項のみを満たさないか
+    pub fn misses_one_ship_term(&self, ship: &BattleMemberRef, ship_comp: Option<&Comp>) -> bool {
+        let terms = self.ship.conjuncts();
+
+        terms.len() > 1
+            && terms
+                .iter()
+                .filter(|term| !term.matches(&mut ship.ns_in(ship_comp)))
+                .count()
+                == 1
+    }
+
+    /// 敵艦の条件を持つか
+    pub fn has_enemy_condition(&self) -> bool {
+        !self.enemy.is_empty()
     }
 }
 
@@ -211,7 +274,7 @@ impl Default for HistoricalParams {
 }
 
 #[serde_as]
-#[derive(Debug, Default, Clone, Deserialize, Tsify)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(from_wasm_abi)]
 pub struct NightCutinDef {
     pub tag: NightCutin,
@@ -243,12 +306,78 @@ impl NightCutinDef {
     }
 }
 
+/// 艦隊特殊攻撃の参加艦の条件
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FleetCutinParticipantDef {
+    /// 艦隊内の位置 (旗艦は0)
+    pub position: usize,
+    /// 艦の条件式
+    #[serde(default)]
+    pub ship: CompiledEvaler,
+}
+
+/// 条件を満たす場合に乗算する補正
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FleetCutinModifierRule {
+    /// 空の場合は常に適用される
+    #[serde(default)]
+    pub expr: CompiledEvaler,
+    pub a: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FleetCutinAttackDef {
+    /// 攻撃艦の艦隊内の位置
+    pub position: usize,
+    /// 艦隊の名前空間で評価する威力補正
+    #[serde(default)]
+    pub power_mods: Vec<FleetCutinModifierRule>,
+}
+
+/// 艦隊特殊攻撃の装備補正
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FleetCutinEquipmentRule {
+    /// 攻撃艦の名前空間で評価する条件式
+    pub expr: CompiledEvaler,
+    pub a: f64,
+    /// 適用する攻撃の番号 (空の場合は全ての攻撃)
+    #[serde(default)]
+    pub attacks: Vec<usize>,
+}
+
+/// 艦隊特殊攻撃の定義
+///
+/// 艦隊の名前空間では `s1_ship_id` のように `s{n}_` を付けることで n 番艦の変数を参照できる
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FleetCutinDef {
+    /// 識別子 (定義ごとに一意な名前)
+    pub tag: FleetCutin,
+    pub formations: Vec<Formation>,
+    #[serde(default)]
+    pub participants: Vec<FleetCutinParticipantDef>,
+    /// 艦隊の条件式
+    #[serde(default)]
+    pub expr: CompiledEvaler,
+    /// 攻撃 (最大3回)
+    pub attacks: Vec<FleetCutinAttackDef>,
+    #[serde(default)]
+    pub equipment_mods: Vec<FleetCutinEquipmentRule>,
+    /// 発動率の式
+    #[serde(default)]
+    pub rate: CompiledEvaler,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Tsify)]
 pub struct BattleDefinitions {
     pub formation: HashMap<Formation, NestedFormationDef>,
     pub anti_air_cutin: HashMap<u8, AntiAirCutinDef>,
+    /// 発動条件を持つ対空カットインの判定順
+    #[serde(default)]
+    pub anti_air_cutin_priority: Vec<u8>,
     pub day_cutin: HashMap<DayCutin, DayCutinDef>,
     pub night_cutin: HashMap<NightCutin, NightCutinDef>,
+    #[serde(default)]
+    pub fleet_cutin: Vec<FleetCutinDef>,
     pub historical_bonuses: Vec<HistoricalBonusDef>,
 }
 
@@ -271,6 +400,8 @@ impl BattleDefinitions {
         node_state: NodeState,
         attacker: &BattleMemberRef,
         target: &BattleMemberRef,
+        attacker_comp: Option<&Comp>,
+        target_comp: Option<&Comp>,
     ) -> HistoricalParams {
         let mut params = HistoricalParams::default();
 
@@ -281,7 +412,7 @@ impl BattleDefinitions {
         if attacker.position.org_type.is_player() {
             self.historical_bonuses
                 .iter()
-                .filter(|def| def.matches(node_state, attacker, target))
+                .filter(|def| def.matches(node_state, attacker, target, attacker_comp, target_comp))
                 .for_each(|def| {
                     params.power_mod *= def.power_mod;
                     params.accuracy_mod *= def.accuracy_mod;
@@ -289,7 +420,7 @@ impl BattleDefinitions {
         } else {
             self.historical_bonuses
                 .iter()
-                .filter(|def| def.matches(node_state, target, attacker))
+                .filter(|def| def.matches(node_state, target, attacker, target_comp, attacker_comp))
                 .for_each(|def| {
                     params.target_evasion_mod *= def.evasion_mod;
                 })
@@ -302,6 +433,13 @@ impl BattleDefinitions {
         self.get_formation_def(formation, 0, 6).fleet_anti_air_mod
     }
 
+    /// 発動条件を持つ対空カットインの定義を判定順に返す
+    pub fn anti_air_cutin_defs_by_priority(&self) -> impl Iterator<Item = &AntiAirCutinDef> {
+        self.anti_air_cutin_priority
+            .iter()
+            .filter_map(|id| self.anti_air_cutin.get(id))
+    }
+
     pub fn get_day_cutin(&self, cutin: Option<DayCutin>) -> Option<&DayCutinDef> {
         self.day_cutin.get(&cutin?)
     }
@@ -494,4 +632,114 @@ mod test {
                 )
             });
     }
+
+    #[test]
+    fn test_historical_bonus_fleet_conditions() {
+        use crate::{
+            comp::Comp,
+            fleet::Fleet,
+            ship::Ship,
+            types::{OrgType, ShipPosition, ShipType},
+        };
+
+        let ship = |ship_id: u16, ship_type: ShipType| {
+            let mut ship = Ship::default();
+            ship.ship_id = ship_id;
+            ship.ship_type = ship_type;
+            ship
+        };
+
+        let comp = Comp {
+            org_type: OrgType::Single,
+            hq_level: 120,
+            main: Fleet {
+                len: 6,
+                ships: [Some(ship(1, ShipType::DD)), Some(ship(2, ShipType::BB))]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            escort: None,
+            route_sup: None,
+            boss_sup: None,
+        };
+
+        let def: HistoricalBonusDef = serde_json::from_value(serde_json::json!({
+            "map": 581,
+            "ship": "position == 1 && in_fleet(2) && fleet_count_stype(8, 9) == 1 && fleet_count_ship_id(3) == 0",
+            "power_mod": 1.2,
+        }))
+        .unwrap();
+
+        let node_state = NodeState {
+            map: 581,
+            ..Default::default()
+        };
+
+        let attacker = comp
+            .get_battle_member(Formation::LINE_AHEAD, ShipPosition::default())
+            .unwrap();
+        let enemy_ship = ship(1501, ShipType::DD);
+        let enemy = BattleMemberRef::new(
+            &enemy_ship,
+            ShipPosition::default(),
+            Formation::LINE_AHEAD,
+            None,
+        );
+
+        assert!(def.matches(node_state, &attacker, &enemy, Some(&comp), None));
+        assert!(!def.matches(node_state, &attacker, &enemy, None, None));
+
+        let second = comp
+            .get_battle_member(
+                Formation::LINE_AHEAD,
+                ShipPosition {
+                    index: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert!(!def.matches(node_state, &second, &enemy, Some(&comp), None));
+    }
+
+    #[test]
+    fn test_historical_bonus_gear_conditions() {
+        use crate::{gear::Gear, ship::Ship, types::GearAttr};
+
+        let mut ship = Ship::default();
+        ship.gears.push(Gear {
+            gear_id: 1,
+            attrs: GearAttr::MainGun.into(),
+            ..Default::default()
+        });
+        ship.gears.push(Gear {
+            gear_id: 2,
+            ..Default::default()
+        });
+
+        let member = BattleMemberRef::new(&ship, Default::default(), Formation::LINE_AHEAD, None);
+        let node_state = NodeState {
+            map: 581,
+            ..Default::default()
+        };
+
+        let matches = |expr: String| {
+            let def: HistoricalBonusDef = serde_json::from_value(serde_json::json!({
+                "map": 581,
+                "ship": expr,
+            }))
+            .unwrap();
+
+            def.matches(node_state, &member, &member, None, None)
+        };
+
+        assert!(matches("count_gear_attr_MainGun == 1".into()));
+        assert!(matches(
+            "has_gear_attr_MainGun && !has_gear_attr_Radar".into()
+        ));
+        assert!(matches("has_gear(2, 3) && count_gear(1, 2) == 2".into()));
+        assert!(matches("count(1, 2) == 1".into()));
+        assert!(!matches("count_gear_attr(0) == 1".into()));
+    }
 }
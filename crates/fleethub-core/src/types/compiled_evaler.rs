@@ -1,5 +1,5 @@
 use fasteval::{eval_compiled_ref, Compiler, EvalNamespace, Evaler, Instruction, Parser, Slab};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -7,63 +7,132 @@ const TS_APPEND_CONTENT: &'static str = r#"
 export type CompiledEvaler = string;
 "#;
 
+/// 事前に解析した式
+///
+/// デシリアライズでは解析できない式もエラーとして保持し、評価時にそのエラーを返す
 #[derive(Debug, Default, Deserialize)]
-#[serde(try_from = "String")]
+#[serde(from = "String")]
 pub struct CompiledEvaler {
     expr: String,
     slab: Slab,
     instruction: Instruction,
+    error: Option<fasteval::Error>,
 }
 
 impl CompiledEvaler {
     pub fn new(expr: String) -> Result<Self, fasteval::Error> {
+        let evaler = Self::new_lenient(expr);
+
+        match evaler.error {
+            Some(err) => Err(err),
+            None => Ok(evaler),
+        }
+    }
+
+    /// 解析できない式もエラーを保持したまま作成する
+    pub fn new_lenient(expr: String) -> Self {
+        let unparsed = |expr, error| Self {
+            expr,
+            slab: Slab::with_capacity(0),
+            instruction: Instruction::IConst(0.0),
+            error,
+        };
+
         if expr.is_empty() {
-            return Ok(Self {
-                expr,
-                slab: Slab::with_capacity(0),
-                instruction: Instruction::IConst(0.0),
-            });
+            return unparsed(expr, None);
         }
 
         let parser = Parser::new();
         let mut slab = Slab::new();
 
-        let instruction = parser
-            .parse(expr.as_str(), &mut slab.ps)?
-            .from(&slab.ps)
-            .compile(&slab.ps, &mut slab.cs);
-
-        Ok(Self {
-            expr,
-            slab,
-            instruction,
-        })
+        match parser.parse(expr.as_str(), &mut slab.ps) {
+            Ok(expression) => {
+                let instruction = expression.from(&slab.ps).compile(&slab.ps, &mut slab.cs);
+
+                Self {
+                    expr,
+                    slab,
+                    instruction,
+                    error: None,
+                }
+            }
+            Err(err) => unparsed(expr, Some(err)),
+        }
     }
 
     pub fn eval<T: EvalNamespace>(&self, ns: &mut T) -> Result<f64, fasteval::Error> {
+        if let Some(err) = &self.error {
+            return Err(err.clone());
+        }
+
         Ok(eval_compiled_ref!(&self.instruction, &self.slab, ns))
     }
 
+    /// 解析時のエラー
+    pub fn error(&self) -> Option<&fasteval::Error> {
+        self.error.as_ref()
+    }
+
     pub fn matches<T: EvalNamespace>(&self, ns: &mut T) -> bool {
         self.eval(ns).unwrap_or_default() == 1.0
     }
 
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
     pub fn is_empty(&self) -> bool {
         self.expr.is_empty()
     }
+
+    /// 最上位の `&&` で区切った各項
+    ///
+    /// 最上位に `||` を含む場合は分割しない
+    pub fn conjuncts(&self) -> Vec<CompiledEvaler> {
+        let bytes = self.expr.as_bytes();
+        let mut depth = 0;
+        let mut start = 0;
+        let mut terms = vec![];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match (bytes[i], bytes.get(i + 1)) {
+                (b'(', _) => depth += 1,
+                (b')', _) => depth -= 1,
+                (b'|', Some(b'|')) if depth == 0 => return vec![self.clone()],
+                (b'&', Some(b'&')) if depth == 0 => {
+                    terms.push(&self.expr[start..i]);
+                    start = i + 2;
+                    i += 1;
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+
+        terms.push(&self.expr[start..]);
+        terms
+            .into_iter()
+            .map(|term| Self::new_lenient(term.trim().to_string()))
+            .collect()
+    }
 }
 
 impl Clone for CompiledEvaler {
     fn clone(&self) -> Self {
-        Self::new(self.expr.clone()).unwrap()
+        Self::new_lenient(self.expr.clone())
     }
 }
 
-impl TryFrom<String> for CompiledEvaler {
-    type Error = fasteval::Error;
+impl Serialize for CompiledEvaler {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.expr)
+    }
+}
 
-    fn try_from(expr: String) -> Result<Self, Self::Error> {
-        Self::new(expr)
+impl From<String> for CompiledEvaler {
+    fn from(expr: String) -> Self {
+        Self::new_lenient(expr)
     }
 }
 
@@ -92,5 +161,24 @@ mod test {
 
         let empty_evaler: CompiledEvaler = serde_json::from_str("\"\"").unwrap();
         assert_eq!(empty_evaler.eval(&mut ns).unwrap(), 0.0);
+
+        let invalid_evaler: CompiledEvaler = serde_json::from_str("\"x ==\"").unwrap();
+        assert!(invalid_evaler.error().is_some());
+        assert!(invalid_evaler.eval(&mut ns).is_err());
+        assert!(!invalid_evaler.matches(&mut ns));
+        assert!(invalid_evaler.clone().error().is_some());
+        assert!(CompiledEvaler::new("x ==".into()).is_err());
+
+        let exprs = |evaler: CompiledEvaler| -> Vec<String> {
+            evaler
+                .conjuncts()
+                .iter()
+                .map(|term| term.expr().to_string())
+                .collect()
+        };
+        let evaler = CompiledEvaler::new("x == 1 && (y || z) && w".into()).unwrap();
+        assert_eq!(exprs(evaler), ["x == 1", "(y || z)", "w"]);
+        let evaler = CompiledEvaler::new("x && y || z".into()).unwrap();
+        assert_eq!(exprs(evaler), ["x && y || z"]);
     }
 }
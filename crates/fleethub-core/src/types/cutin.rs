@@ -1,6 +1,9 @@
+use std::borrow::Cow;
+
 use enumset::EnumSetType;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
+use wasm_bindgen::prelude::*;
 
 #[allow(clippy::derive_hash_xor_eq)]
 #[derive(Debug, Default, Hash, EnumSetType, Serialize, Deserialize, Tsify)]
@@ -77,19 +80,40 @@ pub enum NightCutin {
     Photobomber,
 }
 
-#[allow(clippy::derive_hash_xor_eq)]
-#[derive(Debug, EnumSetType, Hash, Serialize, Deserialize, Tsify)]
-#[tsify(into_wasm_abi, from_wasm_abi)]
-pub enum FleetCutin {
-    NelsonTouch,
-    NagatoClassCutin,
-    ColoradoClassCutin,
-    KongouClassCutin,
-    Yamato2ShipCutin,
-    Yamato3ShipCutin,
+#[wasm_bindgen(typescript_custom_section)]
+const TS_APPEND_CONTENT: &'static str = r#"
+export type FleetCutin = string;
+"#;
+
+/// 艦隊特殊攻撃の識別子
+///
+/// マスタデータの `fleet_cutin` の `tag` で定義した名前を用いる
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FleetCutin(Cow<'static, str>);
+
+impl FleetCutin {
+    pub const NELSON_TOUCH: Self = Self::known("NelsonTouch");
+    pub const NAGATO_CLASS_CUTIN: Self = Self::known("NagatoClassCutin");
+    pub const COLORADO_CLASS_CUTIN: Self = Self::known("ColoradoClassCutin");
+    pub const KONGOU_CLASS_CUTIN: Self = Self::known("KongouClassCutin");
+    pub const YAMATO_2_SHIP_CUTIN: Self = Self::known("Yamato2ShipCutin");
+    pub const YAMATO_3_SHIP_CUTIN: Self = Self::known("Yamato3ShipCutin");
+
+    const fn known(name: &'static str) -> Self {
+        Self(Cow::Borrowed(name))
+    }
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(Cow::Owned(name.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(untagged)]
 pub enum DayCutinLike {
@@ -98,9 +122,9 @@ pub enum DayCutinLike {
 }
 
 impl DayCutinLike {
-    pub fn as_day_cutin(self) -> Option<DayCutin> {
+    pub fn as_day_cutin(&self) -> Option<DayCutin> {
         match self {
-            Self::DayCutin(cutin) => Some(cutin),
+            Self::DayCutin(cutin) => Some(*cutin),
             _ => None,
         }
     }
@@ -118,7 +142,7 @@ impl From<FleetCutin> for DayCutinLike {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Tsify)]
 #[serde(untagged)]
 pub enum NightCutinLike {
     NightCutin(NightCutin),
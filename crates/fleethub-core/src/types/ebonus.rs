@@ -1,18 +1,19 @@
-#![allow(dead_code)]
-
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 use crate::{
     gear::Gear,
-    gear_array::GearArray,
-    master_data::MasterShip,
-    types::{ctype, gear_id, ship_id, ShipAttr, SpeedGroup},
+    gear_array::{into_gear_key, GearArray},
+    master_data::{MasterEBonuses, MasterShip, ShipEquippability},
+    types::{
+        ctype, gear_id, ship_id, GearType, GearTypeIdArray, ShipAttr, Speed, SpeedGearSet,
+        SpeedGroup,
+    },
 };
 
-use super::GearTypeIdArray;
-
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(module = "equipment-bonus")]
 extern "C" {
     #[wasm_bindgen(js_name = createEquipmentBonuses)]
@@ -21,6 +22,7 @@ extern "C" {
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(default)]
 pub struct EBonuses {
     pub firepower: i16,
     pub torpedo: i16,
@@ -106,6 +108,70 @@ impl GearInput {
 #[tsify(into_wasm_abi)]
 struct GearVecInput(Vec<GearInput>);
 
+/// `equipment-bonus` による装備ボーナス
+///
+/// 利用できない環境では None となる
+#[cfg(target_arch = "wasm32")]
+pub fn equipment_bonus_js(ship: &MasterShip, gears: &[&Gear]) -> Option<EBonuses> {
+    let ship_input = ShipInput::new(ship);
+    let gears_input = GearVecInput(gears.iter().map(|gear| GearInput::new(gear)).collect());
+
+    Some(create_equipment_bonuses(ship_input, gears_input))
+}
+
+/// `equipment-bonus` による装備ボーナス
+///
+/// node と `equipment-bonus` が利用できない環境では None となり、以降は node を起動しない
+#[cfg(not(target_arch = "wasm32"))]
+pub fn equipment_bonus_js(ship: &MasterShip, gears: &[&Gear]) -> Option<EBonuses> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    if UNAVAILABLE.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let ship_input = ShipInput::new(ship);
+    let gears_input = GearVecInput(gears.iter().map(|gear| GearInput::new(gear)).collect());
+
+    let ship_json = serde_json::to_string(&ship_input).ok()?;
+    let gears_json = serde_json::to_string(&gears_input).ok()?;
+
+    let code = format!(
+        r#"
+            const {{ createEquipmentBonuses }} = require("equipment-bonus");
+            const result = createEquipmentBonuses({ship_json}, {gears_json});
+            console.log(JSON.stringify(result));
+        "#
+    );
+
+    let result = std::process::Command::new("node")
+        .arg("-e")
+        .arg(code)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice(&output.stdout).ok());
+
+    if result.is_none() {
+        UNAVAILABLE.store(true, Ordering::Relaxed);
+    }
+
+    result
+}
+
+/// 装備ボーナスの定義が網羅的でない場合は `equipment-bonus` を優先する
+fn eval_ebonuses(ship: &MasterShip, gears: &[&Gear], rules: &MasterEBonuses) -> EBonuses {
+    if !rules.complete {
+        if let Some(ebonuses) = equipment_bonus_js(ship, gears) {
+            return ebonuses;
+        }
+    }
+
+    rules.eval(ship, gears.iter().copied())
+}
+
 fn get_speed_bonus(ship: &MasterShip, gears: &GearArray) -> u8 {
     let base = match ship.speed {
         5 => BaseSpeed::Slow,
@@ -214,16 +280,111 @@ fn get_speed_synergy(
     }
 }
 
-fn get_aerial_power(ship: &MasterShip, gears: &GearArray) -> i16 {
-    let ship_input = ShipInput::new(ship);
+/// 空いているスロットに装備を1つずつ割り当てられるか
+fn can_place(gears: &[Gear], slots: &[&str], equippability: &ShipEquippability) -> bool {
+    let Some((gear, rest)) = gears.split_first() else {
+        return true;
+    };
 
+    slots.iter().enumerate().any(|(i, key)| {
+        if !equippability.can_equip(key, gear) {
+            return false;
+        }
+
+        let mut remaining = slots.to_vec();
+        remaining.remove(i);
+        can_place(rest, &remaining, equippability)
+    })
+}
+
+/// 目標の速力に到達するために追加する最少の缶・タービンの組み合わせ
+///
+/// 装備済みの缶・タービンはそのまま用い、追加する装備は空いているスロットのうち装備可能な位置に割り当てる
+pub fn find_speed_gear_set(
+    ship: &MasterShip,
+    equippability: &ShipEquippability,
+    gears: &GearArray,
+    target: Speed,
+) -> Option<SpeedGearSet> {
+    let turbine_id = gear_id!("改良型艦本式タービン");
+    let enhanced_boiler_id = gear_id!("強化型艦本式缶");
+    let new_model_boiler_id = gear_id!("新型高温高圧缶");
+
+    let has_turbine = gears.has(turbine_id);
+    let current_enhanced_boilers = gears.count(enhanced_boiler_id);
+    let current_new_model_boilers = gears.count(new_model_boiler_id);
+
+    let free_slots = (0..GearArray::CAPACITY)
+        .filter(|&index| gears.get(index).is_none())
+        .filter_map(into_gear_key)
+        .collect::<Vec<_>>();
+    let max_boilers = free_slots.len();
+
+    let turbine_cases: &[bool] = if has_turbine {
+        &[false]
+    } else {
+        &[false, true]
+    };
+
+    let create_gear = |gear_id| Gear {
+        gear_id,
+        types: [0, 0, GearType::Engine as u8, 0, 0].into(),
+        gear_type: GearType::Engine,
+        special_type: GearType::Engine as u8,
+        ..Default::default()
+    };
+
+    (0..=max_boilers)
+        .flat_map(|new_model_boilers| {
+            (0..=max_boilers - new_model_boilers).flat_map(move |enhanced_boilers| {
+                turbine_cases.iter().map(move |&turbine| SpeedGearSet {
+                    turbine,
+                    enhanced_boilers,
+                    new_model_boilers,
+                })
+            })
+        })
+        .filter(|set| {
+            let added = std::iter::repeat_n(new_model_boiler_id, set.new_model_boilers)
+                .chain(std::iter::repeat_n(
+                    enhanced_boiler_id,
+                    set.enhanced_boilers,
+                ))
+                .chain(set.turbine.then_some(turbine_id))
+                .map(create_gear)
+                .collect::<Vec<_>>();
+
+            if !can_place(&added, &free_slots, equippability) {
+                return false;
+            }
+
+            let new_model_boilers = std::iter::repeat_n(
+                new_model_boiler_id,
+                current_new_model_boilers + set.new_model_boilers,
+            );
+            let enhanced_boilers = std::iter::repeat_n(
+                enhanced_boiler_id,
+                current_enhanced_boilers + set.enhanced_boilers,
+            );
+            let turbine = (has_turbine || set.turbine).then_some(turbine_id);
+
+            let gears = new_model_boilers
+                .chain(enhanced_boilers)
+                .chain(turbine)
+                .map(|gear_id| Some(create_gear(gear_id)))
+                .collect::<GearArray>();
+
+            let speed = ship.speed + get_speed_bonus(ship, &gears);
+            Speed::from_value(speed) >= target
+        })
+        .min_by_key(|set| (set.gear_count(), set.new_model_boilers, set.turbine))
+}
+
+fn get_aerial_power(ship: &MasterShip, gears: &GearArray, rules: &MasterEBonuses) -> i16 {
     let plane_bonuses = gears
         .values()
         .filter(|gear| gear.has_proficiency())
-        .map(|gear| {
-            let gears_input = GearVecInput(vec![GearInput::new(gear)]);
-            create_equipment_bonuses(ship_input.clone(), gears_input)
-        })
+        .map(|gear| eval_ebonuses(ship, &[gear], rules))
         .collect::<Vec<_>>();
 
     let min_torpedo = plane_bonuses
@@ -240,54 +401,35 @@ fn get_aerial_power(ship: &MasterShip, gears: &GearArray) -> i16 {
         .min()
         .unwrap_or_default();
 
-    let other = gears
+    let others = gears
         .values()
         .filter(|gear| !gear.has_proficiency())
-        .map(GearInput::new)
         .collect::<Vec<_>>();
-
-    let other_bonus = create_equipment_bonuses(ship_input, GearVecInput(other));
+    let other_bonus = eval_ebonuses(ship, &others, rules);
 
     min_torpedo + min_bombing + other_bonus.torpedo + other_bonus.bombing
 }
 
 impl EBonuses {
-    #[cfg(target_arch = "wasm32")]
-    pub fn new(ship: &MasterShip, gears: &GearArray) -> Self {
-        let ship_input = ShipInput::new(ship);
-        let gears_input = GearVecInput(gears.values().map(GearInput::new).collect::<Vec<_>>());
-
-        let mut ebonuses = create_equipment_bonuses(ship_input, gears_input);
-        ebonuses.aerial_power = get_aerial_power(ship, gears);
+    pub fn new(ship: &MasterShip, gears: &GearArray, rules: &MasterEBonuses) -> Self {
+        let mut ebonuses = eval_ebonuses(ship, &gears.values().collect::<Vec<_>>(), rules);
+        ebonuses.aerial_power = get_aerial_power(ship, gears, rules);
         ebonuses.speed = get_speed_bonus(ship, gears);
 
         ebonuses
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn new(ship: &MasterShip, gears: &GearArray) -> Self {
-        let ship_input = ShipInput::new(ship);
-        let gears_input = GearVecInput(gears.values().map(GearInput::new).collect::<Vec<_>>());
-
-        let ship_json = serde_json::to_string(&ship_input).unwrap();
-        let gears_json = serde_json::to_string(&gears_input).unwrap();
-
-        let code = format!(
-            r#"
-                const {{ createEquipmentBonuses }} = require("equipment-bonus");
-                const result = createEquipmentBonuses({ship_json}, {gears_json});
-                console.log(JSON.stringify(result));
-            "#
-        );
-
-        let stdout = std::process::Command::new("node")
-            .arg("-e")
-            .arg(code)
-            .output()
-            .unwrap()
-            .stdout;
-
-        serde_json::from_slice(&stdout).unwrap()
+    pub(crate) fn add_times(&mut self, other: &Self, times: i16) {
+        self.firepower += other.firepower * times;
+        self.torpedo += other.torpedo * times;
+        self.anti_air += other.anti_air * times;
+        self.armor += other.armor * times;
+        self.evasion += other.evasion * times;
+        self.asw += other.asw * times;
+        self.los += other.los * times;
+        self.bombing += other.bombing * times;
+        self.accuracy += other.accuracy * times;
+        self.range += other.range * times as i8;
     }
 }
 
@@ -361,4 +503,111 @@ mod test {
         test_table!(3, 1, 最速, 最速, 最速, 高速P, 最速, 高速P, 高速);
         test_table!(4, 0, 最速, 最速, 最速, 高速P, 最速, 高速P, 高速);
     }
+
+    #[test]
+    fn test_find_speed_gear_set() {
+        let ship = MasterShip {
+            speed: 5,
+            speed_group: SpeedGroup::A,
+            slotnum: 4,
+            ..Default::default()
+        };
+        let equippability = |slotnum| ShipEquippability {
+            types: vec![GearType::Engine as u8],
+            slotnum,
+            ..Default::default()
+        };
+        let find = |ship: &MasterShip, gears: &GearArray, target| {
+            find_speed_gear_set(ship, &equippability(ship.slotnum), gears, target)
+        };
+
+        assert_eq!(
+            find(&ship, &GearArray::default(), Speed::Slow),
+            Some(SpeedGearSet::default())
+        );
+        assert_eq!(
+            find(&ship, &GearArray::default(), Speed::Fast),
+            Some(SpeedGearSet {
+                turbine: true,
+                enhanced_boilers: 1,
+                new_model_boilers: 0,
+            })
+        );
+        assert_eq!(
+            find(&ship, &GearArray::default(), Speed::FastPlus),
+            Some(SpeedGearSet {
+                turbine: true,
+                enhanced_boilers: 1,
+                new_model_boilers: 1,
+            })
+        );
+        assert_eq!(
+            find(&ship, &GearArray::default(), Speed::Fastest),
+            Some(SpeedGearSet {
+                turbine: true,
+                enhanced_boilers: 2,
+                new_model_boilers: 1,
+            })
+        );
+
+        let ship = MasterShip { slotnum: 1, ..ship };
+
+        assert_eq!(find(&ship, &GearArray::default(), Speed::FastPlus), None);
+
+        let gear = |gear_id| {
+            Some(Gear {
+                gear_id,
+                ..Default::default()
+            })
+        };
+        let gears = [
+            gear(gear_id!("強化型艦本式缶")),
+            gear(gear_id!("改良型艦本式タービン")),
+        ]
+        .into_iter()
+        .collect::<GearArray>();
+
+        assert_eq!(
+            find(&ship, &gears, Speed::Fast),
+            Some(SpeedGearSet::default())
+        );
+        assert_eq!(find(&ship, &gears, Speed::FastPlus), None);
+
+        let ship = MasterShip { slotnum: 4, ..ship };
+
+        assert_eq!(
+            find(&ship, &gears, Speed::FastPlus),
+            Some(SpeedGearSet {
+                turbine: false,
+                enhanced_boilers: 0,
+                new_model_boilers: 1,
+            })
+        );
+
+        // 装備済みのスロットには追加しない
+        let occupied = (0..3)
+            .map(|_| gear(gear_id!("12.7cm連装砲")))
+            .collect::<GearArray>();
+
+        assert_eq!(
+            find(&ship, &occupied, Speed::Fast),
+            Some(SpeedGearSet {
+                turbine: true,
+                enhanced_boilers: 1,
+                new_model_boilers: 0,
+            })
+        );
+        assert_eq!(find(&ship, &occupied, Speed::FastPlus), None);
+
+        // 缶・タービンを装備できない艦
+        let equippability = ShipEquippability {
+            slotnum: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            find_speed_gear_set(&ship, &equippability, &GearArray::default(), Speed::Fast),
+            None
+        );
+    }
 }
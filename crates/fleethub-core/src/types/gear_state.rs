@@ -43,6 +43,22 @@ impl GearVecState {
         } = self;
         [g1, g2, g3, g4, g5, gx].into_iter().map(|g| g.as_ref())
     }
+
+    pub fn slot_mut(&mut self, key: &str) -> Option<&mut Option<GearState>> {
+        Some(match key {
+            "g1" => &mut self.g1,
+            "g2" => &mut self.g2,
+            "g3" => &mut self.g3,
+            "g4" => &mut self.g4,
+            "g5" => &mut self.g5,
+            "gx" => &mut self.gx,
+            _ => return None,
+        })
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut GearState> {
+        self.slot_mut(key)?.as_mut()
+    }
 }
 
 impl IntoIterator for GearVecState {
@@ -41,6 +41,7 @@ impl FromStr for NodeId {
 #[serde_as]
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Tsify)]
 #[serde(default)]
+#[tsify(from_wasm_abi)]
 pub struct NodeState {
     #[serde_as(as = "DefaultOnError")]
     pub map: i16,
@@ -66,3 +66,13 @@ impl FleetKey {
         num_traits::FromPrimitive::from_i8((self as i8 - 1).rem_euclid(4)).unwrap()
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum AirSquadronKey {
+    #[default]
+    A1,
+    A2,
+    A3,
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
+use super::Role;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
 pub enum SpeedGroup {
     A,
@@ -9,3 +11,79 @@ pub enum SpeedGroup {
     B2,
     C,
 }
+
+/// 速力区分
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Tsify,
+)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum Speed {
+    /// 陸上
+    Land,
+    /// 低速
+    #[default]
+    Slow,
+    /// 高速
+    Fast,
+    /// 高速+
+    FastPlus,
+    /// 最速
+    Fastest,
+}
+
+impl Speed {
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            0 => Self::Land,
+            1..=5 => Self::Slow,
+            6..=10 => Self::Fast,
+            11..=15 => Self::FastPlus,
+            _ => Self::Fastest,
+        }
+    }
+
+    pub fn value(self) -> u8 {
+        match self {
+            Self::Land => 0,
+            Self::Slow => 5,
+            Self::Fast => 10,
+            Self::FastPlus => 15,
+            Self::Fastest => 20,
+        }
+    }
+}
+
+/// 速力の上昇に必要な装備
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub struct SpeedGearSet {
+    /// 改良型艦本式タービン
+    pub turbine: bool,
+    /// 強化型艦本式缶
+    pub enhanced_boilers: usize,
+    /// 新型高温高圧缶
+    pub new_model_boilers: usize,
+}
+
+impl SpeedGearSet {
+    pub fn gear_count(&self) -> usize {
+        self.turbine as usize + self.enhanced_boilers + self.new_model_boilers
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct ShipSpeedSuggestion {
+    pub role: Role,
+    pub index: usize,
+    pub ship_id: u16,
+    pub speed: Speed,
+    /// 目標の速力に到達できない場合はNone
+    pub gears: Option<SpeedGearSet>,
+}
+
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct SpeedReport {
+    pub speed: Speed,
+    pub target: Speed,
+    pub suggestions: Vec<ShipSpeedSuggestion>,
+}
@@ -0,0 +1,76 @@
+use fleethub_core::{
+    analyzer::{AirStateAnalysis, AirStateAnalyzer, AirStateAnalyzerConfig},
+    types::{AirSquadronKey, AirState},
+};
+
+use crate::*;
+
+#[test]
+fn test_air_state_analyzer() {
+    let org = org_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "赤城", g1 = "零式艦戦52型", g2 = "零式艦戦52型" }
+        a1 = { g1 = "一式戦 隼II型", g2 = "一式戦 隼II型", g3 = "一式戦 隼II型", g4 = "一式戦 隼II型" }
+    }));
+    let player_comp = org.create_comp();
+    let wo_class = FH_CORE
+        .master_data()
+        .ships
+        .iter()
+        .find(|ship| ship.name == "空母ヲ級")
+        .unwrap()
+        .ship_id;
+    let enemy_comp = FH_CORE.create_comp_by_map_enemy(vec![wo_class, wo_class], None);
+    let battle_defs = battle_definitions();
+
+    let analyze = |waves: Vec<AirSquadronKey>| {
+        let config = AirStateAnalyzerConfig {
+            waves,
+            times: 1000,
+            ..Default::default()
+        };
+
+        AirStateAnalyzer {
+            battle_defs: &battle_defs,
+            config: &config,
+            player_org: &org,
+            player_comp: &player_comp,
+            enemy_comp: &enemy_comp,
+        }
+        .analyze(&mut rng(0))
+        .unwrap()
+    };
+
+    let paper = analyze(vec![]);
+    let rate = |analysis: &AirStateAnalysis, air_state: AirState| {
+        analysis
+            .air_state_chance
+            .iter()
+            .find(|chance| chance.air_state == air_state)
+            .unwrap()
+            .rate
+    };
+
+    assert_eq!(rate(&paper, paper.paper_air_state), 1.0);
+    assert_eq!(
+        paper.expected_enemy_fighter_power,
+        paper.enemy_fighter_power as f64
+    );
+
+    let analysis = analyze(vec![AirSquadronKey::A1, AirSquadronKey::A1]);
+    let total = analysis
+        .air_state_chance
+        .iter()
+        .map(|chance| chance.rate)
+        .sum::<f64>();
+
+    assert!((total - 1.0).abs() < 1e-9);
+    assert_eq!(analysis.land_base_waves.len(), 2);
+    assert!(analysis.expected_enemy_fighter_power < analysis.enemy_fighter_power as f64);
+    assert!(analysis.enemy_slots.iter().all(|report| {
+        report
+            .expected_slots
+            .iter()
+            .zip(report.max_slots.iter())
+            .all(|(expected, max)| *expected <= max.unwrap_or_default() as f64)
+    }));
+}
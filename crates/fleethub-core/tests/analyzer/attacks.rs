@@ -53,6 +53,8 @@ impl AttackTestCase {
             config: self.attack_analyzer_config(),
             attacker: &self.attacker.ship,
             target: &self.target.ship,
+            attacker_comp: None,
+            target_comp: None,
         };
 
         analyzer.analyze()
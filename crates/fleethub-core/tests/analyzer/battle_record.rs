@@ -0,0 +1,178 @@
+use fleethub_core::{
+    analyzer::{BattleRecordValidator, HitValidationStatus, NodeAttackAnalyzerConfig},
+    import::{BattleRecord, BattleRecordPhase},
+    types::{OrgType, Side},
+};
+
+use crate::*;
+
+#[test]
+fn test_battle_record_validator() {
+    let player = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "皐月改二", level = 99, g1 = "12.7cm連装砲" }
+    }));
+    let enemy = FH_CORE.create_comp_by_map_enemy(vec![1501], None);
+
+    let record = BattleRecord::from_api_json(
+        r#"{
+            "api_data": {
+                "api_ship_ke": [1501],
+                "api_formation": [1, 1, 1],
+                "api_f_nowhps": [31],
+                "api_f_maxhps": [31],
+                "api_e_nowhps": [20],
+                "api_e_maxhps": [20],
+                "api_hougeki1": {
+                    "api_at_eflag": [0, 0, 1, 0],
+                    "api_at_list": [0, 0, 0, 0],
+                    "api_at_type": [0, 0, 0, 2],
+                    "api_df_list": [[0], [0], [0], [0, 0]],
+                    "api_cl_list": [[1], [1], [0], [1, 1]],
+                    "api_damage": [[999], [3.1], [0], [0, 0]]
+                }
+            }
+        }"#,
+        OrgType::Single,
+    )
+    .unwrap();
+
+    assert_eq!(record.attacks.len(), 4);
+    assert_eq!(record.enemy_hp_after, vec![0]);
+
+    let config = NodeAttackAnalyzerConfig::default();
+    let validator = BattleRecordValidator {
+        battle_defs: &battle_definitions(),
+        config: &config,
+        player: &player,
+        enemy: &enemy,
+    };
+
+    let result = validator.validate(&record);
+
+    assert_eq!(result.hits.len(), 5);
+    assert_eq!(result.hits[0].status, HitValidationStatus::Impossible);
+    assert_eq!(result.hits[1].status, HitValidationStatus::Skipped);
+    assert_eq!(result.hits[2].status, HitValidationStatus::Valid);
+
+    // 主砲1本では連撃が発動しないため、記録された連撃と比較する攻撃がない
+    assert_eq!(result.hits[3].status, HitValidationStatus::Skipped);
+    assert_eq!(result.hits[4].status, HitValidationStatus::Skipped);
+}
+
+#[test]
+fn test_combined_fleet_battle_record() {
+    let player = comp_from_toml(toml::Value::from(toml::toml! {
+        org_type = "CarrierTaskForce"
+        f1.s1 = { ship_id = "赤城改", level = 99 }
+        f2.s1 = { ship_id = "皐月改二", level = 99, g1 = "12.7cm連装砲" }
+    }));
+    let enemy = FH_CORE.create_comp_by_map_enemy(vec![1501], None);
+
+    // 空母機動部隊の第1砲撃戦は随伴艦隊、その後に雷撃戦と主力艦隊の砲撃戦
+    let json = r#"{
+        "api_data": {
+            "api_ship_ke": [1501],
+            "api_formation": [11, 1, 1],
+            "api_f_nowhps": [69],
+            "api_f_maxhps": [69],
+            "api_f_nowhps_combined": [31],
+            "api_f_maxhps_combined": [31],
+            "api_e_nowhps": [20],
+            "api_e_maxhps": [20],
+            "api_kouku": {
+                "api_stage3": { "api_fdam": [0], "api_edam": [2] },
+                "api_stage3_combined": { "api_fdam": [3] }
+            },
+            "api_hougeki1": {
+                "api_at_eflag": [0, 1],
+                "api_at_list": [6, 0],
+                "api_at_type": [0, 0],
+                "api_df_list": [[0], [6]],
+                "api_cl_list": [[1], [1]],
+                "api_damage": [[4], [5]]
+            },
+            "api_raigeki": { "api_fdam": [0, 0, 0, 0, 0, 0, 0], "api_edam": [6] },
+            "api_hougeki2": {
+                "api_at_eflag": [1],
+                "api_at_list": [0],
+                "api_at_type": [0],
+                "api_df_list": [[0]],
+                "api_cl_list": [[1]],
+                "api_damage": [[1]]
+            },
+            "api_hougeki3": {
+                "api_at_eflag": [0],
+                "api_at_list": [0],
+                "api_at_type": [0],
+                "api_df_list": [[0]],
+                "api_cl_list": [[0]],
+                "api_damage": [[0]]
+            }
+        }
+    }"#;
+
+    let record = BattleRecord::from_api_json(json, player.org_type).unwrap();
+
+    assert_eq!(record.player_hp_before, vec![69, 0, 0, 0, 0, 0, 31]);
+    assert_eq!(record.attacks.len(), 4);
+    assert_eq!(record.attacks[0].attacker_index, 6);
+    assert_eq!(record.attacks[0].hits[0].hp_before, 18);
+    assert_eq!(record.attacks[1].hits[0].hp_before, 28);
+    assert_eq!(record.attacks[3].hits[0].hp_before, 8);
+    assert_eq!(record.player_hp_after, vec![68, 0, 0, 0, 0, 0, 23]);
+    assert_eq!(record.enemy_hp_after, vec![8]);
+
+    let surface = BattleRecord::from_api_json(json, OrgType::SurfaceTaskForce).unwrap();
+    assert_eq!(surface.attacks[3].hits[0].hp_before, 14);
+    assert_eq!(surface.enemy_hp_after, vec![8]);
+
+    let config = NodeAttackAnalyzerConfig::default();
+    let validator = BattleRecordValidator {
+        battle_defs: &battle_definitions(),
+        config: &config,
+        player: &player,
+        enemy: &enemy,
+    };
+
+    let result = validator.validate(&record);
+
+    assert_eq!(result.hits.len(), 4);
+    assert_eq!(result.hits[0].attacker_index, 6);
+    assert_eq!(result.hits[1].target_index, 6);
+}
+
+#[test]
+fn test_land_base_battle_record() {
+    // 基地航空隊の噴式強襲、各波の攻撃の後に航空戦と砲撃戦
+    let json = r#"{
+        "api_ship_ke": [1501, 1502],
+        "api_f_nowhps": [31],
+        "api_e_nowhps": [20, 22],
+        "api_air_base_injection": { "api_stage3": { "api_edam": [3, 0] } },
+        "api_air_base_attack": [
+            { "api_stage3": { "api_edam": [0, 10] } },
+            { "api_stage3": { "api_edam": [5, 12] } }
+        ],
+        "api_kouku": { "api_stage3": { "api_fdam": [2], "api_edam": [1, 0] } },
+        "api_hougeki1": {
+            "api_at_eflag": [0],
+            "api_at_list": [0],
+            "api_at_type": [0],
+            "api_df_list": [[0]],
+            "api_cl_list": [[1]],
+            "api_damage": [[4]]
+        }
+    }"#;
+
+    let record = BattleRecord::from_api_json(json, OrgType::Single).unwrap();
+
+    assert_eq!(record.attacks.len(), 1);
+
+    let attack = &record.attacks[0];
+    assert_eq!(attack.phase, BattleRecordPhase::DayShelling);
+    assert_eq!(attack.attacker_side, Side::Player);
+    assert_eq!(attack.hits[0].hp_before, 11);
+
+    assert_eq!(record.player_hp_after, vec![29]);
+    assert_eq!(record.enemy_hp_after, vec![7, 0]);
+}
@@ -0,0 +1,37 @@
+use crate::*;
+
+#[test]
+fn test_improvement_analyzer() {
+    let ship = ship! {
+        ship_id = "夕立改二"
+        g1 = "12.7cm連装砲"
+        g2 = "22号対水上電探"
+    };
+
+    let gun = FH_CORE.analyze_improvement(&ship, "g1").unwrap();
+
+    assert!(gun.improvable);
+    assert_eq!(gun.current_stars, 0);
+    assert_eq!(gun.levels.len(), 11);
+    assert!(gun.levels[0].delta.is_none());
+
+    for level in &gun.levels[1..] {
+        let delta = level.delta.as_ref().unwrap();
+        assert!(delta.shelling_power.unwrap() > 0.0);
+        assert_eq!(delta.elos, Some(0.0));
+    }
+
+    let radar = FH_CORE.analyze_improvement(&ship, "g2").unwrap();
+
+    assert!(radar.improvable);
+    assert_eq!(radar.levels.len(), 11);
+
+    for level in &radar.levels[1..] {
+        let delta = level.delta.as_ref().unwrap();
+        assert_eq!(delta.shelling_power, Some(0.0));
+        assert!(delta.shelling_accuracy.unwrap() > 0.0);
+        assert!(delta.elos.unwrap() > 0.0);
+    }
+
+    assert!(FH_CORE.analyze_improvement(&ship, "g3").is_none());
+}
@@ -1 +1,6 @@
+mod air_state;
 mod attacks;
+mod battle_record;
+mod improvement;
+mod night_conditions;
+mod transport_point;
@@ -0,0 +1,51 @@
+use fleethub_core::{
+    analyzer::{CompAnalyzerConfig, NightConditionsAnalyzer},
+    ship::Ship,
+};
+
+use crate::*;
+
+#[test]
+fn test_night_conditions_analyzer() {
+    let comp = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "夕立改二", g1 = "照明弾" }
+        f1.s2 = { ship_id = "綾波改二", g1 = "探照灯" }
+    }));
+    let battle_defs = battle_definitions();
+
+    let analysis = NightConditionsAnalyzer {
+        battle_defs: &battle_defs,
+        comp: &comp,
+        dummy: &Ship::default(),
+        config: &CompAnalyzerConfig::default(),
+    }
+    .analyze();
+
+    let total = analysis
+        .scenarios
+        .iter()
+        .map(|scenario| scenario.rate)
+        .sum::<f64>();
+
+    assert!((total - 1.0).abs() < 1e-9);
+    assert_eq!(analysis.starshell_index, Some(0));
+    assert_eq!(analysis.starshell_chance, 0.7);
+    assert_eq!(analysis.searchlight_index, Some(1));
+    assert_eq!(analysis.searchlight_chance, 1.0);
+    assert!(!analysis.activates_large_searchlight);
+    assert_eq!(analysis.ships.len(), 2);
+
+    let analysis = NightConditionsAnalyzer {
+        battle_defs: &battle_defs,
+        comp: &comp,
+        dummy: &Ship::default(),
+        config: &CompAnalyzerConfig {
+            starshell_activation_rate: 0.5,
+            ..Default::default()
+        },
+    }
+    .analyze();
+
+    assert_eq!(analysis.starshell_index, Some(0));
+    assert_eq!(analysis.starshell_chance, 0.5);
+}
@@ -0,0 +1,26 @@
+use fleethub_core::analyzer::{TransportPointPlannerConfig, TransportRank};
+
+use crate::*;
+
+#[test]
+fn test_transport_point_planner() {
+    let comp = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "皐月改二", g1 = "ドラム缶(輸送用)" }
+        f1.s2 = { ship_id = "文月改二" }
+    }));
+
+    let config = TransportPointPlannerConfig {
+        gauge: 100,
+        rank: TransportRank::A,
+        ..Default::default()
+    };
+    let plan = FH_CORE.plan_transport_point(&comp, config);
+
+    assert_eq!(plan.transport_point, 15);
+    assert_eq!(plan.rank_transport_point, 10);
+    assert_eq!(plan.sorties, Some(10));
+    assert_eq!(plan.target, Some(18));
+    assert!(plan.planned_transport_point >= 18);
+    assert!(plan.planned_sorties.unwrap() <= 9);
+    assert!(plan.swaps.iter().all(|swap| swap.gain > 0));
+}
@@ -20,7 +20,10 @@ fn test_case(ship_name: &str, gears: Vec<&str>, expected: Vec<u8>) {
         gx = gx
     };
 
-    assert_eq!(ship.get_possible_anti_air_cutin_ids(), expected);
+    assert_eq!(
+        ship.get_possible_anti_air_cutin_ids(&common::battle_definitions()),
+        expected
+    );
 }
 
 macro_rules! table {
@@ -1,4 +1,4 @@
-use fleethub_core::{comp::Comp, fleet::Fleet, ship::Ship};
+use fleethub_core::{comp::Comp, fleet::Fleet, org::Org, ship::Ship};
 
 use super::FH_CORE;
 
@@ -83,11 +83,14 @@ pub fn fleet_from_toml<T: Into<toml::Value>>(toml: T) -> Fleet {
     FH_CORE.create_fleet(Some(state))
 }
 
-pub fn comp_from_toml(mut toml: toml::Value) -> Comp {
+pub fn org_from_toml(mut toml: toml::Value) -> Org {
     format_toml(&mut toml);
     let state = toml.try_into().unwrap();
-    let org = FH_CORE.create_org(state).unwrap();
-    org.create_comp()
+    FH_CORE.create_org(state).unwrap()
+}
+
+pub fn comp_from_toml(toml: toml::Value) -> Comp {
+    org_from_toml(toml).create_comp()
 }
 
 #[macro_export]
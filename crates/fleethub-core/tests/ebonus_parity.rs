@@ -0,0 +1,90 @@
+use fleethub_core::{
+    gear::Gear,
+    master_data::MasterShip,
+    types::{equipment_bonus_js, EBonuses, GearState},
+};
+
+mod common;
+
+use common::*;
+
+fn key(ebonuses: &EBonuses) -> [i16; 10] {
+    [
+        ebonuses.firepower,
+        ebonuses.torpedo,
+        ebonuses.anti_air,
+        ebonuses.armor,
+        ebonuses.evasion,
+        ebonuses.asw,
+        ebonuses.los,
+        ebonuses.bombing,
+        ebonuses.accuracy,
+        ebonuses.range as i16,
+    ]
+}
+
+/// 各ルールの対象となる艦と装備の組み合わせで、組み込みの定義と `equipment-bonus` を比較する
+///
+/// node と `equipment-bonus` が利用できない環境では比較しない
+#[test]
+fn test_ebonus_parity() {
+    let master_data = FH_CORE.master_data();
+    let rules = &master_data.ebonuses;
+
+    let ships = master_data
+        .ships
+        .iter()
+        .filter(|ship| !ship.is_abyssal())
+        .collect::<Vec<_>>();
+    let gears = master_data
+        .gears
+        .iter()
+        .filter_map(|gear| {
+            FH_CORE.create_gear(Some(GearState {
+                gear_id: gear.gear_id,
+                ..Default::default()
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    let corpus = rules
+        .rules
+        .iter()
+        .filter(|rule| rule.requires.is_empty() && !rule.gear.is_empty())
+        .filter_map(|rule| {
+            let ship = ships
+                .iter()
+                .find(|ship| rule.ship.is_empty() || rule.ship.matches(&mut ship.ns()))?;
+            let gear = gears
+                .iter()
+                .find(|gear| rule.gear.matches(&mut gear.ns()))?;
+
+            Some((rule.name.as_str(), *ship, gear))
+        })
+        .collect::<Vec<(&str, &MasterShip, &Gear)>>();
+
+    assert!(!corpus.is_empty());
+
+    let mut mismatches = vec![];
+
+    for (name, ship, gear) in corpus {
+        let Some(expected) = equipment_bonus_js(ship, &[gear]) else {
+            eprintln!("equipment-bonus is unavailable");
+            return;
+        };
+
+        let actual = rules.eval(ship, [gear]);
+
+        if key(&actual) != key(&expected) {
+            mismatches.push(format!(
+                "{name}: {} {} native {:?} js {:?}",
+                ship.name,
+                gear.name,
+                key(&actual),
+                key(&expected)
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
@@ -0,0 +1,88 @@
+use fleethub_core::{
+    expedition::{ExpeditionCondition, ExpeditionDef, ExpeditionShipTypeCount},
+    types::ShipType,
+};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn test_expedition_check() {
+    let def = ExpeditionDef {
+        id: 21,
+        duration: 140,
+        resources: [320, 270, 0, 0],
+        ship_count: 5,
+        flagship_level: 15,
+        total_level: 30,
+        compositions: vec![vec![
+            ExpeditionShipTypeCount {
+                types: vec![ShipType::CL],
+                count: 1,
+            },
+            ExpeditionShipTypeCount {
+                types: vec![ShipType::DD],
+                count: 4,
+            },
+        ]],
+        drum_ship_count: 3,
+        ..Default::default()
+    };
+
+    let fleet = fleet_from_toml(toml::toml! {
+        s1 = { ship_id = "五十鈴改二", level = 50, morale = 53 }
+        s2 = { ship_id = "皐月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s3 = { ship_id = "文月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s4 = { ship_id = "睦月改二", morale = 53, g1 = "大発動艇" }
+        s5 = { ship_id = "如月改二", morale = 53 }
+    });
+
+    let report = def.check(&fleet);
+
+    assert!(!report.qualifies);
+    assert_eq!(report.shortfalls.len(), 1);
+    assert_eq!(
+        report.shortfalls[0].condition,
+        ExpeditionCondition::DrumShipCount
+    );
+    assert_eq!(report.resources_per_hour, [0.0; 4]);
+
+    let fleet = fleet_from_toml(toml::toml! {
+        s1 = { ship_id = "五十鈴改二", level = 50, morale = 53 }
+        s2 = { ship_id = "皐月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s3 = { ship_id = "文月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s4 = { ship_id = "睦月改二", morale = 53, g1 = "ドラム缶(輸送用)", g2 = "大発動艇" }
+        s5 = { ship_id = "如月改二", morale = 53 }
+    });
+
+    let report = def.check(&fleet);
+
+    assert!(report.qualifies);
+    assert_eq!(report.sparkle_count, 5);
+    assert_eq!(report.great_success_rate, 0.95);
+    assert!(report.bonus > 0.0);
+    assert!(report.resources_per_hour[0] > 320.0 / (140.0 / 60.0));
+}
+
+#[test]
+fn test_builtin_expedition() {
+    let fleet = fleet_from_toml(toml::toml! {
+        s1 = { ship_id = "五十鈴改二", level = 50, morale = 53 }
+        s2 = { ship_id = "皐月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s3 = { ship_id = "文月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s4 = { ship_id = "睦月改二", morale = 53, g1 = "ドラム缶(輸送用)" }
+        s5 = { ship_id = "如月改二", morale = 53 }
+    });
+
+    let report = FH_CORE.check_expedition(&fleet, 21).unwrap();
+    assert!(report.qualifies);
+    assert_eq!(report.expedition_id, 21);
+
+    let report = FH_CORE.check_expedition(&fleet, 38).unwrap();
+    assert!(!report.qualifies);
+    assert!(report
+        .shortfalls
+        .iter()
+        .any(|shortfall| shortfall.condition == ExpeditionCondition::FlagshipLevel));
+}
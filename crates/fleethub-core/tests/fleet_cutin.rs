@@ -19,10 +19,15 @@ fn assert_fleet_cutin(
         attacks: expected.into_iter().collect(),
     };
 
-    let result =
-        get_possible_fleet_cutin_effect_vec(&fleet_from_toml(value), formation, engagement, time)
-            .into_iter()
-            .any(|effect| effect == expected_effect);
+    let result = get_possible_fleet_cutin_effect_vec(
+        &common::battle_definitions(),
+        &fleet_from_toml(value),
+        formation,
+        engagement,
+        time,
+    )
+    .into_iter()
+    .any(|effect| effect == expected_effect);
 
     assert!(result)
 }
@@ -41,7 +46,7 @@ fn test_nelson_touch() {
         Formation::DOUBLE_LINE,
         Engagement::Parallel,
         Time::Day,
-        FleetCutin::NelsonTouch,
+        FleetCutin::NELSON_TOUCH,
         [(0, 2.0), (2, 2.0), (4, 2.0)],
     );
 
@@ -58,7 +63,7 @@ fn test_nelson_touch() {
         Formation::DOUBLE_LINE,
         Engagement::RedT,
         Time::Day,
-        FleetCutin::NelsonTouch,
+        FleetCutin::NELSON_TOUCH,
         [(0, 2.0 * 1.25), (2, 2.0 * 1.25), (4, 2.0 * 1.25)],
     );
 }
@@ -77,7 +82,7 @@ fn test_nagato_class_cutin() {
         Formation::ECHELON,
         Engagement::Parallel,
         Time::Day,
-        FleetCutin::NagatoClassCutin,
+        FleetCutin::NAGATO_CLASS_CUTIN,
         [(0, 1.4 * 1.2), (0, 1.4 * 1.2), (1, 1.4 * 1.2)],
     );
 
@@ -94,7 +99,7 @@ fn test_nagato_class_cutin() {
         Formation::ECHELON,
         Engagement::Parallel,
         Time::Day,
-        FleetCutin::NagatoClassCutin,
+        FleetCutin::NAGATO_CLASS_CUTIN,
         [(0, 1.4 * 1.2 * 1.15), (0, 1.4 * 1.2 * 1.15), (1, 1.4 * 1.2)],
     );
 }
@@ -113,7 +118,7 @@ fn test_colorado_class_cutin() {
         Formation::ECHELON,
         Engagement::Parallel,
         Time::Day,
-        FleetCutin::ColoradoClassCutin,
+        FleetCutin::COLORADO_CLASS_CUTIN,
         [(0, 1.5), (1, 1.3), (2, 1.3)],
     );
 }
@@ -132,7 +137,7 @@ fn test_kongou_cutin() {
         Formation::LINE_AHEAD,
         Engagement::Parallel,
         Time::Night,
-        FleetCutin::KongouClassCutin,
+        FleetCutin::KONGOU_CLASS_CUTIN,
         [(0, 2.2), (1, 2.2)],
     );
 }
@@ -151,7 +156,7 @@ fn test_yamato_2ship_cutin() {
         Formation::ECHELON,
         Engagement::Parallel,
         Time::Day,
-        FleetCutin::Yamato2ShipCutin,
+        FleetCutin::YAMATO_2_SHIP_CUTIN,
         [(0, 1.4 * 1.1), (0, 1.4 * 1.1), (1, 1.55 * 1.2)],
     );
 }
@@ -181,7 +186,7 @@ fn test_yamato_3ship_cutin() {
                 Formation::ECHELON,
                 Engagement::Parallel,
                 Time::Day,
-                FleetCutin::Yamato3ShipCutin,
+                FleetCutin::YAMATO_3_SHIP_CUTIN,
                 $expected
             )
         };
@@ -265,3 +270,35 @@ fn test_yamato_3ship_cutin() {
         [(0, 1.5 * 1.15 * 1.1), (1, 1.5), (2, 1.65)]
     );
 }
+
+#[test]
+fn test_data_defined_fleet_cutin() {
+    let mut battle_defs = common::battle_definitions();
+    battle_defs.fleet_cutin.push(
+        serde_json::from_value(serde_json::json!({
+            "tag": "QueenElizabethTouch",
+            "formations": ["LineAhead"],
+            "participants": [{ "position": 0, "ship": "is_battleship" }],
+            "attacks": [{ "position": 0, "power_mods": [{ "a": 1.5 }] }],
+        }))
+        .unwrap(),
+    );
+
+    let effects = get_possible_fleet_cutin_effect_vec(
+        &battle_defs,
+        &fleet_from_toml(toml::toml! {
+            s1.ship_id = "Warspite"
+        }),
+        Formation::LINE_AHEAD,
+        Engagement::Parallel,
+        Time::Day,
+    );
+
+    assert_eq!(
+        effects,
+        vec![FleetCutinEffect {
+            cutin: FleetCutin::new("QueenElizabethTouch"),
+            attacks: [(0, 1.5)].into_iter().collect(),
+        }]
+    );
+}
@@ -0,0 +1,156 @@
+use fleethub_core::{
+    import::{DeckBuilder, PlayerInventory},
+    types::OrgType,
+};
+
+mod common;
+
+use common::*;
+
+#[test]
+fn test_player_inventory_from_api_json() {
+    let ships_json = r#"{
+        "api_result": 1,
+        "api_data": [
+            {
+                "api_id": 10,
+                "api_ship_id": 418,
+                "api_lv": 99,
+                "api_nowhp": 31,
+                "api_cond": 53,
+                "api_fuel": 15,
+                "api_bull": 20,
+                "api_slot": [101, 102, -1, -1, -1],
+                "api_slot_ex": 103,
+                "api_onslot": [0, 0, 0, 0, 0],
+                "api_kyouka": [0, 0, 0, 0, 3, 1, 2]
+            },
+            { "api_id": 11, "api_ship_id": 65535 }
+        ]
+    }"#;
+
+    let slot_items_json = r#"[
+        { "api_id": 101, "api_slotitem_id": 2, "api_level": 10 },
+        { "api_id": 102, "api_slotitem_id": 15, "api_level": 0 },
+        { "api_id": 103, "api_slotitem_id": 65535, "api_level": 0 }
+    ]"#;
+
+    let inventory =
+        PlayerInventory::from_api_json(FH_CORE.master_data(), ships_json, slot_items_json).unwrap();
+
+    assert_eq!(inventory.ships.len(), 1);
+    assert_eq!(inventory.gears.len(), 2);
+    assert_eq!(inventory.unknown_ship_ids, vec![65535]);
+    assert_eq!(inventory.unknown_gear_ids, vec![65535]);
+
+    let ship = &inventory.ships[0];
+    assert_eq!(ship.ship_id, 418);
+    assert_eq!(ship.level, Some(99));
+    assert_eq!(ship.morale, Some(53));
+    assert_eq!(ship.luck_mod, Some(3));
+    assert_eq!(ship.max_hp_mod, Some(1));
+    assert_eq!(ship.asw_mod, Some(2));
+
+    let g1 = ship.gears.g1.as_ref().unwrap();
+    assert_eq!(g1.gear_id, 2);
+    assert_eq!(g1.stars, Some(10));
+    assert_eq!(ship.gears.g2.as_ref().unwrap().gear_id, 15);
+    assert!(ship.gears.g3.is_none());
+    assert!(ship.gears.gx.is_none());
+
+    assert!(PlayerInventory::from_api_json(FH_CORE.master_data(), "{}", "[]").is_err());
+}
+
+#[test]
+fn test_deck_builder_round_trip() {
+    let json = r#"{
+        "version": 4,
+        "hqlv": 120,
+        "f1": {
+            "t": 1,
+            "s1": {
+                "id": 277,
+                "lv": 99,
+                "luck": 20,
+                "items": { "i1": { "id": 21, "rf": 0, "mas": 7 } }
+            },
+            "s2": {
+                "id": 418,
+                "lv": 175,
+                "luck": -1,
+                "hp": 33,
+                "items": { "i1": { "id": 2, "rf": 10 } }
+            }
+        },
+        "a1": {
+            "mode": 2,
+            "items": { "i1": { "id": 168, "mas": 7 } }
+        }
+    }"#;
+
+    let deck = DeckBuilder::from_json(json).unwrap();
+    let org = deck.to_org_state(FH_CORE.factory()).unwrap();
+
+    assert_eq!(org.hq_level, Some(120));
+    assert_eq!(org.org_type, Some(OrgType::CarrierTaskForce));
+
+    let f1 = org.f1.as_ref().unwrap();
+    let s1 = f1.s1.as_ref().unwrap();
+    assert!(s1.luck_mod.is_some());
+    assert_eq!(s1.gears.g1.as_ref().unwrap().exp, Some(120));
+
+    let s2 = f1.s2.as_ref().unwrap();
+    assert_eq!(s2.luck_mod, None);
+    assert!(s2.max_hp_mod.is_some());
+
+    let a1 = org.a1.as_ref().unwrap();
+    assert!(a1.mode.unwrap().is_air_defense());
+
+    let exported = DeckBuilder::from_org_state(FH_CORE.factory(), &org).unwrap();
+    assert_eq!(exported, deck);
+
+    let unknown = DeckBuilder::from_json(r#"{ "f1": { "s1": { "id": 65535 } } }"#).unwrap();
+    assert!(unknown.to_org_state(FH_CORE.factory()).is_err());
+}
+
+#[test]
+fn test_deck_builder_string_numbers() {
+    let deck = DeckBuilder::from_json(
+        r#"{ "f1": { "s1": { "id": "277", "lv": "99", "items": { "i1": { "id": "21" } } } } }"#,
+    )
+    .unwrap();
+    let org = deck.to_org_state(FH_CORE.factory()).unwrap();
+
+    let s1 = org.f1.unwrap().s1.unwrap();
+    assert_eq!(s1.ship_id, 277);
+    assert_eq!(s1.level, Some(99));
+    assert_eq!(s1.gears.g1.unwrap().gear_id, 21);
+}
+
+#[test]
+fn test_deck_builder_exslot_fallback() {
+    let slotnum = FH_CORE.master_data().get_ship(418).unwrap().slotnum;
+    let item = |index: usize| format!(r#""i{}": {{ "id": 2 }}"#, index + 1);
+
+    let items = (0..=slotnum).map(item).collect::<Vec<_>>().join(", ");
+    let json = format!(r#"{{ "f1": {{ "s1": {{ "id": 418, "items": {{ {items} }} }} }} }}"#);
+    let org = DeckBuilder::from_json(&json)
+        .unwrap()
+        .to_org_state(FH_CORE.factory())
+        .unwrap();
+
+    let gears = org.f1.unwrap().s1.unwrap().gears;
+    assert_eq!(gears.iter().flatten().count(), slotnum + 1);
+    assert_eq!(gears.gx.unwrap().gear_id, 2);
+
+    let json = format!(
+        r#"{{ "f1": {{ "s1": {{ "id": 418, "items": {{ {items}, "ix": {{ "id": 15 }} }} }} }} }}"#
+    );
+    let org = DeckBuilder::from_json(&json)
+        .unwrap()
+        .to_org_state(FH_CORE.factory())
+        .unwrap();
+
+    let gears = org.f1.unwrap().s1.unwrap().gears;
+    assert_eq!(gears.gx.unwrap().gear_id, 15);
+}
@@ -0,0 +1,50 @@
+use fleethub_core::master_data::{MasterData, MasterDataDiagnosticKind};
+
+mod common;
+
+#[test]
+fn test_validate_json_with_invalid_expression() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(fleethub_core_test::MASTER_DATA_STR).unwrap();
+    json["ship_attrs"][0]["expr"] = "ship_id ==".into();
+
+    let validation = MasterData::validate_json(&json.to_string());
+
+    assert_eq!(validation.count(MasterDataDiagnosticKind::InvalidData), 0);
+    assert_eq!(
+        validation.count(MasterDataDiagnosticKind::InvalidExpression),
+        1
+    );
+    assert!(validation
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.location == "ship_attrs[0].expr"));
+}
+
+#[test]
+fn test_validate_builtin_battle_data() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(fleethub_core_test::MASTER_DATA_STR).unwrap();
+    json["fleet_cutin"] =
+        serde_json::from_str(include_str!("../src/master_data/fleet_cutins.json")).unwrap();
+    json["special_enemy_modifiers"] = serde_json::from_str(include_str!(
+        "../src/master_data/special_enemy_modifiers.json"
+    ))
+    .unwrap();
+
+    let validation = MasterData::validate_json(&json.to_string());
+
+    let diagnostics = validation
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            diagnostic
+                .location
+                .starts_with("battle_definitions.fleet_cutin")
+                || diagnostic.location.starts_with("special_enemy_modifiers")
+                || diagnostic.location.starts_with("routes")
+        })
+        .collect::<Vec<_>>();
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
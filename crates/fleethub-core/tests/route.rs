@@ -0,0 +1,53 @@
+use fleethub_core::master_data::MasterNodeRoute;
+
+mod common;
+
+use common::*;
+
+#[test]
+fn test_route_evaluation() {
+    let route: MasterNodeRoute = serde_json::from_value(serde_json::json!({
+        "map": 11,
+        "node": "A",
+        "rules": [
+            {
+                "expr": "count_ship_type(7, 11, 18) >= 1 || speed < 10",
+                "routes": [{ "node": "B", "rate": 1.0 }]
+            },
+            {
+                "expr": "drum_ship_count >= 2",
+                "routes": [{ "node": "C", "rate": 1.0 }]
+            },
+            {
+                "routes": [{ "node": "B", "rate": 1.0 }, { "node": "C", "rate": 1.0 }]
+            }
+        ]
+    }))
+    .unwrap();
+
+    let comp = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "皐月改二", g1 = "ドラム缶(輸送用)" }
+        f1.s2 = { ship_id = "文月改二", g1 = "ドラム缶(輸送用)" }
+    }));
+
+    let result = route.evaluate(&comp);
+    assert_eq!(result.rule_index, Some(1));
+    assert_eq!(result.routes.len(), 1);
+    assert_eq!(result.routes[0].node.as_str(), "C");
+
+    let comp = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "皐月改二" }
+        f1.s2 = { ship_id = "文月改二" }
+    }));
+
+    let result = route.evaluate(&comp);
+    assert_eq!(result.rule_index, Some(2));
+    assert!(result.routes.iter().all(|route| route.rate == 0.5));
+
+    let comp = comp_from_toml(toml::Value::from(toml::toml! {
+        f1.s1 = { ship_id = "赤城" }
+    }));
+
+    let result = route.evaluate(&comp);
+    assert_eq!(result.rule_index, Some(0));
+}
@@ -1,7 +1,12 @@
-use fleethub_core::types::Side;
+use fleethub_core::{
+    ship::{OpeningAswRequirement, OpeningAswRule},
+    types::{GearState, GearType, Side},
+};
 
 mod common;
 
+use common::FH_CORE;
+
 #[test]
 fn test_can_do_opening_asw() {
     macro_rules! table {
@@ -32,6 +37,85 @@ fn test_can_do_opening_asw() {
     };
 }
 
+#[test]
+fn test_opening_asw_report() {
+    let report = ship! {
+        ship_id = "五十鈴改二"
+    }
+    .opening_asw_report();
+
+    assert_eq!(report.rule, OpeningAswRule::Unconditional);
+    assert!(report.can_do);
+
+    let report = ship! {
+        ship_id = "夕立改二"
+        level = 99
+    }
+    .opening_asw_report();
+
+    assert_eq!(report.rule, OpeningAswRule::Standard);
+    assert_eq!(report.required_asw, Some(100));
+    assert_eq!(report.missing, vec![OpeningAswRequirement::Sonar]);
+    assert_eq!(report.asw_shortfall, 100 - report.asw);
+    assert!(!report.can_do);
+
+    let report = ship! {
+        ship_id = "夕立改二"
+        level = 1
+        g1 = "三式水中探信儀"
+        g2 = "三式水中探信儀"
+        g3 = "三式爆雷投射機"
+    }
+    .opening_asw_report();
+
+    assert!(report.missing.is_empty());
+    assert!(report.required_level.unwrap() > 1);
+    assert_eq!(report.asw_synergy_mod, 1.15);
+
+    let report = ship! {
+        ship_id = "Samuel B.Roberts"
+    }
+    .opening_asw_report();
+
+    assert_eq!(report.rule, OpeningAswRule::EscortShip);
+    assert_eq!(report.missing, vec![OpeningAswRequirement::Sonar]);
+    assert!(!report.can_do);
+}
+
+#[test]
+fn test_opening_asw_suggestion() {
+    let ship = ship! {
+        ship_id = "夕立改二"
+        level = 175
+        g1 = "三式爆雷投射機"
+    };
+
+    let report = FH_CORE.opening_asw_report(&ship);
+    assert_eq!(report.missing, vec![OpeningAswRequirement::Sonar]);
+
+    let suggestion = report.suggestion.unwrap();
+    let gear = FH_CORE
+        .create_gear(Some(GearState {
+            gear_id: suggestion.gear_id,
+            ..Default::default()
+        }))
+        .unwrap();
+    assert_eq!(gear.gear_type, GearType::Sonar);
+
+    let mut state = ship.state();
+    *state.gears.slot_mut(&suggestion.key).unwrap() = Some(GearState {
+        gear_id: suggestion.gear_id,
+        ..Default::default()
+    });
+    assert!(FH_CORE
+        .create_ship(Some(state))
+        .unwrap()
+        .can_do_opening_asw());
+
+    let report = FH_CORE.opening_asw_report(&ship! { ship_id = "五十鈴改二" });
+    assert_eq!(report.suggestion, None);
+}
+
 #[test]
 fn test_ship_fleet_anti_air_mod() {
     let ship = ship! {